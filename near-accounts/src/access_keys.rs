@@ -1,5 +1,16 @@
+//! The `access_keys` module constructs access keys and watches the access keys of an account for changes.
+
+use futures::stream::{self, Stream};
+use near_crypto::PublicKey;
 use near_primitives::account::{AccessKey, AccessKeyPermission, FunctionCallPermission};
-use near_primitives::types::Balance;
+use near_primitives::types::{AccountId, Balance};
+use near_primitives::views::{AccessKeyInfoView, QueryRequest};
+use near_providers::types::query::QueryResponseKind;
+use near_providers::Provider;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{Interval, MissedTickBehavior};
 
 pub fn full_access_key() -> AccessKey {
     AccessKey::full_access()
@@ -19,3 +30,188 @@ pub fn function_call_access_key(
         }),
     }
 }
+
+/// A change to the access keys of a watched account, see `AccessKeyMonitor`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessKeyChange {
+    /// A key was added, with its permission so it can be inspected.
+    Added(AccessKeyInfoView),
+    /// A key was deleted.
+    Removed(PublicKey),
+}
+
+/// Watches the access keys of an account, e.g. to alert when a key appears on a critical account.
+pub struct AccessKeyMonitor;
+
+impl AccessKeyMonitor {
+    /// Returns a stream of the changes to the access keys of `account_id`.
+    ///
+    /// The key list is fetched every `poll_interval` and compared to the previous one, the first fetch
+    /// only recording the keys present when watching starts. A key whose permission changed is reported
+    /// as removed and added again; nonce changes are not reported. Failed fetches are skipped, so keys
+    /// added and deleted again between two successful fetches go unnoticed.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The account whose access keys are watched.
+    /// * `provider` - The provider through which to fetch the access keys.
+    /// * `poll_interval` - How long to wait between two fetches.
+    pub fn watch(
+        account_id: AccountId,
+        provider: Arc<dyn Provider>,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = AccessKeyChange> {
+        let mut interval = tokio::time::interval(poll_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let state = WatchState {
+            account_id,
+            provider,
+            interval,
+            snapshot: None,
+            pending: VecDeque::new(),
+        };
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(change) = state.pending.pop_front() {
+                    return Some((change, state));
+                }
+                state.interval.tick().await;
+                let Some(keys) = fetch_access_keys(&*state.provider, &state.account_id).await
+                else {
+                    continue;
+                };
+                if let Some(previous) = &state.snapshot {
+                    state.pending.extend(access_key_changes(previous, &keys));
+                }
+                state.snapshot = Some(keys);
+            }
+        })
+    }
+}
+
+struct WatchState {
+    account_id: AccountId,
+    provider: Arc<dyn Provider>,
+    interval: Interval,
+    snapshot: Option<Vec<AccessKeyInfoView>>,
+    pending: VecDeque<AccessKeyChange>,
+}
+
+async fn fetch_access_keys(
+    provider: &dyn Provider,
+    account_id: &AccountId,
+) -> Option<Vec<AccessKeyInfoView>> {
+    let response = provider
+        .query(QueryRequest::ViewAccessKeyList {
+            account_id: account_id.clone(),
+        })
+        .await
+        .ok()?;
+    match response.kind {
+        QueryResponseKind::AccessKeyList(list) => Some(list.keys),
+        _ => None,
+    }
+}
+
+/// Lists the keys of `before` missing from `after` as removed, then the keys of `after` missing from
+/// `before` as added. Keys are compared by public key and permission.
+fn access_key_changes(
+    before: &[AccessKeyInfoView],
+    after: &[AccessKeyInfoView],
+) -> Vec<AccessKeyChange> {
+    let same_key = |a: &AccessKeyInfoView, b: &AccessKeyInfoView| {
+        a.public_key == b.public_key && a.access_key.permission == b.access_key.permission
+    };
+    let removed = before
+        .iter()
+        .filter(|key| !after.iter().any(|other| same_key(key, other)))
+        .map(|key| AccessKeyChange::Removed(key.public_key.clone()));
+    let added = after
+        .iter()
+        .filter(|key| !before.iter().any(|other| same_key(key, other)))
+        .map(|key| AccessKeyChange::Added(key.clone()));
+    removed.chain(added).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use near_crypto::KeyType;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::views::{AccessKeyList, AccessKeyPermissionView, AccessKeyView};
+    use near_providers::jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+    use near_providers::test_utils::MockProvider;
+    use near_providers::types::query::{RpcQueryError, RpcQueryResponse};
+    use std::sync::Mutex;
+
+    /// Returns a provider answering each access key list request with the next of `snapshots`, failing
+    /// for `None`, and repeating the last snapshot once all are used up.
+    fn mock_provider(snapshots: Vec<Option<Vec<AccessKeyInfoView>>>) -> MockProvider {
+        let snapshots = Mutex::new(VecDeque::from(snapshots));
+        let last = Mutex::new(Vec::new());
+        MockProvider::default().on_query(move |request| {
+            let QueryRequest::ViewAccessKeyList { .. } = request else {
+                panic!("unexpected request: {:?}", request)
+            };
+            let mut last = last.lock().unwrap();
+            let result = match snapshots.lock().unwrap().pop_front() {
+                Some(None) => Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                    RpcQueryError::NoSyncedBlocks,
+                ))),
+                snapshot => {
+                    if let Some(Some(keys)) = snapshot {
+                        *last = keys;
+                    }
+                    Ok(RpcQueryResponse {
+                        kind: QueryResponseKind::AccessKeyList(AccessKeyList {
+                            keys: last.clone(),
+                        }),
+                        block_height: 42,
+                        block_hash: CryptoHash::default(),
+                    })
+                }
+            };
+            async move { result }
+        })
+    }
+
+    fn key(seed: &str, nonce: u64) -> AccessKeyInfoView {
+        AccessKeyInfoView {
+            public_key: PublicKey::from_seed(KeyType::ED25519, seed),
+            access_key: AccessKeyView {
+                nonce,
+                permission: AccessKeyPermissionView::FullAccess,
+            },
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watch_reports_changes() {
+        let provider = Arc::new(mock_provider(vec![
+            None,
+            Some(vec![key("a", 1), key("b", 1)]),
+            Some(vec![key("a", 2), key("b", 1)]),
+            None,
+            Some(vec![key("a", 2), key("c", 0)]),
+            Some(vec![key("a", 2), key("c", 0), key("d", 0)]),
+        ]));
+
+        let changes: Vec<_> = AccessKeyMonitor::watch(
+            "alice.near".parse().unwrap(),
+            provider,
+            Duration::from_secs(1),
+        )
+        .take(3)
+        .collect()
+        .await;
+        assert_eq!(
+            changes,
+            vec![
+                AccessKeyChange::Removed(key("b", 1).public_key),
+                AccessKeyChange::Added(key("c", 0)),
+                AccessKeyChange::Added(key("d", 0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! The `account_manager` module coordinates the lifecycle of accounts funded by a single signer account:
+//! creating them with their keys, topping up their balance and deleting them again.
+
+use crate::access_keys::full_access_key;
+use crate::accounts::{state, Account};
+use near_crypto::{PublicKey, Signer};
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::AccountId;
+use near_primitives::views::FinalExecutionOutcomeView;
+use near_providers::Provider;
+use near_transactions::NearToken;
+use std::sync::Arc;
+
+/// The result of `AccountManager::create_named_account`.
+#[derive(Debug, Clone)]
+pub struct AccountCreationReceipt {
+    /// The created account.
+    pub account_id: AccountId,
+    /// The hash of the creating transaction.
+    pub transaction_hash: CryptoHash,
+    /// The final execution outcome of the creating transaction.
+    pub outcome: FinalExecutionOutcomeView,
+}
+
+/// Creates, funds and deletes accounts on behalf of a signer account.
+pub struct AccountManager {
+    signer: Arc<dyn Signer>,
+    provider: Arc<dyn Provider>,
+    account: Account,
+}
+
+impl AccountManager {
+    /// Constructs a new `AccountManager`.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - A provider instance for interacting with the blockchain.
+    /// * `signer_account` - The account funding new accounts.
+    /// * `signer` - A signer instance for signing transactions.
+    pub fn new(
+        provider: Arc<dyn Provider>,
+        signer_account: AccountId,
+        signer: Arc<dyn Signer>,
+    ) -> Self {
+        Self {
+            account: Account::new(signer_account, signer.clone(), provider.clone()),
+            signer,
+            provider,
+        }
+    }
+
+    /// Creates the sub account `new_id`, funds it with `initial_balance` and adds each of `keys` as a full
+    /// access key, in a single transaction.
+    ///
+    /// # Returns
+    ///
+    /// A receipt of the creating transaction, or an error if it could not be sent.
+    pub async fn create_named_account(
+        &self,
+        new_id: AccountId,
+        initial_balance: NearToken,
+        keys: Vec<PublicKey>,
+    ) -> Result<AccountCreationReceipt, Box<dyn std::error::Error>> {
+        let mut builder = self.account.get_transaction_builder(&new_id).await?;
+        builder
+            .create_account()
+            .transfer(initial_balance.as_yoctonear());
+        for public_key in keys {
+            builder.add_key(public_key, full_access_key());
+        }
+        let signed_tx = builder.sign_transaction(&*self.signer);
+        let transaction_hash = signed_tx.get_hash();
+
+        let outcome = self.provider.send_transaction(signed_tx).await?;
+        Ok(AccountCreationReceipt {
+            account_id: new_id,
+            transaction_hash,
+            outcome,
+        })
+    }
+
+    /// Transfers `amount` from the signer account to `account_id`.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the transfer transaction, or an error if it could not be sent.
+    pub async fn top_up_balance(
+        &self,
+        account_id: AccountId,
+        amount: NearToken,
+    ) -> Result<CryptoHash, Box<dyn std::error::Error>> {
+        let signed_tx = self
+            .account
+            .get_transaction_builder(&account_id)
+            .await?
+            .transfer(amount.as_yoctonear())
+            .sign_transaction(&*self.signer);
+        let transaction_hash = signed_tx.get_hash();
+
+        self.provider.send_transaction(signed_tx).await?;
+        Ok(transaction_hash)
+    }
+
+    /// Deletes `account_id`, sending its remaining balance to `beneficiary`.
+    ///
+    /// The signer's key must be a full access key of `account_id`, e.g. because it was passed to
+    /// `create_named_account`.
+    ///
+    /// # Returns
+    ///
+    /// The balance of the account before deletion minus the tokens burnt by the deleting transaction,
+    /// which is approximately the amount the beneficiary receives.
+    pub async fn delete_account_and_recover_balance(
+        &self,
+        account_id: AccountId,
+        beneficiary: AccountId,
+    ) -> Result<NearToken, Box<dyn std::error::Error>> {
+        let balance = state(self.provider.clone(), account_id.clone())
+            .await?
+            .amount;
+
+        let account = Account::new(
+            account_id.clone(),
+            self.signer.clone(),
+            self.provider.clone(),
+        );
+        let signed_tx = account
+            .get_transaction_builder(&account_id)
+            .await?
+            .delete_account(beneficiary)
+            .sign_transaction(&*self.signer);
+
+        let outcome = self.provider.send_transaction(signed_tx).await?;
+        let tokens_burnt: u128 = std::iter::once(&outcome.transaction_outcome)
+            .chain(&outcome.receipts_outcome)
+            .map(|outcome| outcome.outcome.tokens_burnt)
+            .sum();
+        Ok(NearToken::from_yoctonear(
+            balance.saturating_sub(tokens_burnt),
+        ))
+    }
+}
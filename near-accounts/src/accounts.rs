@@ -4,13 +4,19 @@
 //! making it easier to perform account-related operations.
 
 use crate::access_keys::{full_access_key, function_call_access_key};
-use near_crypto::{PublicKey, Signer};
+use crate::function_call_error::{parse_function_call_error, FunctionCallError};
+use near_crypto::{InMemorySigner, PublicKey, Signer};
 use near_primitives::account::AccessKey;
+use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, Balance, BlockReference, Finality, Gas};
-use near_primitives::views::{FinalExecutionOutcomeView, QueryRequest};
-use near_providers::types::query::{QueryResponseKind, RpcQueryResponse};
+use near_primitives::views::{
+    AccessKeyInfoView, CallResult, FinalExecutionOutcomeView, FinalExecutionStatus, QueryRequest,
+};
+use near_providers::jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use near_providers::types::query::{QueryResponseKind, RpcQueryError, RpcQueryResponse};
 use near_providers::Provider;
 use near_transactions::TransactionBuilder;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::ops::{Add, Mul, Sub};
@@ -23,6 +29,33 @@ pub struct Account {
     pub provider: Arc<dyn Provider>, // Use your Provider abstraction
 }
 
+/// The result of `Account::call_and_view`.
+#[derive(Debug, Clone)]
+pub struct CallAndViewResult<T> {
+    /// The decoded return value of the function call.
+    pub value: T,
+    /// The result of the follow-up view call, if one was requested.
+    pub view: Option<CallResult>,
+}
+
+/// The result of `ensure_account`.
+#[derive(Debug)]
+pub enum EnsureAccountOutcome {
+    /// The account did not exist and was created by the given transaction.
+    Created(FinalExecutionOutcomeView),
+    /// The account already existed, so no transaction was sent.
+    AlreadyExisted,
+}
+
+/// Gas attached to the init call of `init_if_needed`.
+pub const INIT_GAS: Gas = 100_000_000_000_000;
+
+/// Storage key under which `near-sdk` contracts keep their state once initialized.
+const SDK_STATE_KEY: &[u8] = b"STATE";
+
+/// Panic message of `near-sdk` when an init method is called on an initialized contract.
+const ALREADY_INITIALIZED_MESSAGE: &str = "The contract has already been initialized";
+
 /// Represents the balance details of a NEAR account.
 #[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
 pub struct AccountBalance {
@@ -65,7 +98,7 @@ impl Account {
     /// # Returns
     ///
     /// A result containing a `TransactionBuilder` instance or an error if fetching the nonce or block hash failed.
-    async fn get_transaction_builder(
+    pub(crate) async fn get_transaction_builder(
         &self,
         receiver_id: &AccountId,
     ) -> Result<TransactionBuilder, Box<dyn std::error::Error>> {
@@ -227,6 +260,37 @@ impl Account {
         }
     }
 
+    /// Builds and signs a transaction deleting every access key of the account that is neither in `keep`
+    /// nor the key of this account's signer, e.g. to revoke unexpected keys found by an audit.
+    ///
+    /// The transaction is not sent, so it can be reviewed first.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - The access keys to keep.
+    ///
+    /// # Returns
+    ///
+    /// The signed transaction, `None` if there is no key to delete, or an error if the keys could not be
+    /// listed or more keys would be deleted than fit in a single transaction.
+    pub async fn prune_access_keys(
+        &self,
+        keep: &[PublicKey],
+    ) -> Result<Option<SignedTransaction>, Box<dyn std::error::Error>> {
+        let keys = list_access_keys(self.provider.clone(), self.account_id.clone()).await?;
+        let to_delete = keys_to_prune(&keys, keep, &self.signer.public_key());
+        if to_delete.is_empty() {
+            return Ok(None);
+        }
+
+        let signed_tx = self
+            .get_transaction_builder(&self.account_id)
+            .await?
+            .delete_keys(to_delete)?
+            .sign_transaction(&*self.signer);
+        Ok(Some(signed_tx))
+    }
+
     /// Deploys a contract to the account associated with this `Account` instance.
     ///
     /// # Arguments
@@ -342,7 +406,7 @@ impl Account {
         let signed_tx = self
             .get_transaction_builder(contract_id)
             .await?
-            .function_call(method_name, args, gas, deposit)
+            .function_call(method_name, args, gas, deposit)?
             .sign_transaction(&*self.signer); // Sign the transaction
 
         // Send the transaction
@@ -353,6 +417,51 @@ impl Account {
         }
     }
 
+    /// Calls a function on a contract, decodes its JSON return value and optionally reads derived state
+    /// with a follow-up view call.
+    ///
+    /// The two calls are not atomic: other transactions can change the contract's state between the
+    /// function call and the view call, so the view may not reflect exactly the state the call left behind.
+    /// The view is also only run once the function call succeeded, and may be served by a node that has
+    /// not yet applied the call's receipts.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_id` - The account ID of the contract.
+    /// * `method_name` - The name of the function to call.
+    /// * `args` - The arguments to the function call, serialized into a JSON `Value`.
+    /// * `gas` - The amount of gas to attach to the call.
+    /// * `deposit` - The amount of NEAR tokens to transfer to the contract, in yoctoNEAR.
+    /// * `view` - The method name and JSON arguments of an optional view call on the same contract.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the decoded return value and the view result, or an error if either call
+    /// fails or the return value cannot be decoded into `T`.
+    pub async fn call_and_view<T: DeserializeOwned>(
+        &self,
+        contract_id: &AccountId,
+        method_name: String,
+        args: Value,
+        gas: Gas,
+        deposit: Balance,
+        view: Option<(String, Value)>,
+    ) -> Result<CallAndViewResult<T>, Box<dyn std::error::Error>> {
+        let outcome = self
+            .function_call(contract_id, method_name, args, gas, deposit)
+            .await?;
+        let value = decode_success_value(&outcome.status)?;
+
+        let view = match view {
+            Some((view_method_name, view_args)) => Some(
+                self.view_function(contract_id.clone(), view_method_name, view_args)
+                    .await?,
+            ),
+            None => None,
+        };
+        Ok(CallAndViewResult { value, view })
+    }
+
     /// Calls a view function on a contract deployed on the NEAR blockchain.
     ///
     /// View functions are read-only and do not modify state. They're free to call.
@@ -426,6 +535,155 @@ pub async fn view_state(
     }
 }
 
+/// Creates an account unless it already exists, e.g. in provisioning scripts that may run repeatedly.
+///
+/// The existence check and the creation are separate requests, so an account created in between still
+/// makes the creation transaction fail.
+///
+/// # Arguments
+///
+/// * `provider` - The provider through which to query the blockchain.
+/// * `new_account_id` - The account to create, a sub account of the funder.
+/// * `public_key` - The full access key of the new account.
+/// * `initial_balance` - The balance transferred from the funder to the new account.
+/// * `funder_signer` - The signer of the account paying for the creation.
+///
+/// # Returns
+///
+/// A `Result` telling whether the account was created or already existed, or an error if a request fails.
+pub async fn ensure_account(
+    provider: Arc<dyn Provider>,
+    new_account_id: &AccountId,
+    public_key: PublicKey,
+    initial_balance: Balance,
+    funder_signer: Arc<InMemorySigner>,
+) -> Result<EnsureAccountOutcome, Box<dyn std::error::Error>> {
+    let query_request = QueryRequest::ViewAccount {
+        account_id: new_account_id.clone(),
+    };
+    match provider.query(query_request).await {
+        Ok(_) => return Ok(EnsureAccountOutcome::AlreadyExisted),
+        Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+            RpcQueryError::UnknownAccount { .. },
+        ))) => {}
+        Err(err) => return Err(Box::new(err)),
+    }
+
+    let funder = Account::new(funder_signer.account_id.clone(), funder_signer, provider);
+    let outcome = funder
+        .create_account(new_account_id, public_key, initial_balance)
+        .await?;
+    Ok(EnsureAccountOutcome::Created(outcome))
+}
+
+/// Calls the init method of a `near-sdk` contract unless the contract is already initialized, e.g. after
+/// deploying it from a factory.
+///
+/// A contract counts as initialized if it stores the `STATE` key `near-sdk` writes on initialization,
+/// which is checked with a free view query. If it is initialized concurrently, the init call fails with
+/// `near-sdk`'s already-initialized panic, which is treated the same way.
+///
+/// # Arguments
+///
+/// * `provider` - The provider through which to query the blockchain.
+/// * `contract_id` - The account ID of the contract.
+/// * `init_method` - The name of the init method, usually `new`.
+/// * `args` - The arguments to the init method, serialized into a JSON `Value`.
+/// * `signer` - The signer calling the init method.
+///
+/// # Returns
+///
+/// A `Result` containing true if the contract was initialized by this call, or an error if a request or
+/// the init call fails.
+pub async fn init_if_needed(
+    provider: Arc<dyn Provider>,
+    contract_id: &AccountId,
+    init_method: &str,
+    args: Value,
+    signer: Arc<InMemorySigner>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let prefix = String::from_utf8(SDK_STATE_KEY.to_vec())?;
+    let state = view_state(provider.clone(), contract_id.clone(), Some(prefix)).await?;
+    if state
+        .values
+        .iter()
+        .any(|item| item.key.as_slice() == SDK_STATE_KEY)
+    {
+        return Ok(false);
+    }
+
+    let account = Account::new(signer.account_id.clone(), signer, provider);
+    let outcome = account
+        .function_call(contract_id, init_method.to_string(), args, INIT_GAS, 0)
+        .await?;
+    if let FinalExecutionStatus::Failure(err) = &outcome.status {
+        return match outcome
+            .receipts_outcome
+            .iter()
+            .find_map(parse_function_call_error)
+        {
+            Some(FunctionCallError::Panic { message })
+                if message == ALREADY_INITIALIZED_MESSAGE =>
+            {
+                Ok(false)
+            }
+            Some(error) => Err(Box::new(error)),
+            None => Err(format!("Transaction failed: {}", err).into()),
+        };
+    }
+    Ok(true)
+}
+
+/// Decodes the JSON return value of a successful transaction.
+///
+/// An empty return value, as produced by functions without a return value, is decoded as `null`.
+///
+/// # Arguments
+///
+/// * `status` - The final execution status of the transaction.
+///
+/// # Returns
+///
+/// A `Result` containing the decoded value, or an error if the transaction did not succeed or the value
+/// is not valid JSON for `T`.
+pub fn decode_success_value<T: DeserializeOwned>(
+    status: &FinalExecutionStatus,
+) -> Result<T, Box<dyn std::error::Error>> {
+    match status {
+        FinalExecutionStatus::SuccessValue(value) if value.is_empty() => {
+            Ok(serde_json::from_slice(b"null")?)
+        }
+        FinalExecutionStatus::SuccessValue(value) => Ok(serde_json::from_slice(value)?),
+        FinalExecutionStatus::Failure(err) => Err(format!("Transaction failed: {}", err).into()),
+        FinalExecutionStatus::NotStarted | FinalExecutionStatus::Started => {
+            Err("Transaction has not finished".into())
+        }
+    }
+}
+
+/// Replaces the block hash of `builder` with the hash of the block at `block_reference`, usually the
+/// latest block at some finality.
+///
+/// A transaction is only valid for a limited number of blocks after the block it references. A block
+/// fetched with `Finality::None` (optimistic) is newer, so the transaction stays valid slightly longer,
+/// but the block may still be dropped from the canonical chain, which invalidates the transaction.
+/// `Finality::Final` is the safe default.
+///
+/// # Arguments
+///
+/// * `builder` - The builder of the transaction.
+/// * `provider` - The provider through which to fetch the block.
+/// * `block_reference` - The block whose hash the transaction references.
+pub async fn set_recent_block_hash(
+    builder: &mut TransactionBuilder,
+    provider: &dyn Provider,
+    block_reference: BlockReference,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let block = provider.block(block_reference).await?;
+    builder.set_block_hash(block.header.hash);
+    Ok(())
+}
+
 /// Retrieves the list of access keys for a given account.
 ///
 /// # Arguments
@@ -452,6 +710,39 @@ pub async fn get_access_key(
     }
 }
 
+/// Retrieves the access keys of an account, with their nonces and permissions.
+///
+/// # Arguments
+///
+/// * `provider` - The provider through which to query the blockchain.
+/// * `account_id` - The account ID for which to retrieve access keys.
+///
+/// # Returns
+///
+/// A `Result` containing the access keys of the specified account, or an error if the operation fails.
+pub async fn list_access_keys(
+    provider: Arc<dyn Provider>,
+    account_id: AccountId,
+) -> Result<Vec<AccessKeyInfoView>, Box<dyn std::error::Error>> {
+    Ok(get_access_key(provider, account_id).await?.keys)
+}
+
+/// Selects the keys of `keys` that are neither in `keep` nor `signing_key`, in the order they are listed.
+///
+/// The signing key is never selected, since deleting it would leave the transaction deleting the other
+/// keys without a valid signature.
+pub fn keys_to_prune(
+    keys: &[AccessKeyInfoView],
+    keep: &[PublicKey],
+    signing_key: &PublicKey,
+) -> Vec<PublicKey> {
+    keys.iter()
+        .map(|key| &key.public_key)
+        .filter(|public_key| *public_key != signing_key && !keep.contains(*public_key))
+        .cloned()
+        .collect()
+}
+
 /// Retrieves the state of an account on the NEAR blockchain.
 ///
 /// # Arguments
@@ -522,3 +813,257 @@ pub async fn get_account_balance(
         available: available_balance.to_string(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::errors::{InvalidTxError, TxExecutionError};
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::transaction::Action;
+    use near_primitives::types::{StoreKey, StoreValue};
+    use near_primitives::views::{
+        AccessKeyPermissionView, AccessKeyView, AccountView, StateItem, ViewStateResult,
+    };
+    use near_providers::test_utils::{block, block_hash, outcome, MockProvider};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Returns a provider answering only the requests `ensure_account` and `init_if_needed` make, and
+    /// the transactions sent through it.
+    fn mock_provider(
+        account_exists: bool,
+        contract_initialized: bool,
+    ) -> (Arc<MockProvider>, Arc<Mutex<Vec<SignedTransaction>>>) {
+        let sent_transactions = Arc::new(Mutex::new(Vec::new()));
+        let sent = sent_transactions.clone();
+        let provider = MockProvider::default()
+            .on_send_transaction(move |signed_transaction| {
+                let nonce = signed_transaction.transaction.nonce();
+                sent.lock().unwrap().push(signed_transaction);
+                async move { Ok(outcome(nonce)) }
+            })
+            .on_block(|_| async { Ok(block(42)) })
+            .on_query(move |request| async move {
+                let kind = match request {
+                    QueryRequest::ViewAccount { account_id } if !account_exists => {
+                        return Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                            RpcQueryError::UnknownAccount {
+                                requested_account_id: account_id,
+                                block_height: 42,
+                                block_hash: CryptoHash::default(),
+                            },
+                        )));
+                    }
+                    QueryRequest::ViewAccount { .. } => {
+                        QueryResponseKind::ViewAccount(AccountView {
+                            amount: 1,
+                            locked: 0,
+                            code_hash: CryptoHash::default(),
+                            storage_usage: 0,
+                            storage_paid_at: 0,
+                        })
+                    }
+                    QueryRequest::ViewAccessKey { .. } => {
+                        QueryResponseKind::AccessKey(AccessKeyView {
+                            nonce: 1,
+                            permission: AccessKeyPermissionView::FullAccess,
+                        })
+                    }
+                    QueryRequest::ViewState { .. } => {
+                        let values = if contract_initialized {
+                            vec![StateItem {
+                                key: StoreKey::from(b"STATE".to_vec()),
+                                value: StoreValue::from(vec![0]),
+                            }]
+                        } else {
+                            Vec::new()
+                        };
+                        QueryResponseKind::ViewState(ViewStateResult {
+                            values,
+                            proof: Vec::new(),
+                        })
+                    }
+                    request => panic!("unexpected request: {:?}", request),
+                };
+                Ok(RpcQueryResponse {
+                    kind,
+                    block_height: 42,
+                    block_hash: CryptoHash::default(),
+                })
+            });
+        (Arc::new(provider), sent_transactions)
+    }
+
+    fn funder_signer() -> Arc<InMemorySigner> {
+        Arc::new(InMemorySigner::from_seed(
+            "funder.near".parse().unwrap(),
+            KeyType::ED25519,
+            "funder.near",
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_set_recent_block_hash() {
+        let provider = MockProvider::default().on_block(|block_reference| async move {
+            match block_reference {
+                BlockReference::Finality(Finality::Final) => Ok(block(42)),
+                _ => Ok(block(43)),
+            }
+        });
+        let mut builder = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            1,
+            CryptoHash::default(),
+        );
+
+        set_recent_block_hash(
+            &mut builder,
+            &provider,
+            BlockReference::Finality(Finality::Final),
+        )
+        .await
+        .unwrap();
+        assert_eq!(builder.build().block_hash(), &block_hash(42));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_account_already_existed() {
+        let (provider, sent_transactions) = mock_provider(true, false);
+        let outcome = ensure_account(
+            provider,
+            &"new.funder.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            1,
+            funder_signer(),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(outcome, EnsureAccountOutcome::AlreadyExisted));
+        assert!(sent_transactions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_account_created() {
+        let (provider, sent_transactions) = mock_provider(false, false);
+        let new_account_id: AccountId = "new.funder.near".parse().unwrap();
+        let outcome = ensure_account(
+            provider,
+            &new_account_id,
+            PublicKey::empty(KeyType::ED25519),
+            1,
+            funder_signer(),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(outcome, EnsureAccountOutcome::Created(_)));
+        let sent_transactions = sent_transactions.lock().unwrap();
+        assert_eq!(sent_transactions.len(), 1);
+        let transaction = &sent_transactions[0].transaction;
+        assert_eq!(transaction.receiver_id(), &new_account_id);
+        assert_eq!(transaction.nonce(), 2);
+        assert!(matches!(
+            transaction.actions(),
+            [
+                Action::CreateAccount(_),
+                Action::Transfer(_),
+                Action::AddKey(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_decode_success_value() {
+        let status = FinalExecutionStatus::SuccessValue(br#"{"count":3}"#.to_vec());
+        let value: Value = decode_success_value(&status).unwrap();
+        assert_eq!(value, serde_json::json!({ "count": 3 }));
+
+        let status = FinalExecutionStatus::SuccessValue(b"42".to_vec());
+        assert_eq!(decode_success_value::<u64>(&status).unwrap(), 42);
+
+        let status = FinalExecutionStatus::SuccessValue(Vec::new());
+        assert_eq!(decode_success_value::<Option<u64>>(&status).unwrap(), None);
+        assert!(decode_success_value::<u64>(&status).is_err());
+    }
+
+    #[test]
+    fn test_keys_to_prune() {
+        let key = |seed: &str| {
+            InMemorySigner::from_seed("a.near".parse().unwrap(), KeyType::ED25519, seed)
+                .public_key()
+        };
+        let keys: Vec<AccessKeyInfoView> = ["signer", "kept", "unexpected", "other"]
+            .iter()
+            .map(|seed| AccessKeyInfoView {
+                public_key: key(seed),
+                access_key: AccessKeyView {
+                    nonce: 0,
+                    permission: AccessKeyPermissionView::FullAccess,
+                },
+            })
+            .collect();
+
+        let to_delete = keys_to_prune(&keys, &[key("kept")], &key("signer"));
+        assert_eq!(to_delete, vec![key("unexpected"), key("other")]);
+
+        let to_delete = keys_to_prune(&keys, &[], &key("signer"));
+        assert!(!to_delete.contains(&key("signer")));
+        assert_eq!(to_delete.len(), 3);
+    }
+
+    #[test]
+    fn test_decode_failed_outcome() {
+        let status = FinalExecutionStatus::Failure(TxExecutionError::InvalidTxError(
+            InvalidTxError::InvalidSignature,
+        ));
+        assert!(decode_success_value::<Value>(&status).is_err());
+        assert!(decode_success_value::<Value>(&FinalExecutionStatus::Started).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_init_if_needed_fresh_contract() {
+        let (provider, sent_transactions) = mock_provider(true, false);
+        let contract_id: AccountId = "new.funder.near".parse().unwrap();
+        let initialized = init_if_needed(
+            provider,
+            &contract_id,
+            "new",
+            json!({ "owner_id": "funder.near" }),
+            funder_signer(),
+        )
+        .await
+        .unwrap();
+
+        assert!(initialized);
+        let sent_transactions = sent_transactions.lock().unwrap();
+        assert_eq!(sent_transactions.len(), 1);
+        let transaction = &sent_transactions[0].transaction;
+        assert_eq!(transaction.receiver_id(), &contract_id);
+        let [Action::FunctionCall(call)] = transaction.actions() else {
+            panic!("Expected a single function call");
+        };
+        assert_eq!(call.method_name, "new");
+        assert_eq!(call.gas, INIT_GAS);
+    }
+
+    #[tokio::test]
+    async fn test_init_if_needed_already_initialized() {
+        let (provider, sent_transactions) = mock_provider(true, true);
+        let initialized = init_if_needed(
+            provider,
+            &"new.funder.near".parse().unwrap(),
+            "new",
+            json!({}),
+            funder_signer(),
+        )
+        .await
+        .unwrap();
+
+        assert!(!initialized);
+        assert!(sent_transactions.lock().unwrap().is_empty());
+    }
+}
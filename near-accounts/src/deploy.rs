@@ -0,0 +1,96 @@
+//! The `deploy` module checks that the contract deployed to an account is the expected build, e.g. in a
+//! CI/CD pipeline after deploying.
+
+use crate::accounts::state;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::AccountId;
+use near_providers::Provider;
+use std::fmt;
+use std::sync::Arc;
+
+/// The reason the deployed contract could not be verified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployVerifyError {
+    /// The account has no contract deployed.
+    NoContractDeployed(AccountId),
+    /// The account could not be fetched.
+    Request(String),
+}
+
+impl fmt::Display for DeployVerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeployVerifyError::NoContractDeployed(account_id) => {
+                write!(f, "No contract is deployed to {}", account_id)
+            }
+            DeployVerifyError::Request(message) => write!(f, "Request failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DeployVerifyError {}
+
+/// Returns the `sha256` hash of `wasm`, the hash the protocol stores as an account's code hash.
+pub fn wasm_hash(wasm: &[u8]) -> [u8; 32] {
+    CryptoHash::hash_bytes(wasm).0
+}
+
+/// Compares the contract deployed to an account with a local WASM file.
+pub struct DeployChecksumVerifier {
+    provider: Arc<dyn Provider>,
+}
+
+impl DeployChecksumVerifier {
+    /// Constructs a new `DeployChecksumVerifier`.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - A provider instance for interacting with the blockchain.
+    pub fn new(provider: Arc<dyn Provider>) -> Self {
+        Self { provider }
+    }
+
+    /// Fetches the `sha256` hash of the contract deployed to `account_id`.
+    ///
+    /// The hash is read from the account's `code_hash`, so the code itself is not downloaded.
+    pub async fn fetch_deployed_hash(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<[u8; 32], DeployVerifyError> {
+        let account = state(self.provider.clone(), account_id.clone())
+            .await
+            .map_err(|err| DeployVerifyError::Request(err.to_string()))?;
+        if account.code_hash == CryptoHash::default() {
+            return Err(DeployVerifyError::NoContractDeployed(account_id.clone()));
+        }
+        Ok(account.code_hash.0)
+    }
+
+    /// Checks whether the contract deployed to `account_id` is `local_wasm`.
+    ///
+    /// # Returns
+    ///
+    /// Whether the hashes match, or an error if the account has no contract or could not be fetched.
+    pub async fn verify(
+        &self,
+        account_id: &AccountId,
+        local_wasm: &[u8],
+    ) -> Result<bool, DeployVerifyError> {
+        Ok(self.fetch_deployed_hash(account_id).await? == wasm_hash(local_wasm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_hash() {
+        // sha256 of the empty input
+        assert_eq!(
+            CryptoHash(wasm_hash(b"")).to_string(),
+            "GKot5hBsd81kMupNCXHaqbhv3huEbxAFMLnpcX2hniwn"
+        );
+        assert_ne!(wasm_hash(b"\0asm"), wasm_hash(b"\0asm\x01"));
+    }
+}
@@ -0,0 +1,99 @@
+//! Parsing of NEP-297 events, which contracts emit as `EVENT_JSON:{...}` log lines, e.g. to find the
+//! tokens minted by a transaction.
+
+use near_primitives::views::FinalExecutionOutcomeView;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The prefix of log lines holding a NEP-297 event.
+const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// An event emitted by a contract, as defined by NEP-297.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Nep297Event {
+    /// The standard the event belongs to, e.g. `nep171`.
+    pub standard: String,
+    /// The version of the standard, e.g. `1.0.0`.
+    pub version: String,
+    /// The type of the event, e.g. `nft_mint`.
+    pub event: String,
+    /// The data of the event, whose structure is defined by the standard.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// Extracts the NEP-297 events from the logs of all receipts of `outcome`, in order.
+///
+/// Logs without the `EVENT_JSON:` prefix are ignored, as are events whose JSON is malformed.
+pub fn parse_events(outcome: &FinalExecutionOutcomeView) -> Vec<Nep297Event> {
+    outcome
+        .receipts_outcome
+        .iter()
+        .flat_map(|receipt| &receipt.outcome.logs)
+        .filter_map(|log| parse_event(log))
+        .collect()
+}
+
+fn parse_event(log: &str) -> Option<Nep297Event> {
+    let json = log.strip_prefix(EVENT_JSON_PREFIX)?;
+    serde_json::from_str(json.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn outcome(logs: Vec<&str>) -> FinalExecutionOutcomeView {
+        let execution_outcome = |logs: Vec<&str>| {
+            json!({
+                "proof": [],
+                "block_hash": "11111111111111111111111111111111",
+                "id": "11111111111111111111111111111111",
+                "outcome": {
+                    "logs": logs,
+                    "receipt_ids": [],
+                    "gas_burnt": 0,
+                    "tokens_burnt": "0",
+                    "executor_id": "nft.near",
+                    "status": { "SuccessValue": "" },
+                    "metadata": { "version": 1, "gas_profile": null }
+                }
+            })
+        };
+        serde_json::from_value(json!({
+            "status": { "SuccessValue": "" },
+            "transaction": {
+                "signer_id": "alice.near",
+                "public_key": "ed25519:11111111111111111111111111111111",
+                "nonce": 1,
+                "receiver_id": "nft.near",
+                "actions": [],
+                "signature": "ed25519:1111111111111111111111111111111111111111111111111111111111111111",
+                "hash": "11111111111111111111111111111111"
+            },
+            "transaction_outcome": execution_outcome(Vec::new()),
+            "receipts_outcome": [execution_outcome(logs), execution_outcome(Vec::new())]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let outcome = outcome(vec![
+            "Minting token 1",
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"alice.near","token_ids":["1"]}]}"#,
+            "EVENT_JSON:not json",
+        ]);
+
+        assert_eq!(
+            parse_events(&outcome),
+            vec![Nep297Event {
+                standard: "nep171".to_string(),
+                version: "1.0.0".to_string(),
+                event: "nft_mint".to_string(),
+                data: Some(json!([{ "owner_id": "alice.near", "token_ids": ["1"] }])),
+            }]
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Typed parsing of failed function calls.
+//!
+//! The RPC reports contract failures deep inside the execution outcome, often only as a string such as
+//! `"Smart contract panicked: some message"`. `parse_function_call_error` maps these to a `FunctionCallError`.
+
+use near_primitives::errors::{
+    ActionErrorKind, FunctionCallError as RawFunctionCallError, HostError, TxExecutionError,
+};
+use near_primitives::views::{ExecutionOutcomeWithIdView, ExecutionStatusView};
+use std::fmt;
+
+/// Prefix of the execution error reported when a contract panics.
+const PANIC_PREFIX: &str = "Smart contract panicked: ";
+
+/// Prefix used by `near-sdk-rs` for the message of a contract panic.
+const SDK_PANIC_PREFIX: &str = "PanicError: ";
+
+/// Execution error reported when a function call runs out of its prepaid gas.
+const GAS_EXCEEDED_MESSAGE: &str = "Exceeded the prepaid gas.";
+
+/// The reason a function call failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FunctionCallError {
+    /// The contract panicked, e.g. through `env::panic_str` or a failed `require!`.
+    Panic { message: String },
+    /// A host function returned an error.
+    HostError { kind: HostError },
+    /// The contract could not be compiled, linked or executed by the wasm runtime.
+    WasmError { message: String },
+    /// The function call used up all of its prepaid gas.
+    GasExceeded,
+}
+
+impl fmt::Display for FunctionCallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FunctionCallError::Panic { message } => write!(f, "Contract panicked: {}", message),
+            FunctionCallError::HostError { kind } => write!(f, "Host error: {:?}", kind),
+            FunctionCallError::WasmError { message } => write!(f, "Wasm error: {}", message),
+            FunctionCallError::GasExceeded => write!(f, "Exceeded the prepaid gas"),
+        }
+    }
+}
+
+impl std::error::Error for FunctionCallError {}
+
+/// Extracts the function call error from a failed execution outcome.
+///
+/// # Arguments
+///
+/// * `outcome` - The execution outcome of a transaction or receipt.
+///
+/// # Returns
+///
+/// The parsed error, or `None` if the outcome did not fail because of a function call.
+pub fn parse_function_call_error(
+    outcome: &ExecutionOutcomeWithIdView,
+) -> Option<FunctionCallError> {
+    let ExecutionStatusView::Failure(TxExecutionError::ActionError(action_error)) =
+        &outcome.outcome.status
+    else {
+        return None;
+    };
+    let ActionErrorKind::FunctionCallError(error) = &action_error.kind else {
+        return None;
+    };
+
+    Some(match error {
+        RawFunctionCallError::ExecutionError(message) => parse_execution_error(message),
+        RawFunctionCallError::HostError(HostError::GuestPanic { panic_msg }) => {
+            parse_panic_message(panic_msg)
+        }
+        RawFunctionCallError::HostError(HostError::GasExceeded) => FunctionCallError::GasExceeded,
+        RawFunctionCallError::HostError(kind) => {
+            FunctionCallError::HostError { kind: kind.clone() }
+        }
+        RawFunctionCallError::CompilationError(error) => FunctionCallError::WasmError {
+            message: format!("{:?}", error),
+        },
+        RawFunctionCallError::LinkError { msg } => FunctionCallError::WasmError {
+            message: msg.clone(),
+        },
+        RawFunctionCallError::MethodResolveError(error) => FunctionCallError::WasmError {
+            message: format!("{:?}", error),
+        },
+        RawFunctionCallError::WasmTrap(trap) => FunctionCallError::WasmError {
+            message: format!("{:?}", trap),
+        },
+        error => FunctionCallError::WasmError {
+            message: format!("{:?}", error),
+        },
+    })
+}
+
+/// Maps the string form of an execution error to a `FunctionCallError`.
+fn parse_execution_error(message: &str) -> FunctionCallError {
+    if message == GAS_EXCEEDED_MESSAGE {
+        FunctionCallError::GasExceeded
+    } else if let Some(panic_msg) = message.strip_prefix(PANIC_PREFIX) {
+        parse_panic_message(panic_msg)
+    } else {
+        FunctionCallError::WasmError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Strips the `near-sdk-rs` prefix from a panic message.
+fn parse_panic_message(panic_msg: &str) -> FunctionCallError {
+    let message = panic_msg
+        .strip_prefix(SDK_PANIC_PREFIX)
+        .unwrap_or(panic_msg);
+    FunctionCallError::Panic {
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::errors::ActionError;
+    use near_providers::test_utils::outcome;
+
+    /// Returns an outcome whose first action failed with `error`.
+    fn failed_outcome(error: RawFunctionCallError) -> ExecutionOutcomeWithIdView {
+        let mut outcome = outcome(1).transaction_outcome;
+        outcome.outcome.status =
+            ExecutionStatusView::Failure(TxExecutionError::ActionError(ActionError {
+                index: Some(0),
+                kind: ActionErrorKind::FunctionCallError(error),
+            }));
+        outcome
+    }
+
+    #[test]
+    fn test_parse_execution_error_panic() {
+        let outcome = failed_outcome(RawFunctionCallError::ExecutionError(
+            "Smart contract panicked: PanicError: msg".to_string(),
+        ));
+        assert_eq!(
+            parse_function_call_error(&outcome),
+            Some(FunctionCallError::Panic {
+                message: "msg".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_execution_error_gas_exceeded() {
+        let outcome = failed_outcome(RawFunctionCallError::ExecutionError(
+            "Exceeded the prepaid gas.".to_string(),
+        ));
+        assert_eq!(
+            parse_function_call_error(&outcome),
+            Some(FunctionCallError::GasExceeded)
+        );
+    }
+
+    #[test]
+    fn test_parse_guest_panic() {
+        let outcome = failed_outcome(RawFunctionCallError::HostError(HostError::GuestPanic {
+            panic_msg: "PanicError: not enough balance".to_string(),
+        }));
+        assert_eq!(
+            parse_function_call_error(&outcome),
+            Some(FunctionCallError::Panic {
+                message: "not enough balance".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_successful_outcome() {
+        assert_eq!(
+            parse_function_call_error(&outcome(1).transaction_outcome),
+            None
+        );
+    }
+}
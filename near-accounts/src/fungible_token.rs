@@ -0,0 +1,264 @@
+//! A typed client for NEP-141 fungible token contracts.
+//!
+//! `FtClient` wraps the view and change methods of the standard, so callers don't have to build the JSON
+//! arguments and decode the results of `ft_*` calls themselves. Contract failures caused by the
+//! reference implementation's checks, such as transferring more than the balance, are mapped to `FtError`.
+
+use crate::accounts::Account;
+use crate::function_call_error::{parse_function_call_error, FunctionCallError};
+use near_crypto::Signer;
+use near_primitives::types::{AccountId, Balance, Gas};
+use near_primitives::views::{FinalExecutionOutcomeView, FinalExecutionStatus};
+use near_providers::Provider;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::fmt;
+use std::sync::Arc;
+
+/// Gas attached to `ft_transfer` calls.
+pub const FT_TRANSFER_GAS: Gas = 30_000_000_000_000;
+
+/// Gas attached to `ft_transfer_call` calls, which also cover the receiver's `ft_on_transfer` and the
+/// `ft_resolve_transfer` callback.
+pub const FT_TRANSFER_CALL_GAS: Gas = 100_000_000_000_000;
+
+/// Deposit attached to `ft_transfer` and `ft_transfer_call`, which require exactly one yoctoNEAR.
+const ONE_YOCTO: Balance = 1;
+
+/// Panic message of the reference implementation when the sender's balance is too low.
+const INSUFFICIENT_BALANCE_MESSAGE: &str = "The account doesn't have enough balance";
+
+/// A token amount, serialized as a decimal string as NEP-141 requires, because JSON numbers can't
+/// represent all `u128` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct U128(pub u128);
+
+impl From<u128> for U128 {
+    fn from(value: u128) -> Self {
+        U128(value)
+    }
+}
+
+impl From<U128> for u128 {
+    fn from(value: U128) -> Self {
+        value.0
+    }
+}
+
+impl Serialize for U128 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for U128 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map(U128).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The reason a fungible token call failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FtError {
+    /// The sender's balance is lower than the transferred amount.
+    InsufficientBalance,
+    /// The account is not registered with the token contract, see NEP-145 storage management.
+    AccountNotRegistered(AccountId),
+    /// The contract failed for another reason.
+    FunctionCall(FunctionCallError),
+    /// The request failed or its result could not be decoded.
+    Request(String),
+}
+
+impl fmt::Display for FtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FtError::InsufficientBalance => write!(f, "Insufficient token balance"),
+            FtError::AccountNotRegistered(account_id) => {
+                write!(f, "Account {} is not registered", account_id)
+            }
+            FtError::FunctionCall(error) => write!(f, "{}", error),
+            FtError::Request(message) => write!(f, "Request failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FtError {}
+
+/// A client for the NEP-141 methods of the token contract `contract_id`, signing change calls as
+/// `signer_id`.
+pub struct FtClient {
+    contract_id: AccountId,
+    account: Account,
+}
+
+impl FtClient {
+    /// Constructs a new `FtClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_id` - The account ID of the token contract.
+    /// * `provider` - A provider instance for interacting with the blockchain.
+    /// * `signer` - A signer instance for signing transfers.
+    /// * `signer_id` - The account sending transfers.
+    pub fn new(
+        contract_id: AccountId,
+        provider: Arc<dyn Provider>,
+        signer: Arc<dyn Signer>,
+        signer_id: AccountId,
+    ) -> Self {
+        Self {
+            contract_id,
+            account: Account::new(signer_id, signer, provider),
+        }
+    }
+
+    /// Fetches the total supply of the token with `ft_total_supply`.
+    pub async fn total_supply(&self) -> Result<U128, FtError> {
+        self.view("ft_total_supply", json!({})).await
+    }
+
+    /// Fetches the balance of `account_id` with `ft_balance_of`.
+    pub async fn balance_of(&self, account_id: &AccountId) -> Result<U128, FtError> {
+        self.view("ft_balance_of", json!({ "account_id": account_id }))
+            .await
+    }
+
+    /// Transfers `amount` to `receiver_id` with `ft_transfer`, attaching one yoctoNEAR.
+    ///
+    /// # Returns
+    ///
+    /// The final execution outcome, or an `FtError` if the transfer failed.
+    pub async fn transfer(
+        &self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+    ) -> Result<FinalExecutionOutcomeView, FtError> {
+        let args = json!({
+            "receiver_id": receiver_id,
+            "amount": amount,
+            "memo": memo,
+        });
+        self.call("ft_transfer", args, FT_TRANSFER_GAS).await
+    }
+
+    /// Transfers `amount` to the contract `receiver_id` with `ft_transfer_call`, which then calls the
+    /// receiver's `ft_on_transfer` with `msg`, attaching one yoctoNEAR.
+    ///
+    /// The outcome only fails if the transfer itself failed. Tokens the receiver doesn't accept are
+    /// refunded by `ft_resolve_transfer`, which shows in the outcome's return value.
+    ///
+    /// # Returns
+    ///
+    /// The final execution outcome, or an `FtError` if the transfer failed.
+    pub async fn transfer_call(
+        &self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> Result<FinalExecutionOutcomeView, FtError> {
+        let args = json!({
+            "receiver_id": receiver_id,
+            "amount": amount,
+            "memo": memo,
+            "msg": msg,
+        });
+        self.call("ft_transfer_call", args, FT_TRANSFER_CALL_GAS)
+            .await
+    }
+
+    async fn view(&self, method_name: &str, args: Value) -> Result<U128, FtError> {
+        let result = self
+            .account
+            .view_function(self.contract_id.clone(), method_name.to_string(), args)
+            .await
+            .map_err(|err| FtError::Request(err.to_string()))?;
+        serde_json::from_slice(&result.result).map_err(|err| FtError::Request(err.to_string()))
+    }
+
+    async fn call(
+        &self,
+        method_name: &str,
+        args: Value,
+        gas: Gas,
+    ) -> Result<FinalExecutionOutcomeView, FtError> {
+        let outcome = self
+            .account
+            .function_call(
+                &self.contract_id,
+                method_name.to_string(),
+                args,
+                gas,
+                ONE_YOCTO,
+            )
+            .await
+            .map_err(|err| FtError::Request(err.to_string()))?;
+        if let FinalExecutionStatus::Failure(err) = &outcome.status {
+            let error = outcome
+                .receipts_outcome
+                .iter()
+                .find_map(parse_function_call_error)
+                .map(parse_ft_error)
+                .unwrap_or_else(|| FtError::Request(err.to_string()));
+            return Err(error);
+        }
+        Ok(outcome)
+    }
+}
+
+/// Maps the panics of the NEP-141 reference implementation to the matching `FtError`.
+fn parse_ft_error(error: FunctionCallError) -> FtError {
+    if let FunctionCallError::Panic { message } = &error {
+        if message == INSUFFICIENT_BALANCE_MESSAGE {
+            return FtError::InsufficientBalance;
+        }
+        if let Some(account_id) = message
+            .strip_prefix("The account ")
+            .and_then(|rest| rest.strip_suffix(" is not registered"))
+            .and_then(|account_id| account_id.parse().ok())
+        {
+            return FtError::AccountNotRegistered(account_id);
+        }
+    }
+    FtError::FunctionCall(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u128_serialization() {
+        let amount = U128(u128::MAX);
+        let json = serde_json::to_string(&amount).unwrap();
+        assert_eq!(json, format!("\"{}\"", u128::MAX));
+        assert_eq!(serde_json::from_str::<U128>(&json).unwrap(), amount);
+        assert!(serde_json::from_str::<U128>("100").is_err());
+    }
+
+    #[test]
+    fn test_parse_ft_error() {
+        let panic = |message: &str| FunctionCallError::Panic {
+            message: message.to_string(),
+        };
+        assert_eq!(
+            parse_ft_error(panic("The account doesn't have enough balance")),
+            FtError::InsufficientBalance
+        );
+        assert_eq!(
+            parse_ft_error(panic("The account bob.near is not registered")),
+            FtError::AccountNotRegistered("bob.near".parse().unwrap())
+        );
+        assert_eq!(
+            parse_ft_error(panic("Requires attached deposit of exactly 1 yoctoNEAR")),
+            FtError::FunctionCall(panic("Requires attached deposit of exactly 1 yoctoNEAR"))
+        );
+        assert_eq!(
+            parse_ft_error(FunctionCallError::GasExceeded),
+            FtError::FunctionCall(FunctionCallError::GasExceeded)
+        );
+    }
+}
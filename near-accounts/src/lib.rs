@@ -72,7 +72,39 @@
 //! #}
 //! ```
 
-pub use crate::accounts::Account;
+pub use crate::access_keys::{AccessKeyChange, AccessKeyMonitor};
+pub use crate::account_manager::{AccountCreationReceipt, AccountManager};
+pub use crate::accounts::{
+    decode_success_value, ensure_account, init_if_needed, keys_to_prune, list_access_keys,
+    set_recent_block_hash, Account, CallAndViewResult, EnsureAccountOutcome,
+};
+pub use crate::deploy::{wasm_hash, DeployChecksumVerifier, DeployVerifyError};
+pub use crate::events::{parse_events, Nep297Event};
+pub use crate::function_call_error::{parse_function_call_error, FunctionCallError};
+pub use crate::nonces::{handle_nonce_error, resync_nonce};
+pub use crate::relayer::RelayerClient;
+#[cfg(feature = "sandbox")]
+pub use crate::sandbox::{SandboxNode, SandboxOutcome};
+pub use crate::storage::{get_storage_balance, get_storage_balance_bounds};
+pub use crate::sub_account::{InvalidSubAccountId, SubAccountCreator};
+#[cfg(feature = "price-oracle")]
+pub use crate::token_price::TokenPriceClient;
 
-mod access_keys;
+pub mod access_keys;
+pub mod account_manager;
 pub mod accounts;
+pub mod deploy;
+pub mod events;
+mod function_call_error;
+pub mod fungible_token;
+pub mod nft;
+pub mod nonces;
+pub mod promises;
+pub mod relayer;
+#[cfg(feature = "sandbox")]
+pub mod sandbox;
+pub mod staking;
+pub mod storage;
+pub mod sub_account;
+#[cfg(feature = "price-oracle")]
+pub mod token_price;
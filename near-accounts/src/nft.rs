@@ -0,0 +1,279 @@
+//! A typed client for NEP-171 non-fungible token contracts.
+//!
+//! `NftClient` wraps the core and enumeration methods of the standard, decoding tokens and their NEP-177
+//! metadata into `NftToken`. Missing tokens are reported as `NftError::TokenNotFound` wherever the call
+//! requires the token to exist.
+
+use crate::accounts::Account;
+use crate::function_call_error::{parse_function_call_error, FunctionCallError};
+use crate::fungible_token::U128;
+use near_crypto::Signer;
+use near_primitives::types::{AccountId, Balance, Gas};
+use near_primitives::views::{FinalExecutionOutcomeView, FinalExecutionStatus};
+use near_providers::Provider;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Gas attached to `nft_transfer` calls.
+pub const NFT_TRANSFER_GAS: Gas = 30_000_000_000_000;
+
+/// Deposit attached to `nft_transfer`, which requires exactly one yoctoNEAR.
+const ONE_YOCTO: Balance = 1;
+
+/// Panic message of the reference implementation when the transferred token does not exist.
+const TOKEN_NOT_FOUND_MESSAGE: &str = "Token not found";
+
+/// The metadata of a token, as defined by NEP-177.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NftTokenMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// URL of the associated media, preferably on decentralized storage.
+    pub media: Option<String>,
+    /// Base64-encoded sha256 hash of the content referenced by `media`.
+    pub media_hash: Option<String>,
+    /// The number of copies of this set of metadata in existence when the token was minted.
+    pub copies: Option<u64>,
+    /// When the token was issued, as Unix epoch milliseconds.
+    pub issued_at: Option<String>,
+    /// When the token expires, as Unix epoch milliseconds.
+    pub expires_at: Option<String>,
+    /// When the token starts being valid, as Unix epoch milliseconds.
+    pub starts_at: Option<String>,
+    /// When the token was last updated, as Unix epoch milliseconds.
+    pub updated_at: Option<String>,
+    /// Anything extra the contract stores on-chain, often a JSON string.
+    pub extra: Option<String>,
+    /// URL of an off-chain JSON file with more info.
+    pub reference: Option<String>,
+    /// Base64-encoded sha256 hash of the JSON file referenced by `reference`.
+    pub reference_hash: Option<String>,
+}
+
+/// A token, as returned by `nft_token` and the enumeration methods.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftToken {
+    pub token_id: String,
+    pub owner_id: AccountId,
+    /// The token's metadata, `None` if the contract does not implement NEP-177.
+    pub metadata: Option<NftTokenMetadata>,
+    /// The accounts approved to transfer the token and their approval IDs, `None` if the contract does
+    /// not implement NEP-178.
+    pub approved_account_ids: Option<HashMap<AccountId, u64>>,
+}
+
+/// The reason a non-fungible token call failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NftError {
+    /// The token does not exist.
+    TokenNotFound(String),
+    /// The contract failed for another reason.
+    FunctionCall(FunctionCallError),
+    /// The request failed or its result could not be decoded.
+    Request(String),
+}
+
+impl fmt::Display for NftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NftError::TokenNotFound(token_id) => write!(f, "Token {} not found", token_id),
+            NftError::FunctionCall(error) => write!(f, "{}", error),
+            NftError::Request(message) => write!(f, "Request failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for NftError {}
+
+/// A client for the NEP-171 methods of the token contract `contract_id`, signing change calls as
+/// `signer_id`.
+pub struct NftClient {
+    contract_id: AccountId,
+    account: Account,
+}
+
+impl NftClient {
+    /// Constructs a new `NftClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_id` - The account ID of the token contract.
+    /// * `provider` - A provider instance for interacting with the blockchain.
+    /// * `signer` - A signer instance for signing transfers.
+    /// * `signer_id` - The account sending transfers.
+    pub fn new(
+        contract_id: AccountId,
+        provider: Arc<dyn Provider>,
+        signer: Arc<dyn Signer>,
+        signer_id: AccountId,
+    ) -> Self {
+        Self {
+            contract_id,
+            account: Account::new(signer_id, signer, provider),
+        }
+    }
+
+    /// Fetches the token `token_id` with `nft_token`.
+    ///
+    /// # Returns
+    ///
+    /// The token, or `None` if it does not exist.
+    pub async fn nft_token(&self, token_id: &str) -> Result<Option<NftToken>, NftError> {
+        self.view("nft_token", json!({ "token_id": token_id }))
+            .await
+    }
+
+    /// Fetches the token `token_id` like `nft_token`, but fails with `NftError::TokenNotFound` if it
+    /// does not exist.
+    pub async fn nft_token_required(&self, token_id: &str) -> Result<NftToken, NftError> {
+        self.nft_token(token_id)
+            .await?
+            .ok_or_else(|| NftError::TokenNotFound(token_id.to_string()))
+    }
+
+    /// Transfers the token `token_id` to `receiver_id` with `nft_transfer`, attaching one yoctoNEAR.
+    ///
+    /// `approval_id` is only needed when the signer is an approved account rather than the owner.
+    ///
+    /// # Returns
+    ///
+    /// The final execution outcome, or an `NftError` if the transfer failed.
+    pub async fn nft_transfer(
+        &self,
+        receiver_id: AccountId,
+        token_id: String,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) -> Result<FinalExecutionOutcomeView, NftError> {
+        let args = json!({
+            "receiver_id": receiver_id,
+            "token_id": token_id,
+            "approval_id": approval_id,
+            "memo": memo,
+        });
+        let outcome = self
+            .account
+            .function_call(
+                &self.contract_id,
+                "nft_transfer".to_string(),
+                args,
+                NFT_TRANSFER_GAS,
+                ONE_YOCTO,
+            )
+            .await
+            .map_err(|err| NftError::Request(err.to_string()))?;
+        if let FinalExecutionStatus::Failure(err) = &outcome.status {
+            let error = outcome
+                .receipts_outcome
+                .iter()
+                .find_map(parse_function_call_error)
+                .map(|error| parse_nft_error(error, &token_id))
+                .unwrap_or_else(|| NftError::Request(err.to_string()));
+            return Err(error);
+        }
+        Ok(outcome)
+    }
+
+    /// Fetches the tokens owned by `account_id` with `nft_tokens_for_owner`, starting at the
+    /// `from_index`-th token and returning at most `limit` tokens.
+    pub async fn nft_tokens_for_owner(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Result<Vec<NftToken>, NftError> {
+        let args = json!({
+            "account_id": account_id,
+            "from_index": from_index,
+            "limit": limit,
+        });
+        self.view("nft_tokens_for_owner", args).await
+    }
+
+    async fn view<T: DeserializeOwned>(
+        &self,
+        method_name: &str,
+        args: Value,
+    ) -> Result<T, NftError> {
+        let result = self
+            .account
+            .view_function(self.contract_id.clone(), method_name.to_string(), args)
+            .await
+            .map_err(|err| NftError::Request(err.to_string()))?;
+        serde_json::from_slice(&result.result).map_err(|err| NftError::Request(err.to_string()))
+    }
+}
+
+/// Maps the panics of the NEP-171 reference implementation to the matching `NftError`.
+fn parse_nft_error(error: FunctionCallError, token_id: &str) -> NftError {
+    match &error {
+        FunctionCallError::Panic { message } if message == TOKEN_NOT_FOUND_MESSAGE => {
+            NftError::TokenNotFound(token_id.to_string())
+        }
+        _ => NftError::FunctionCall(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_token() {
+        let token: Option<NftToken> = serde_json::from_str(
+            r#"{
+                "token_id": "1",
+                "owner_id": "alice.near",
+                "metadata": {
+                    "title": "First",
+                    "description": null,
+                    "media": "https://example.com/1.png",
+                    "media_hash": null,
+                    "copies": 10,
+                    "issued_at": "1700000000000",
+                    "expires_at": null,
+                    "starts_at": null,
+                    "updated_at": null,
+                    "extra": null,
+                    "reference": null,
+                    "reference_hash": null
+                },
+                "approved_account_ids": { "market.near": 3 }
+            }"#,
+        )
+        .unwrap();
+        let token = token.unwrap();
+
+        assert_eq!(token.owner_id.as_str(), "alice.near");
+        let metadata = token.metadata.unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("First"));
+        assert_eq!(metadata.copies, Some(10));
+        assert_eq!(
+            token.approved_account_ids.unwrap()[&"market.near".parse::<AccountId>().unwrap()],
+            3
+        );
+        assert_eq!(
+            serde_json::from_str::<Option<NftToken>>("null").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_nft_error() {
+        let panic = |message: &str| FunctionCallError::Panic {
+            message: message.to_string(),
+        };
+        assert_eq!(
+            parse_nft_error(panic("Token not found"), "1"),
+            NftError::TokenNotFound("1".to_string())
+        );
+        assert_eq!(
+            parse_nft_error(panic("Unauthorized"), "1"),
+            NftError::FunctionCall(panic("Unauthorized"))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! The `nonces` module keeps a `near_transactions::NonceManager` in sync with the access key on chain,
+//! so the local counter only goes back to the RPC when it has drifted.
+
+use near_crypto::PublicKey;
+use near_primitives::errors::InvalidTxError;
+use near_primitives::types::{AccountId, Nonce};
+use near_primitives::views::QueryRequest;
+use near_providers::jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use near_providers::types::query::QueryResponseKind;
+use near_providers::types::transactions::RpcTransactionError;
+use near_providers::Provider;
+use near_transactions::NonceManager;
+
+/// Resynchronizes the counter of `manager` with the nonce of the access key on chain.
+///
+/// # Arguments
+///
+/// * `manager` - The nonce manager of the access key.
+/// * `provider` - The provider through which to query the access key.
+/// * `account_id` - The account owning the access key.
+/// * `public_key` - The public key of the access key.
+///
+/// # Returns
+///
+/// The nonce of the access key on chain, or an error if the query failed.
+pub async fn resync_nonce(
+    manager: &NonceManager,
+    provider: &dyn Provider,
+    account_id: &AccountId,
+    public_key: &PublicKey,
+) -> Result<Nonce, Box<dyn std::error::Error>> {
+    let query_request = QueryRequest::ViewAccessKey {
+        account_id: account_id.clone(),
+        public_key: public_key.clone(),
+    };
+    let response = provider.query(query_request).await?;
+    if let QueryResponseKind::AccessKey(access_key_view) = response.kind {
+        manager.advance_to(access_key_view.nonce);
+        Ok(access_key_view.nonce)
+    } else {
+        Err("Unexpected response kind".into())
+    }
+}
+
+/// Resynchronizes the counter of `manager` if `error` rejected a transaction because of its nonce,
+/// using the access key nonce reported in the error. Returns true if the transaction should be retried
+/// with a new nonce.
+pub fn handle_nonce_error(
+    manager: &NonceManager,
+    error: &JsonRpcError<RpcTransactionError>,
+) -> bool {
+    if let JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+        RpcTransactionError::InvalidTransaction {
+            context: InvalidTxError::InvalidNonce { ak_nonce, .. },
+        },
+    )) = error
+    {
+        manager.advance_to(*ak_nonce);
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::views::{AccessKeyPermissionView, AccessKeyView};
+    use near_providers::test_utils::MockProvider;
+    use near_providers::types::query::RpcQueryResponse;
+
+    #[tokio::test]
+    async fn test_resync_nonce() {
+        let provider = MockProvider::default().on_query(|_| async {
+            Ok(RpcQueryResponse {
+                kind: QueryResponseKind::AccessKey(AccessKeyView {
+                    nonce: 20,
+                    permission: AccessKeyPermissionView::FullAccess,
+                }),
+                block_height: 42,
+                block_hash: CryptoHash::default(),
+            })
+        });
+        let manager = NonceManager::new(10);
+
+        let nonce = resync_nonce(
+            &manager,
+            &provider,
+            &"alice.near".parse().unwrap(),
+            &PublicKey::empty(KeyType::ED25519),
+        )
+        .await
+        .unwrap();
+        assert_eq!(nonce, 20);
+        assert_eq!(manager.next_nonce(), 21);
+    }
+
+    #[test]
+    fn test_handle_nonce_error() {
+        let manager = NonceManager::new(10);
+        let invalid_nonce = JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+            RpcTransactionError::InvalidTransaction {
+                context: InvalidTxError::InvalidNonce {
+                    tx_nonce: 11,
+                    ak_nonce: 30,
+                },
+            },
+        ));
+        assert!(handle_nonce_error(&manager, &invalid_nonce));
+        assert_eq!(manager.next_nonce(), 31);
+
+        let timeout = JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+            RpcTransactionError::TimeoutError,
+        ));
+        assert!(!handle_nonce_error(&manager, &timeout));
+        assert_eq!(manager.next_nonce(), 32);
+    }
+}
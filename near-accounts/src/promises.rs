@@ -0,0 +1,176 @@
+//! Decoding of promise results, as received by cross-contract callbacks through `env::promise_result`.
+//!
+//! A callback receives the return value of the promise it waits on as raw bytes, serialized either as
+//! JSON or with borsh depending on the called contract. The same bytes can be read off-chain from the
+//! execution outcome of the receipt, e.g. to verify what a callback was given.
+
+use borsh::BorshDeserialize;
+use near_primitives::views::{ExecutionStatusView, FinalExecutionOutcomeView};
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// The serialization format of a promise's return value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    Borsh,
+}
+
+/// Errors that can occur while decoding a promise result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes are not valid JSON for the requested type.
+    Json(String),
+    /// The bytes are not valid borsh for the requested type.
+    Borsh(String),
+    /// The promise failed, so there is no value to decode.
+    Failed,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Json(reason) => write!(f, "Invalid JSON promise result: {}", reason),
+            DecodeError::Borsh(reason) => write!(f, "Invalid borsh promise result: {}", reason),
+            DecodeError::Failed => write!(f, "The promise failed"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// The result of a promise, mirroring `near_sdk::PromiseResult`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromiseResult {
+    /// The promise succeeded with the given return value.
+    Successful(Vec<u8>),
+    /// The promise failed.
+    Failed,
+}
+
+impl PromiseResult {
+    /// Decodes the raw return value of a promise.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The raw return value.
+    /// * `encoding` - The format the value was serialized with.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the decoded value, or an error if the bytes are not valid for `T`.
+    pub fn decode<T: BorshDeserialize + DeserializeOwned>(
+        bytes: &[u8],
+        encoding: Encoding,
+    ) -> Result<T, DecodeError> {
+        match encoding {
+            Encoding::Json => {
+                serde_json::from_slice(bytes).map_err(|err| DecodeError::Json(err.to_string()))
+            }
+            Encoding::Borsh => {
+                T::try_from_slice(bytes).map_err(|err| DecodeError::Borsh(err.to_string()))
+            }
+        }
+    }
+
+    /// Decodes the return value of a successful promise, see `PromiseResult::decode`.
+    pub fn decode_value<T: BorshDeserialize + DeserializeOwned>(
+        &self,
+        encoding: Encoding,
+    ) -> Result<T, DecodeError> {
+        match self {
+            PromiseResult::Successful(bytes) => Self::decode(bytes, encoding),
+            PromiseResult::Failed => Err(DecodeError::Failed),
+        }
+    }
+}
+
+/// Extracts the promise result of a receipt from a transaction's execution outcome.
+///
+/// # Arguments
+///
+/// * `outcome` - The final execution outcome of the transaction.
+/// * `receipt_index` - The index of the receipt in `outcome.receipts_outcome`.
+///
+/// # Returns
+///
+/// The promise result, or `None` if there is no receipt at `receipt_index`, or its result is not known
+/// yet or is the result of another promise the receipt returned.
+pub fn parse_promise_result_from_outcome(
+    outcome: &FinalExecutionOutcomeView,
+    receipt_index: usize,
+) -> Option<PromiseResult> {
+    let receipt_outcome = outcome.receipts_outcome.get(receipt_index)?;
+    promise_result_from_status(&receipt_outcome.outcome.status)
+}
+
+/// Maps the execution status of a receipt to the result a callback waiting on it would receive.
+fn promise_result_from_status(status: &ExecutionStatusView) -> Option<PromiseResult> {
+    match status {
+        ExecutionStatusView::SuccessValue(bytes) => Some(PromiseResult::Successful(bytes.clone())),
+        ExecutionStatusView::Failure(_) => Some(PromiseResult::Failed),
+        ExecutionStatusView::Unknown | ExecutionStatusView::SuccessReceiptId(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::errors::{ActionError, ActionErrorKind, TxExecutionError};
+    use near_primitives::hash::CryptoHash;
+
+    #[test]
+    fn test_decode_json() {
+        let value: String = PromiseResult::decode(br#""hello""#, Encoding::Json).unwrap();
+        assert_eq!(value, "hello");
+        let value: Vec<u64> = PromiseResult::decode(b"[1,2,3]", Encoding::Json).unwrap();
+        assert_eq!(value, vec![1, 2, 3]);
+        assert!(matches!(
+            PromiseResult::decode::<u64>(b"not json", Encoding::Json),
+            Err(DecodeError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_borsh() {
+        let value: u64 =
+            PromiseResult::decode(&[42, 0, 0, 0, 0, 0, 0, 0], Encoding::Borsh).unwrap();
+        assert_eq!(value, 42);
+        let value: String =
+            PromiseResult::decode(&[5, 0, 0, 0, b'h', b'e', b'l', b'l', b'o'], Encoding::Borsh)
+                .unwrap();
+        assert_eq!(value, "hello");
+        assert!(matches!(
+            PromiseResult::decode::<u64>(&[1, 2, 3], Encoding::Borsh),
+            Err(DecodeError::Borsh(_))
+        ));
+    }
+
+    #[test]
+    fn test_promise_result_from_status() {
+        assert_eq!(
+            promise_result_from_status(&ExecutionStatusView::SuccessValue(b"true".to_vec())),
+            Some(PromiseResult::Successful(b"true".to_vec()))
+        );
+        let failure = ExecutionStatusView::Failure(TxExecutionError::ActionError(ActionError {
+            index: Some(0),
+            kind: ActionErrorKind::AccountDoesNotExist {
+                account_id: "bob.near".parse().unwrap(),
+            },
+        }));
+        assert_eq!(
+            promise_result_from_status(&failure),
+            Some(PromiseResult::Failed)
+        );
+        assert_eq!(
+            PromiseResult::Failed.decode_value::<bool>(Encoding::Json),
+            Err(DecodeError::Failed)
+        );
+        assert_eq!(
+            promise_result_from_status(&ExecutionStatusView::SuccessReceiptId(
+                CryptoHash::default()
+            )),
+            None
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! The `relayer` module relays NEP-366 meta transactions, wrapping delegate actions signed by a user
+//! into transactions paid for by the relayer. The delegate actions themselves are built with
+//! `near_transactions::MetaTxBuilder`.
+
+use near_crypto::InMemorySigner;
+use near_primitives::action::delegate::SignedDelegateAction;
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::Action;
+use near_primitives::types::{AccountId, BlockReference, Finality};
+use near_primitives::views::QueryRequest;
+use near_providers::types::query::QueryResponseKind;
+use near_providers::Provider;
+use near_transactions::TransactionBuilder;
+use std::sync::Arc;
+
+/// Relays signed delegate actions by wrapping them in a transaction paid for by the relayer.
+pub struct RelayerClient {
+    provider: Arc<dyn Provider>,
+}
+
+impl RelayerClient {
+    /// Creates a new relayer client.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider used to fetch the relayer's nonce and broadcast transactions.
+    pub fn new(provider: Arc<dyn Provider>) -> Self {
+        Self { provider }
+    }
+
+    /// Wraps a signed delegate action in a transaction from the relayer and broadcasts it.
+    ///
+    /// # Arguments
+    ///
+    /// * `signed_delegate` - The delegate action signed by the sender.
+    /// * `relayer_account` - The account paying for the gas of the outer transaction.
+    /// * `relayer_signer` - The signer holding an access key of `relayer_account`.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the hash of the outer transaction, without waiting for its execution.
+    pub async fn relay(
+        &self,
+        signed_delegate: SignedDelegateAction,
+        relayer_account: AccountId,
+        relayer_signer: &InMemorySigner,
+    ) -> Result<CryptoHash, Box<dyn std::error::Error>> {
+        let query_request = QueryRequest::ViewAccessKey {
+            account_id: relayer_account.clone(),
+            public_key: relayer_signer.public_key(),
+        };
+        let response = self.provider.query(query_request).await?;
+        let QueryResponseKind::AccessKey(access_key_view) = response.kind else {
+            return Err("Unexpected response kind".into());
+        };
+
+        let block = self
+            .provider
+            .block(BlockReference::Finality(Finality::Final))
+            .await?;
+
+        // The outer transaction is sent to the sender, who executes the inner actions.
+        let receiver_id = signed_delegate.delegate_action.sender_id.clone();
+        let signed_tx = TransactionBuilder::new(
+            relayer_account,
+            relayer_signer.public_key(),
+            receiver_id,
+            access_key_view.nonce + 1,
+            block.header.hash,
+        )
+        .add_action(Action::Delegate(Box::new(signed_delegate)))
+        .sign_transaction(relayer_signer);
+
+        Ok(self.provider.send_transaction_async(signed_tx).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::transaction::SignedTransaction;
+    use near_primitives::views::{AccessKeyPermissionView, AccessKeyView};
+    use near_providers::test_utils::{block, block_hash, MockProvider};
+    use near_providers::types::query::RpcQueryResponse;
+    use near_transactions::MetaTxBuilder;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_relay() {
+        let sent_transactions = Arc::new(Mutex::new(Vec::<SignedTransaction>::new()));
+        let sent = sent_transactions.clone();
+        let provider = MockProvider::default()
+            .on_query(|_| async {
+                Ok(RpcQueryResponse {
+                    kind: QueryResponseKind::AccessKey(AccessKeyView {
+                        nonce: 4,
+                        permission: AccessKeyPermissionView::FullAccess,
+                    }),
+                    block_height: 42,
+                    block_hash: block_hash(42),
+                })
+            })
+            .on_block(|_| async { Ok(block(42)) })
+            .on_send_transaction_async(move |signed_transaction| {
+                let hash = signed_transaction.get_hash();
+                sent.lock().unwrap().push(signed_transaction);
+                async move { Ok(hash) }
+            });
+        let sender = InMemorySigner::from_seed(
+            "alice.near".parse().unwrap(),
+            KeyType::ED25519,
+            "alice.near",
+        );
+        let relayer = InMemorySigner::from_seed(
+            "relayer.near".parse().unwrap(),
+            KeyType::ED25519,
+            "relayer.near",
+        );
+        let signed_delegate = MetaTxBuilder::new(
+            "alice.near".parse().unwrap(),
+            1,
+            1_000,
+            "bob.near".parse().unwrap(),
+        )
+        .transfer(1)
+        .sign(&sender);
+
+        let hash = RelayerClient::new(Arc::new(provider))
+            .relay(
+                signed_delegate.clone(),
+                "relayer.near".parse().unwrap(),
+                &relayer,
+            )
+            .await
+            .unwrap();
+
+        let sent_transactions = sent_transactions.lock().unwrap();
+        assert_eq!(sent_transactions.len(), 1);
+        assert_eq!(sent_transactions[0].get_hash(), hash);
+        let transaction = &sent_transactions[0].transaction;
+        assert_eq!(transaction.signer_id().as_str(), "relayer.near");
+        assert_eq!(transaction.receiver_id().as_str(), "alice.near");
+        assert_eq!(transaction.nonce(), 5);
+        assert_eq!(transaction.block_hash(), &block_hash(42));
+        assert_eq!(
+            transaction.actions(),
+            [Action::Delegate(Box::new(signed_delegate))]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! The `sandbox` module executes transactions on a local `near-sandbox` node, for tests.
+//!
+//! `SandboxNode` connects to a sandbox that is already running, e.g. one started with
+//! `near-sandbox --home /tmp/near-sandbox run`, and signs with the key of an account on it, usually the
+//! `test.near` root account from `/tmp/near-sandbox/validator_key.json`.
+//!
+//! Transactions executed on the sandbox are not simulated: they are committed like on any other
+//! network, so their effects persist in the sandbox state and are seen by later transactions. Restart
+//! the sandbox from a fresh home directory to start over.
+
+use crate::accounts::set_recent_block_hash;
+use near_crypto::InMemorySigner;
+use near_primitives::types::{BlockReference, Finality, Gas};
+use near_primitives::views::{FinalExecutionStatus, QueryRequest};
+use near_providers::types::query::QueryResponseKind;
+use near_providers::{JsonRpcProvider, Provider};
+use near_transactions::TransactionBuilder;
+
+/// A running sandbox node and the signer used for transactions sent to it.
+pub struct SandboxNode {
+    provider: JsonRpcProvider,
+    signer: InMemorySigner,
+}
+
+/// The effects of a transaction executed on a sandbox node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxOutcome {
+    /// Gas burnt by the transaction and all receipts it created.
+    pub gas_burnt: Gas,
+    /// Logs emitted by the transaction and all receipts it created, in execution order.
+    pub logs: Vec<String>,
+    /// The value returned by the last action, if the transaction succeeded.
+    pub return_value: Option<Vec<u8>>,
+    /// Why the transaction failed, including contract panic messages.
+    pub error: Option<String>,
+}
+
+impl SandboxNode {
+    /// Connects to the sandbox RPC at `rpc_endpoint`, e.g. `http://localhost:3030`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_endpoint` - The RPC address of the sandbox.
+    /// * `signer` - The signer of an account on the sandbox, used to sign executed transactions.
+    pub fn new(rpc_endpoint: &str, signer: InMemorySigner) -> Self {
+        Self {
+            provider: JsonRpcProvider::new(rpc_endpoint),
+            signer,
+        }
+    }
+
+    /// The provider connected to the sandbox.
+    pub fn provider(&self) -> &JsonRpcProvider {
+        &self.provider
+    }
+
+    /// The signer used for executed transactions.
+    pub fn signer(&self) -> &InMemorySigner {
+        &self.signer
+    }
+
+    /// Signs the transaction of `builder` with the sandbox signer, executes it on the sandbox and
+    /// returns its effects.
+    ///
+    /// This is not a dry run: the transaction is committed and its effects persist in the sandbox state.
+    /// The nonce and block hash are replaced with fresh values from the sandbox. The transaction's
+    /// signer must be the account of the sandbox signer.
+    pub async fn execute(
+        &self,
+        builder: &TransactionBuilder,
+    ) -> Result<SandboxOutcome, Box<dyn std::error::Error>> {
+        let query_request = QueryRequest::ViewAccessKey {
+            account_id: self.signer.account_id.clone(),
+            public_key: self.signer.public_key(),
+        };
+        let response = self.provider.query(query_request).await?;
+        let QueryResponseKind::AccessKey(access_key_view) = response.kind else {
+            return Err("Unexpected response kind".into());
+        };
+
+        let mut builder = builder.clone_with_new_nonce(access_key_view.nonce + 1);
+        set_recent_block_hash(
+            &mut builder,
+            &self.provider,
+            BlockReference::Finality(Finality::Final),
+        )
+        .await?;
+        let signed_transaction = builder.sign_transaction(&self.signer);
+        let outcome = self.provider.send_transaction(signed_transaction).await?;
+
+        let outcomes =
+            std::iter::once(&outcome.transaction_outcome).chain(outcome.receipts_outcome.iter());
+        let mut result = SandboxOutcome {
+            gas_burnt: 0,
+            logs: Vec::new(),
+            return_value: None,
+            error: None,
+        };
+        for execution in outcomes {
+            result.gas_burnt += execution.outcome.gas_burnt;
+            result.logs.extend(execution.outcome.logs.iter().cloned());
+        }
+        match outcome.status {
+            FinalExecutionStatus::SuccessValue(value) => result.return_value = Some(value),
+            FinalExecutionStatus::Failure(error) => result.error = Some(error.to_string()),
+            status => result.error = Some(format!("Unexpected final status {:?}", status)),
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,248 @@
+//! A client for delegators of a staking pool contract, e.g. `core-contracts/staking-pool`.
+//!
+//! `DelegatorClient` reads a delegator's position in a pool and estimates the yield of delegating to it.
+//! Yields are estimated from how the pool's stake grew over past epochs, so deposits and withdrawals in
+//! that period distort the estimate.
+
+use crate::fungible_token::U128;
+use near_primitives::types::{AccountId, Balance, BlockId, EpochReference};
+use near_primitives::views::QueryRequest;
+use near_providers::types::query::{QueryResponseKind, RpcQueryResponse};
+use near_providers::validators::EPOCHS_PER_YEAR;
+use near_providers::Provider;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// A delegator's position in a staking pool, as returned by `get_account`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegatorAccount {
+    pub account_id: AccountId,
+    /// Balance unstaked or never staked, withdrawable once `can_withdraw` is true.
+    pub unstaked_balance: U128,
+    /// Balance currently staked, including rewards.
+    pub staked_balance: U128,
+    /// Whether the unstaked balance can be withdrawn, i.e. the unstaking period has passed.
+    pub can_withdraw: bool,
+}
+
+/// The share of rewards the pool owner keeps, as returned by `get_reward_fee_fraction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardFeeFraction {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl RewardFeeFraction {
+    /// Returns the fee as a fraction, e.g. `0.1` for 10%.
+    pub fn as_f64(&self) -> f64 {
+        if self.denominator == 0 {
+            return 0.0;
+        }
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+/// A client for the view methods of the staking pool `pool_id`.
+pub struct DelegatorClient {
+    pool_id: AccountId,
+    provider: Arc<dyn Provider>,
+}
+
+impl DelegatorClient {
+    /// Constructs a new `DelegatorClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool_id` - The account ID of the staking pool contract.
+    /// * `provider` - A provider instance for interacting with the blockchain.
+    pub fn new(pool_id: AccountId, provider: Arc<dyn Provider>) -> Self {
+        Self { pool_id, provider }
+    }
+
+    /// Fetches the position of `account_id` in the pool.
+    pub async fn get_account(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<DelegatorAccount, Box<dyn std::error::Error>> {
+        self.view("get_account", json!({ "account_id": account_id }))
+            .await
+    }
+
+    /// Fetches the share of rewards the pool owner keeps.
+    pub async fn get_reward_fee_fraction(
+        &self,
+    ) -> Result<RewardFeeFraction, Box<dyn std::error::Error>> {
+        self.view("get_reward_fee_fraction", json!({})).await
+    }
+
+    /// Estimates the annual yield of delegating to the pool, as a fraction (e.g. `0.1` for 10%).
+    ///
+    /// The pool's stake growth over the last `num_epochs` epochs gives its average reward per epoch,
+    /// from which the owner's fee is deducted and which is compounded over `EPOCHS_PER_YEAR` epochs.
+    /// Looking back more than a few epochs requires an archival node.
+    ///
+    /// # Returns
+    ///
+    /// The estimated APY, or an error if the pool is not validating in the current or the oldest epoch,
+    /// or if the chain has fewer than `num_epochs` past epochs.
+    pub async fn estimate_apy(&self, num_epochs: u32) -> Result<f64, Box<dyn std::error::Error>> {
+        let num_epochs = num_epochs.max(1);
+        let fee = self.get_reward_fee_fraction().await?;
+
+        let mut epoch_reference = EpochReference::Latest;
+        let mut stake_now = None;
+        let mut stake_then = 0;
+        for epoch in 0..=num_epochs {
+            let validators = self.provider.validators(epoch_reference).await?;
+            stake_then = validators
+                .current_validators
+                .iter()
+                .find(|validator| validator.account_id == self.pool_id)
+                .map(|validator| validator.stake)
+                .ok_or_else(|| format!("{} is not a current validator", self.pool_id))?;
+            stake_now.get_or_insert(stake_then);
+            if epoch < num_epochs {
+                let previous_epoch_height = validators
+                    .epoch_start_height
+                    .checked_sub(1)
+                    .ok_or("there is no epoch before the genesis epoch")?;
+                epoch_reference = EpochReference::BlockId(BlockId::Height(previous_epoch_height));
+            }
+        }
+        Ok(delegator_apy(
+            stake_then,
+            stake_now.unwrap_or(stake_then),
+            num_epochs,
+            &fee,
+        ))
+    }
+
+    async fn view<T: DeserializeOwned>(
+        &self,
+        method_name: &str,
+        args: Value,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        let query_request = QueryRequest::CallFunction {
+            account_id: self.pool_id.clone(),
+            method_name: method_name.to_string(),
+            args: serde_json::to_vec(&args)?.into(),
+        };
+
+        // Send the query to the NEAR blockchain
+        let response: RpcQueryResponse = self.provider.query(query_request).await?;
+
+        if let QueryResponseKind::CallResult(result) = response.kind {
+            Ok(serde_json::from_slice(&result.result)?)
+        } else {
+            Err("Unexpected response kind".into())
+        }
+    }
+}
+
+/// Annualizes the average per-epoch growth from `stake_then` to `stake_now` over `num_epochs` epochs,
+/// after deducting the pool owner's `fee`.
+fn delegator_apy(
+    stake_then: Balance,
+    stake_now: Balance,
+    num_epochs: u32,
+    fee: &RewardFeeFraction,
+) -> f64 {
+    if stake_then == 0 || num_epochs == 0 {
+        return 0.0;
+    }
+    let growth = stake_now as f64 / stake_then as f64;
+    let epoch_rate = (growth.powf(1.0 / num_epochs as f64) - 1.0).max(0.0);
+    let delegator_rate = epoch_rate * (1.0 - fee.as_f64());
+    (1.0 + delegator_rate).powf(EPOCHS_PER_YEAR) - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delegator_apy() {
+        let no_fee = RewardFeeFraction {
+            numerator: 0,
+            denominator: 100,
+        };
+        let ten_percent = RewardFeeFraction {
+            numerator: 10,
+            denominator: 100,
+        };
+        let stake = 1_000_000;
+        let grown = 1_000_200;
+
+        let expected = (1.0002f64).powf(EPOCHS_PER_YEAR) - 1.0;
+        assert!((delegator_apy(stake, grown, 1, &no_fee) - expected).abs() < 1e-9);
+
+        let expected = (1.0 + 0.0002 * 0.9f64).powf(EPOCHS_PER_YEAR) - 1.0;
+        assert!((delegator_apy(stake, grown, 1, &ten_percent) - expected).abs() < 1e-9);
+
+        let two_epochs = stake + stake / 10_000 * 2 + 1;
+        assert!(delegator_apy(stake, two_epochs, 2, &no_fee) > 0.07);
+        assert_eq!(delegator_apy(stake, stake - 1, 1, &no_fee), 0.0);
+        assert_eq!(delegator_apy(0, stake, 1, &no_fee), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_apy_genesis_epoch() {
+        use near_primitives::views::{CallResult, EpochValidatorInfo};
+        use near_providers::test_utils::MockProvider;
+
+        let provider = MockProvider::default()
+            .on_query(|_| async {
+                Ok(RpcQueryResponse {
+                    kind: QueryResponseKind::CallResult(CallResult {
+                        result: br#"{"numerator":10,"denominator":100}"#.to_vec(),
+                        logs: Vec::new(),
+                    }),
+                    block_height: 1,
+                    block_hash: Default::default(),
+                })
+            })
+            .on_validators(|_| async {
+                let validators: EpochValidatorInfo = serde_json::from_value(json!({
+                    "current_validators": [{
+                        "account_id": "pool.near",
+                        "public_key": "ed25519:11111111111111111111111111111111",
+                        "is_slashed": false,
+                        "stake": "1000000",
+                        "shards": [0],
+                        "num_produced_blocks": 1,
+                        "num_expected_blocks": 1
+                    }],
+                    "next_validators": [],
+                    "current_fishermen": [],
+                    "next_fishermen": [],
+                    "current_proposals": [],
+                    "prev_epoch_kickout": [],
+                    "epoch_start_height": 0,
+                    "epoch_height": 1
+                }))
+                .unwrap();
+                Ok(validators)
+            });
+
+        let client = DelegatorClient::new("pool.near".parse().unwrap(), Arc::new(provider));
+        assert!(client.estimate_apy(1).await.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_delegator_account() {
+        let account: DelegatorAccount = serde_json::from_str(
+            r#"{
+                "account_id": "alice.near",
+                "unstaked_balance": "5",
+                "staked_balance": "1000000000000000000000000",
+                "can_withdraw": true
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(account.unstaked_balance, U128(5));
+        assert_eq!(account.staked_balance, U128(10u128.pow(24)));
+        assert!(account.can_withdraw);
+    }
+}
@@ -0,0 +1,136 @@
+//! The `storage` module queries the NEP-145 storage balances of contracts, such as most fungible token
+//! contracts, which accounts must register with before interacting with them. The storage management
+//! calls themselves are built with `near_transactions::StorageDepositBuilder`.
+
+use near_primitives::types::AccountId;
+use near_primitives::views::QueryRequest;
+use near_providers::types::query::QueryResponseKind;
+use near_providers::Provider;
+use near_transactions::{
+    parse_storage_balance, parse_storage_balance_bounds, StorageBalance, StorageBalanceBounds,
+};
+use serde_json::{json, Value};
+
+/// Fetches the storage balance of `account_id` on `contract_id` with a `storage_balance_of` view call.
+///
+/// # Returns
+///
+/// The storage balance, `None` if the account is not registered, or an error if the view call failed.
+pub async fn get_storage_balance(
+    provider: &dyn Provider,
+    account_id: &AccountId,
+    contract_id: &AccountId,
+) -> Result<Option<StorageBalance>, Box<dyn std::error::Error>> {
+    let args = json!({ "account_id": account_id });
+    let result = view_call(provider, contract_id, "storage_balance_of", args).await?;
+    parse_storage_balance(&result)
+}
+
+/// Fetches the deposit bounds of `contract_id` with a `storage_balance_bounds` view call.
+///
+/// `min` is the deposit `StorageDepositBuilder::deposit_for` requires to register a new account.
+pub async fn get_storage_balance_bounds(
+    provider: &dyn Provider,
+    contract_id: &AccountId,
+) -> Result<StorageBalanceBounds, Box<dyn std::error::Error>> {
+    let result = view_call(provider, contract_id, "storage_balance_bounds", json!({})).await?;
+    parse_storage_balance_bounds(&result)
+}
+
+async fn view_call(
+    provider: &dyn Provider,
+    contract_id: &AccountId,
+    method_name: &str,
+    args: Value,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let query_request = QueryRequest::CallFunction {
+        account_id: contract_id.clone(),
+        method_name: method_name.to_string(),
+        args: args.to_string().into_bytes().into(),
+    };
+    let response = provider.query(query_request).await?;
+    if let QueryResponseKind::CallResult(result) = response.kind {
+        Ok(result.result)
+    } else {
+        Err("Unexpected response kind".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::views::CallResult;
+    use near_providers::test_utils::MockProvider;
+    use near_providers::types::query::RpcQueryResponse;
+    use near_transactions::NearToken;
+
+    /// Returns a provider answering `storage_balance_of` for `alice.near` only, and
+    /// `storage_balance_bounds`.
+    fn mock_provider() -> MockProvider {
+        MockProvider::default().on_query(|request| {
+            let QueryRequest::CallFunction {
+                method_name, args, ..
+            } = request
+            else {
+                panic!("unexpected request: {:?}", request)
+            };
+            let args: Value = serde_json::from_slice(&args).unwrap();
+            let result = match (method_name.as_str(), args["account_id"].as_str()) {
+                ("storage_balance_of", Some("alice.near")) => {
+                    json!({ "total": "100", "available": "40" })
+                }
+                ("storage_balance_of", _) => Value::Null,
+                ("storage_balance_bounds", None) => {
+                    json!({ "min": "1250000000000000000000", "max": null })
+                }
+                _ => panic!("unexpected request: {} {}", method_name, args),
+            };
+            async move {
+                Ok(RpcQueryResponse {
+                    kind: QueryResponseKind::CallResult(CallResult {
+                        result: result.to_string().into_bytes(),
+                        logs: Vec::new(),
+                    }),
+                    block_height: 42,
+                    block_hash: CryptoHash::default(),
+                })
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_storage_balance() {
+        let provider = mock_provider();
+        let contract_id = "token.near".parse().unwrap();
+
+        let balance = get_storage_balance(&provider, &"alice.near".parse().unwrap(), &contract_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            balance,
+            Some(StorageBalance {
+                total: NearToken::from_yoctonear(100),
+                available: NearToken::from_yoctonear(40),
+            })
+        );
+        let balance = get_storage_balance(&provider, &"bob.near".parse().unwrap(), &contract_id)
+            .await
+            .unwrap();
+        assert_eq!(balance, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_storage_balance_bounds() {
+        let bounds = get_storage_balance_bounds(&mock_provider(), &"token.near".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            bounds,
+            StorageBalanceBounds {
+                min: NearToken::from_yoctonear(1_250_000_000_000_000_000_000),
+                max: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! The `sub_account` module builds transactions creating sub accounts, checking that the new account is a
+//! direct sub account of its parent before anything is signed.
+
+use crate::access_keys::full_access_key;
+use near_crypto::PublicKey;
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::Transaction;
+use near_primitives::types::{AccountId, Nonce};
+use near_transactions::{NearToken, TransactionBuilder};
+use std::fmt;
+
+/// Error returned when an account ID is not a direct sub account of the parent account, e.g.
+/// `alice.near` for the parent `bob.near`. The protocol rejects creating such accounts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSubAccountId {
+    /// The rejected account ID.
+    pub sub_account_id: AccountId,
+    /// The account the sub account was to be created by.
+    pub parent_account: AccountId,
+}
+
+impl fmt::Display for InvalidSubAccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is not a sub account of {}",
+            self.sub_account_id, self.parent_account
+        )
+    }
+}
+
+impl std::error::Error for InvalidSubAccountId {}
+
+/// Builds transactions creating direct sub accounts of `parent_account`, such as `app.alice.near` for
+/// `alice.near`.
+#[derive(Debug, Clone)]
+pub struct SubAccountCreator {
+    parent_account: AccountId,
+    signer_public_key: PublicKey,
+}
+
+impl SubAccountCreator {
+    /// Constructs a new `SubAccountCreator`.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_account` - The account creating and funding the sub accounts.
+    /// * `signer_public_key` - The access key of `parent_account` the transactions are signed with.
+    pub fn new(parent_account: AccountId, signer_public_key: PublicKey) -> Self {
+        Self {
+            parent_account,
+            signer_public_key,
+        }
+    }
+
+    /// Returns the account creating the sub accounts.
+    pub fn parent_account(&self) -> &AccountId {
+        &self.parent_account
+    }
+
+    /// Checks that `sub_account_id` is `{name}.{parent_account}`, with `name` not containing any dot.
+    pub fn validate(&self, sub_account_id: &AccountId) -> Result<(), InvalidSubAccountId> {
+        if sub_account_id.is_sub_account_of(&self.parent_account) {
+            Ok(())
+        } else {
+            Err(InvalidSubAccountId {
+                sub_account_id: sub_account_id.clone(),
+                parent_account: self.parent_account.clone(),
+            })
+        }
+    }
+
+    /// Builds an unsigned transaction creating `sub_account_id`, funding it with `initial_balance` and
+    /// adding `public_key` as its full access key.
+    ///
+    /// # Returns
+    ///
+    /// The transaction, or an error if `sub_account_id` is not a direct sub account of the parent account.
+    pub fn build_transaction(
+        &self,
+        sub_account_id: AccountId,
+        public_key: PublicKey,
+        initial_balance: NearToken,
+        nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> Result<Transaction, InvalidSubAccountId> {
+        self.validate(&sub_account_id)?;
+        let transaction = TransactionBuilder::new(
+            self.parent_account.clone(),
+            self.signer_public_key.clone(),
+            sub_account_id,
+            nonce,
+            block_hash,
+        )
+        .create_account()
+        .transfer(initial_balance.as_yoctonear())
+        .add_key(public_key, full_access_key())
+        .build();
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::transaction::Action;
+
+    fn creator() -> SubAccountCreator {
+        SubAccountCreator::new(
+            "bob.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+        )
+    }
+
+    #[test]
+    fn test_build_transaction() {
+        let transaction = creator()
+            .build_transaction(
+                "app.bob.near".parse().unwrap(),
+                PublicKey::empty(KeyType::ED25519),
+                NearToken::from_yoctonear(1),
+                1,
+                CryptoHash::default(),
+            )
+            .unwrap();
+
+        assert_eq!(transaction.signer_id().as_str(), "bob.near");
+        assert_eq!(transaction.receiver_id().as_str(), "app.bob.near");
+        assert!(matches!(
+            transaction.actions(),
+            [
+                Action::CreateAccount(_),
+                Action::Transfer(_),
+                Action::AddKey(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_rejects_other_accounts() {
+        for account_id in ["alice.near", "bob.near", "app.sub.bob.near", "appbob.near"] {
+            let account_id: AccountId = account_id.parse().unwrap();
+            assert_eq!(
+                creator().validate(&account_id),
+                Err(InvalidSubAccountId {
+                    sub_account_id: account_id,
+                    parent_account: "bob.near".parse().unwrap(),
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! The `token_price` module fetches the NEAR/USD price from the Pyth price oracle deployed on NEAR.
+//!
+//! By default `TokenPriceClient` calls `get_price` on the Pyth contract `pyth-oracle.near` with the
+//! NEAR/USD price feed. Other deployments, e.g. `pyth-oracle.testnet` with its beta price feeds, can be
+//! used with `TokenPriceClient::with_oracle`. Oracle prices lag the market, so only use them for
+//! display, e.g. with `NearToken::to_usd`.
+
+use near_primitives::types::AccountId;
+use near_primitives::views::QueryRequest;
+use near_providers::types::query::QueryResponseKind;
+use near_providers::Provider;
+use near_transactions::{parse_pyth_price, NEAR_USD_PRICE_FEED, PYTH_ORACLE_MAINNET};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Fetches the NEAR/USD price from a Pyth price oracle contract.
+pub struct TokenPriceClient {
+    provider: Arc<dyn Provider>,
+    oracle_id: AccountId,
+    price_feed_id: String,
+}
+
+impl TokenPriceClient {
+    /// Creates a client for the NEAR/USD price feed of the mainnet Pyth oracle.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - A provider connected to mainnet.
+    pub fn new(provider: Arc<dyn Provider>) -> Self {
+        Self::with_oracle(
+            provider,
+            PYTH_ORACLE_MAINNET.parse().unwrap(),
+            NEAR_USD_PRICE_FEED,
+        )
+    }
+
+    /// Creates a client for the price feed `price_feed_id` of the Pyth oracle deployed at `oracle_id`.
+    pub fn with_oracle(
+        provider: Arc<dyn Provider>,
+        oracle_id: AccountId,
+        price_feed_id: &str,
+    ) -> Self {
+        Self {
+            provider,
+            oracle_id,
+            price_feed_id: price_feed_id.to_string(),
+        }
+    }
+
+    /// Fetches the price of one NEAR in USD.
+    ///
+    /// # Returns
+    ///
+    /// The price, or an error if the view call failed or the oracle has no recent price.
+    pub async fn get_near_usd_price(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        let args = json!({ "price_identifier": self.price_feed_id });
+        let query_request = QueryRequest::CallFunction {
+            account_id: self.oracle_id.clone(),
+            method_name: "get_price".to_string(),
+            args: args.to_string().into_bytes().into(),
+        };
+        let response = self.provider.query(query_request).await?;
+        if let QueryResponseKind::CallResult(result) = response.kind {
+            parse_pyth_price(&serde_json::from_slice(&result.result)?)
+        } else {
+            Err("Unexpected response kind".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::views::CallResult;
+    use near_providers::test_utils::MockProvider;
+    use near_providers::types::query::RpcQueryResponse;
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_get_near_usd_price() {
+        let provider = MockProvider::default().on_query(|request| {
+            let QueryRequest::CallFunction {
+                account_id,
+                method_name,
+                args,
+            } = request
+            else {
+                panic!("unexpected request: {:?}", request)
+            };
+            assert_eq!(account_id.as_str(), PYTH_ORACLE_MAINNET);
+            assert_eq!(method_name, "get_price");
+            let args: Value = serde_json::from_slice(&args).unwrap();
+            assert_eq!(args, json!({ "price_identifier": NEAR_USD_PRICE_FEED }));
+            async {
+                Ok(RpcQueryResponse {
+                    kind: QueryResponseKind::CallResult(CallResult {
+                        result:
+                            br#"{"price":"512345678","conf":"1000","expo":-8,"publish_time":1}"#
+                                .to_vec(),
+                        logs: Vec::new(),
+                    }),
+                    block_height: 42,
+                    block_hash: CryptoHash::default(),
+                })
+            }
+        });
+
+        let price = TokenPriceClient::new(Arc::new(provider))
+            .get_near_usd_price()
+            .await
+            .unwrap();
+        assert!((price - 5.12345678).abs() < 1e-9);
+    }
+}
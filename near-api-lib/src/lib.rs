@@ -19,7 +19,23 @@
 //! This crate aims to be a one-stop solution for Rust developers building on the NEAR platform,
 //! providing the necessary tools and abstractions to create robust, secure, and scalable applications.
 
+pub use near_accounts::access_keys;
+pub use near_accounts::account_manager;
 pub use near_accounts::accounts;
+pub use near_accounts::deploy;
+pub use near_accounts::events;
+pub use near_accounts::fungible_token;
+pub use near_accounts::nft;
+pub use near_accounts::nonces;
+pub use near_accounts::promises;
+pub use near_accounts::relayer;
+#[cfg(feature = "sandbox")]
+pub use near_accounts::sandbox;
+pub use near_accounts::staking;
+pub use near_accounts::storage;
+pub use near_accounts::sub_account;
+#[cfg(feature = "price-oracle")]
+pub use near_accounts::token_price;
 pub use near_accounts::Account;
 
 pub use near_providers as providers;
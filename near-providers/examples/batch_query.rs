@@ -0,0 +1,47 @@
+use near_primitives::types::AccountId;
+use near_primitives::views::{AccountView, QueryRequest};
+use near_providers::{BatchQuery, JsonRpcProvider, Provider};
+use std::time::Instant;
+
+mod utils;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+
+    let provider = JsonRpcProvider::new("https://rpc.testnet.near.org");
+
+    let account_ids: Vec<AccountId> = vec![
+        "testnet".parse()?,
+        "near-api-rs.testnet".parse()?,
+        "contract.near-api-rs.testnet".parse()?,
+    ];
+
+    // Only an illustration of the saved round trips, the timings of a single run are noisy.
+    // Sequential requests, one round trip per query
+    let start = Instant::now();
+    for account_id in &account_ids {
+        let request = QueryRequest::ViewAccount {
+            account_id: account_id.clone(),
+        };
+        provider.query(request).await?;
+    }
+    println!("Sequential: {:?}", start.elapsed());
+
+    // The same queries in a single batch request
+    let start = Instant::now();
+    let mut batch = BatchQuery::<AccountView>::new();
+    for account_id in &account_ids {
+        batch.add_view(QueryRequest::ViewAccount {
+            account_id: account_id.clone(),
+        });
+    }
+    let results = batch.execute(&provider).await?;
+    println!("Batched: {:?}", start.elapsed());
+
+    for (account_id, result) in account_ids.iter().zip(results) {
+        println!("{}: {:#?}", account_id, result);
+    }
+
+    Ok(())
+}
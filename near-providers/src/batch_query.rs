@@ -0,0 +1,156 @@
+//! The `batch_query` module allows sending several view queries to the NEAR blockchain in a single
+//! HTTP request, using the JSON RPC batch format. It also defines the requests and responses accepted
+//! by `JsonRpcProvider::batch`.
+//!
+//! Each query is still executed separately by the node, but only one round trip is paid for the whole
+//! batch, so `n` queries take about one round trip instead of `n`. The `batch_query` example prints
+//! the elapsed time of three account queries sent sequentially and as one batch. It is only an
+//! illustration, not a benchmark: the difference depends on the latency to the RPC node and on its
+//! load, and a single run against a public endpoint is noisy.
+
+use crate::error::{parse_result, BatchError};
+use crate::types::query::{RpcQueryRequest, RpcQueryResponse};
+use crate::JsonRpcProvider;
+use near_primitives::types::{BlockReference, Finality};
+use near_primitives::views::{BlockView, QueryRequest};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A request that can be sent as part of `JsonRpcProvider::batch`.
+#[derive(Debug, Clone)]
+pub enum BatchRequest {
+    /// A query at final finality, as sent by `Provider::query`.
+    Query(QueryRequest),
+    /// A block lookup, as sent by `Provider::block`.
+    Block(BlockReference),
+}
+
+/// The response to a `BatchRequest` of the same kind.
+#[derive(Debug)]
+pub enum BatchResponse {
+    /// The response to `BatchRequest::Query`.
+    Query(RpcQueryResponse),
+    /// The response to `BatchRequest::Block`.
+    Block(BlockView),
+}
+
+/// Accumulates view queries and executes them in a single batch request.
+///
+/// The result of every query is deserialized into `T`, e.g. `AccountView` for a batch of
+/// `QueryRequest::ViewAccount`, or the default `RpcQueryResponse` for queries of mixed kinds.
+pub struct BatchQuery<T = RpcQueryResponse> {
+    requests: Vec<QueryRequest>,
+    response: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for BatchQuery<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchQuery")
+            .field("requests", &self.requests)
+            .finish()
+    }
+}
+
+impl<T> Clone for BatchQuery<T> {
+    fn clone(&self) -> Self {
+        Self {
+            requests: self.requests.clone(),
+            response: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for BatchQuery<T> {
+    fn default() -> Self {
+        Self {
+            requests: Vec::new(),
+            response: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> BatchQuery<T> {
+    /// Constructs an empty `BatchQuery`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a view query to the batch, returning its index in the results of `execute`.
+    pub fn add_view(&mut self, request: QueryRequest) -> usize {
+        self.requests.push(request);
+        self.requests.len() - 1
+    }
+
+    /// Returns the number of queries in the batch.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns `true` if no queries have been added.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Sends all queries, at final finality, in one HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// The result of every query deserialized into `T`, in the order they were added, or an error if the
+    /// batch request itself failed.
+    pub async fn execute(
+        &self,
+        provider: &JsonRpcProvider,
+    ) -> Result<Vec<Result<T, BatchError>>, reqwest::Error> {
+        let calls = self
+            .requests
+            .iter()
+            .map(|request| {
+                let params = json!(RpcQueryRequest {
+                    block_reference: BlockReference::Finality(Finality::Final),
+                    request: request.clone(),
+                });
+                ("query", params)
+            })
+            .collect();
+
+        let responses = provider.call_batch(calls).await?;
+        Ok(responses
+            .into_iter()
+            .map(|response| {
+                response.and_then(|value| parse_result("query", value).map_err(BatchError::Parse))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_rpc_provider::mock_rpc_server;
+    use near_primitives::views::AccountView;
+
+    #[tokio::test]
+    async fn test_execute_deserializes_results() {
+        let (url, _server) = mock_rpc_server(
+            r#"[
+                {"jsonrpc": "2.0", "id": 1, "result": {"amount": "5", "locked": "0", "code_hash": "11111111111111111111111111111111", "storage_usage": 100, "storage_paid_at": 0, "block_height": 1, "block_hash": "11111111111111111111111111111111"}},
+                {"jsonrpc": "2.0", "id": 0, "result": {"amount": "7", "locked": "0", "code_hash": "11111111111111111111111111111111", "storage_usage": 200, "storage_paid_at": 0, "block_height": 1, "block_hash": "11111111111111111111111111111111"}}
+            ]"#,
+        )
+        .await;
+
+        let mut batch = BatchQuery::<AccountView>::new();
+        let alice = batch.add_view(QueryRequest::ViewAccount {
+            account_id: "alice.near".parse().unwrap(),
+        });
+        let bob = batch.add_view(QueryRequest::ViewAccount {
+            account_id: "bob.near".parse().unwrap(),
+        });
+        let results = batch.execute(&JsonRpcProvider::new(&url)).await.unwrap();
+
+        assert_eq!(results[alice].as_ref().unwrap().amount, 7);
+        assert_eq!(results[bob].as_ref().unwrap().storage_usage, 200);
+    }
+}
@@ -0,0 +1,46 @@
+//! An LRU cache of blocks, looked up by height or by hash.
+
+use lru::LruCache;
+use near_primitives::{hash::CryptoHash, types::BlockHeight, views::BlockView};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+/// Number of blocks cached by a `JsonRpcProvider` unless configured otherwise.
+pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 128;
+
+/// Caches up to `capacity` blocks. Every block is reachable by both its height and its hash.
+pub(crate) struct BlockCache {
+    heights: Mutex<LruCache<BlockHeight, CryptoHash>>,
+    blocks: Mutex<LruCache<CryptoHash, BlockView>>,
+}
+
+impl BlockCache {
+    /// Creates a cache holding up to `capacity` blocks, or `None` if `capacity` is zero.
+    pub(crate) fn new(capacity: usize) -> Option<Self> {
+        let capacity = NonZeroUsize::new(capacity)?;
+        Some(Self {
+            heights: Mutex::new(LruCache::new(capacity)),
+            blocks: Mutex::new(LruCache::new(capacity)),
+        })
+    }
+
+    pub(crate) fn get_by_height(&self, height: BlockHeight) -> Option<BlockView> {
+        let hash = *self.heights.lock().unwrap().get(&height)?;
+        self.get_by_hash(&hash)
+    }
+
+    pub(crate) fn get_by_hash(&self, hash: &CryptoHash) -> Option<BlockView> {
+        self.blocks.lock().unwrap().get(hash).cloned()
+    }
+
+    pub(crate) fn insert(&self, block: &BlockView) {
+        self.heights
+            .lock()
+            .unwrap()
+            .put(block.header.height, block.header.hash);
+        self.blocks
+            .lock()
+            .unwrap()
+            .put(block.header.hash, block.clone());
+    }
+}
@@ -0,0 +1,193 @@
+//! Streams a range of blocks from the NEAR blockchain in block height order.
+//!
+//! Blocks are yielded strictly by ascending height, regardless of the order in which the underlying
+//! requests complete. Note that height ordering is not finality ordering: a block near the head of the
+//! chain may be returned before it is final, and heights that were skipped by the chain never resolve
+//! to a block. Only blocks below the latest final height are guaranteed to be final.
+
+use crate::jsonrpc_client::errors::JsonRpcError;
+use crate::types::blocks::RpcBlockError;
+use crate::{Provider, RetryPolicy};
+use futures::stream::{self, Stream, StreamExt};
+use near_primitives::types::{BlockHeight, BlockId, BlockReference};
+use near_primitives::views::BlockView;
+use std::sync::Arc;
+
+/// Options controlling how `block_stream` fetches blocks.
+#[derive(Debug, Clone)]
+pub struct BlockStreamOptions {
+    /// Maximum number of block requests in flight at once.
+    pub max_concurrency: usize,
+    /// How often and how long to wait before a missing block is fetched again.
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for BlockStreamOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 4,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+/// Returns a stream of the blocks from height `start` to `end` (inclusive), in height order.
+///
+/// Up to `max_concurrency` blocks are fetched ahead of the consumer. The stream is lazy, so a consumer
+/// that stops polling pauses fetching once that window is full and resumes it when polled again.
+/// Blocks the node does not know about yet are retried according to the `retry_policy`; a height that
+/// is still unknown after its last attempt (e.g. a skipped height) yields the error and the stream
+/// moves on to the next height.
+///
+/// Blocks are ordered by height, not by finality: blocks above the latest final height may be yielded
+/// before they are final, so keep `end` at or below it if only final blocks should be returned.
+///
+/// # Arguments
+///
+/// * `provider` - The provider through which to fetch the blocks.
+/// * `start` - The height of the first block in the range.
+/// * `end` - The height of the last block in the range.
+/// * `options` - Concurrency and retry settings.
+pub fn block_stream(
+    provider: Arc<dyn Provider>,
+    start: BlockHeight,
+    end: BlockHeight,
+    options: BlockStreamOptions,
+) -> impl Stream<Item = Result<BlockView, JsonRpcError<RpcBlockError>>> {
+    let max_concurrency = options.max_concurrency.max(1);
+    stream::iter(start..=end)
+        .map(move |height| fetch_block_with_retry(provider.clone(), height, options.clone()))
+        .buffered(max_concurrency)
+}
+
+async fn fetch_block_with_retry(
+    provider: Arc<dyn Provider>,
+    height: BlockHeight,
+    options: BlockStreamOptions,
+) -> Result<BlockView, JsonRpcError<RpcBlockError>> {
+    options
+        .retry_policy
+        .retry(
+            || provider.block(BlockReference::BlockId(BlockId::Height(height))),
+            is_unknown_block,
+        )
+        .await
+}
+
+fn is_unknown_block(err: &JsonRpcError<RpcBlockError>) -> bool {
+    matches!(
+        err.handler_error(),
+        Some(RpcBlockError::UnknownBlock { .. })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc_client::errors::JsonRpcServerError;
+    use crate::test_utils::{block, MockProvider};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    fn height(reference: BlockReference) -> BlockHeight {
+        match reference {
+            BlockReference::BlockId(BlockId::Height(height)) => height,
+            reference => panic!("Unexpected block reference {:?}", reference),
+        }
+    }
+
+    fn unknown_block() -> JsonRpcError<RpcBlockError> {
+        JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+            RpcBlockError::UnknownBlock {
+                error_message: "DB Not Found Error".to_string(),
+            },
+        ))
+    }
+
+    fn options(max_concurrency: usize, max_attempts: u32) -> BlockStreamOptions {
+        BlockStreamOptions {
+            max_concurrency,
+            retry_policy: RetryPolicy {
+                max_attempts,
+                base_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(10),
+                jitter: 0.0,
+            },
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_block_stream_orders_by_height() {
+        // Higher blocks are answered first
+        let provider = MockProvider::default().on_block(|reference| {
+            let height = height(reference);
+            async move {
+                tokio::time::sleep(Duration::from_millis(100 - height)).await;
+                Ok(block(height))
+            }
+        });
+
+        let heights: Vec<BlockHeight> = block_stream(Arc::new(provider), 10, 20, options(4, 1))
+            .map(|block| block.unwrap().header.height)
+            .collect()
+            .await;
+        assert_eq!(heights, (10..=20).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_block_stream_retries_missing_blocks() {
+        // Height 2 is produced after the first attempt, height 3 is skipped by the chain
+        let attempts = Arc::new(Mutex::new(HashMap::<BlockHeight, u32>::new()));
+        let counted = attempts.clone();
+        let provider = MockProvider::default().on_block(move |reference| {
+            let height = height(reference);
+            let mut attempts = counted.lock().unwrap();
+            let attempt = attempts.entry(height).or_default();
+            *attempt += 1;
+            let result = match (height, *attempt) {
+                (2, 1) | (3, _) => Err(unknown_block()),
+                _ => Ok(block(height)),
+            };
+            async move { result }
+        });
+
+        let results: Vec<_> = block_stream(Arc::new(provider), 1, 4, options(2, 3))
+            .collect()
+            .await;
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[1].as_ref().unwrap().header.height, 2);
+        assert!(is_unknown_block(results[2].as_ref().unwrap_err()));
+        assert_eq!(results[3].as_ref().unwrap().header.height, 4);
+
+        let attempts = attempts.lock().unwrap();
+        assert_eq!(attempts[&1], 1);
+        assert_eq!(attempts[&2], 2);
+        assert_eq!(attempts[&3], 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_block_stream_limits_concurrency() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let (current, max) = (in_flight.clone(), max_in_flight.clone());
+        let provider = MockProvider::default().on_block(move |reference| {
+            let height = height(reference);
+            let (current, max) = (current.clone(), max.clone());
+            async move {
+                let running = current.fetch_add(1, Ordering::SeqCst) + 1;
+                max.fetch_max(running, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                current.fetch_sub(1, Ordering::SeqCst);
+                Ok(block(height))
+            }
+        });
+
+        let count = block_stream(Arc::new(provider), 1, 10, options(3, 1))
+            .count()
+            .await;
+        assert_eq!(count, 10);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+    }
+}
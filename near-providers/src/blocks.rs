@@ -0,0 +1,107 @@
+//! Convenience functions around the `block` JSON RPC method.
+
+use crate::jsonrpc_client::errors::JsonRpcError;
+use crate::types::blocks::RpcBlockError;
+use crate::Provider;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::{BlockHeight, BlockReference, Finality};
+
+/// The number of blocks after its referenced block during which a transaction is accepted, the
+/// protocol's `transaction_validity_period` (roughly a day of mainnet blocks).
+pub const TRANSACTION_VALIDITY_PERIOD: BlockHeight = 86_400;
+
+/// Fetches the hash and height of the latest final block with a single `block` query.
+///
+/// The hash is what a transaction references, the height is what the `max_block_height` of a delegate
+/// action is computed from.
+pub async fn latest_block(
+    provider: &dyn Provider,
+) -> Result<(CryptoHash, BlockHeight), JsonRpcError<RpcBlockError>> {
+    let block = provider
+        .block(BlockReference::Finality(Finality::Final))
+        .await?;
+    Ok((block.header.hash, block.header.height))
+}
+
+/// Estimates the height after which a block hash fetched at `set_at_height` is too old to be referenced
+/// by a transaction, so senders can decide whether to refresh it before signing.
+pub fn block_hash_expiry_estimate(set_at_height: BlockHeight) -> BlockHeight {
+    set_at_height.saturating_add(TRANSACTION_VALIDITY_PERIOD)
+}
+
+#[cfg(test)]
+#[test]
+fn test_block_hash_expiry_estimate() {
+    assert_eq!(block_hash_expiry_estimate(0), 86_400);
+    assert_eq!(block_hash_expiry_estimate(100_000_000), 100_086_400);
+    assert_eq!(
+        block_hash_expiry_estimate(BlockHeight::MAX),
+        BlockHeight::MAX
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_latest_block() {
+    use crate::json_rpc_provider::mock_rpc_server;
+    use crate::JsonRpcProvider;
+
+    let (url, server) = mock_rpc_server(
+        r#"{
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "result": {
+                "author": "node0",
+                "header": {
+                    "height": 42,
+                    "prev_height": 41,
+                    "epoch_id": "11111111111111111111111111111111",
+                    "next_epoch_id": "11111111111111111111111111111111",
+                    "hash": "7tkzFg8RHBmMw1ncRJZCCZAizgq4rwCftTKYLce8RU8t",
+                    "prev_hash": "11111111111111111111111111111111",
+                    "prev_state_root": "11111111111111111111111111111111",
+                    "block_body_hash": null,
+                    "chunk_receipts_root": "11111111111111111111111111111111",
+                    "chunk_headers_root": "11111111111111111111111111111111",
+                    "chunk_tx_root": "11111111111111111111111111111111",
+                    "outcome_root": "11111111111111111111111111111111",
+                    "chunks_included": 0,
+                    "challenges_root": "11111111111111111111111111111111",
+                    "timestamp": 1700000000000000000,
+                    "timestamp_nanosec": "1700000000000000000",
+                    "random_value": "11111111111111111111111111111111",
+                    "validator_proposals": [],
+                    "chunk_mask": [],
+                    "gas_price": "100000000",
+                    "block_ordinal": null,
+                    "rent_paid": "0",
+                    "validator_reward": "0",
+                    "total_supply": "0",
+                    "challenges_result": [],
+                    "last_final_block": "11111111111111111111111111111111",
+                    "last_ds_final_block": "11111111111111111111111111111111",
+                    "next_bp_hash": "11111111111111111111111111111111",
+                    "block_merkle_root": "11111111111111111111111111111111",
+                    "epoch_sync_data_hash": null,
+                    "approvals": [],
+                    "signature": "ed25519:1111111111111111111111111111111111111111111111111111111111111111",
+                    "latest_protocol_version": 64
+                },
+                "chunks": []
+            }
+        }"#,
+    )
+    .await;
+
+    let provider = JsonRpcProvider::new(&url);
+    let (hash, height) = latest_block(&provider).await.unwrap();
+
+    assert_eq!(
+        hash,
+        "7tkzFg8RHBmMw1ncRJZCCZAizgq4rwCftTKYLce8RU8t"
+            .parse()
+            .unwrap()
+    );
+    assert_eq!(height, 42);
+    assert!(server.await.unwrap().contains(r#""finality":"final""#));
+}
@@ -0,0 +1,92 @@
+//! Broadcasting many signed transactions at once, e.g. for airdrops or migrations.
+
+use crate::jsonrpc_client::errors::JsonRpcError;
+use crate::types::transactions::RpcTransactionError;
+use crate::Provider;
+use futures::stream::{self, StreamExt};
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::views::FinalExecutionOutcomeView;
+
+/// Sends every transaction of `txs`, waiting for its final execution outcome, with at most `concurrency`
+/// transactions in flight at once so the node isn't overwhelmed.
+///
+/// A failed transaction doesn't stop the others from being sent. Transactions of the same access key
+/// must be sent in nonce order, so only give them a `concurrency` of 1 or split them into separate
+/// calls.
+///
+/// # Returns
+///
+/// The result of each transaction, at the index of the transaction in `txs`.
+pub async fn broadcast_all(
+    txs: Vec<SignedTransaction>,
+    provider: &dyn Provider,
+    concurrency: usize,
+) -> Vec<Result<FinalExecutionOutcomeView, JsonRpcError<RpcTransactionError>>> {
+    stream::iter(txs)
+        .map(|tx| provider.send_transaction(tx))
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::jsonrpc_client::errors::JsonRpcServerError;
+    use crate::test_utils::{outcome, signed_transaction, MockProvider};
+    use crate::types::transactions::TransactionInfo;
+    use std::time::Duration;
+
+    /// Returns a provider failing every transaction whose nonce is a multiple of 3, answering later
+    /// transactions sooner so results complete out of order. It never knows the status of a transaction.
+    pub(crate) fn flaky_provider() -> MockProvider {
+        MockProvider::default()
+            .on_send_transaction(|signed_transaction| async move {
+                let nonce = signed_transaction.transaction.nonce;
+                tokio::time::sleep(Duration::from_millis(10 - nonce)).await;
+                if nonce % 3 == 0 {
+                    return Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                        RpcTransactionError::TimeoutError,
+                    )));
+                }
+                Ok(outcome(nonce))
+            })
+            .on_send_transaction_async(|signed_transaction| async move {
+                let nonce = signed_transaction.transaction.nonce;
+                tokio::time::sleep(Duration::from_millis(10 - nonce)).await;
+                if nonce % 3 == 0 {
+                    return Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                        (),
+                    )));
+                }
+                Ok(signed_transaction.get_hash())
+            })
+            .on_tx_status(|transaction_info, _| async move {
+                let TransactionInfo::TransactionId { tx_hash, .. } = transaction_info else {
+                    panic!("unexpected request: {:?}", transaction_info)
+                };
+                Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                    RpcTransactionError::UnknownTransaction {
+                        requested_transaction_hash: tx_hash,
+                    },
+                )))
+            })
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_all_keeps_order() {
+        let txs = (0..10).map(signed_transaction).collect();
+
+        let results = broadcast_all(txs, &flaky_provider(), 4).await;
+        assert_eq!(results.len(), 10);
+        for (nonce, result) in results.iter().enumerate() {
+            match result {
+                Ok(outcome) => {
+                    assert_ne!(nonce % 3, 0);
+                    assert_eq!(outcome.transaction.nonce, nonce as u64);
+                }
+                Err(_) => assert_eq!(nonce % 3, 0),
+            }
+        }
+    }
+}
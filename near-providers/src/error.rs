@@ -0,0 +1,126 @@
+//! Error types of the requests sent by `JsonRpcProvider` outside of `near-jsonrpc-client`.
+
+use crate::jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use crate::jsonrpc_primitives::errors::RpcError;
+use crate::types::blocks::RpcBlockError;
+use crate::types::chunks::RpcChunkError;
+use crate::types::receipts::RpcReceiptError;
+use near_primitives::hash::CryptoHash;
+use serde_json::Value;
+use std::fmt;
+
+/// A response in a batch request, sent by `JsonRpcProvider::batch` or `BatchQuery::execute`, does not
+/// have the expected shape, e.g. because the node runs an older version lacking a field.
+///
+/// Single calls are parsed by `near-jsonrpc-client`, which reports such responses as its own
+/// `JsonRpcError` instead.
+#[derive(Debug)]
+pub struct RpcParseError {
+    /// The JSON RPC method that was called.
+    pub method: String,
+    /// The unexpected response, so it can be inspected without fetching it again.
+    pub raw: Value,
+    /// Why the response could not be parsed.
+    pub source: serde_json::Error,
+}
+
+impl fmt::Display for RpcParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to parse the response to `{}`: {}",
+            self.method, self.source
+        )
+    }
+}
+
+impl std::error::Error for RpcParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The reason a single request of a batch failed.
+#[derive(Debug)]
+pub enum BatchError {
+    /// The node returned an error for the request.
+    Rpc(RpcError),
+    /// The node returned a result that could not be parsed.
+    Parse(RpcParseError),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Rpc(error) => write!(f, "RPC error: {:?}", error),
+            BatchError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchError::Rpc(_) => None,
+            BatchError::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl From<RpcError> for BatchError {
+    fn from(error: RpcError) -> Self {
+        BatchError::Rpc(error)
+    }
+}
+
+/// Parses `raw`, the result of a call to `method`, keeping the raw value on failure.
+pub(crate) fn parse_result<T: serde::de::DeserializeOwned>(
+    method: &str,
+    raw: Value,
+) -> Result<T, RpcParseError> {
+    serde_json::from_value(raw.clone()).map_err(|source| RpcParseError {
+        method: method.to_string(),
+        raw,
+        source,
+    })
+}
+
+/// Errors that can occur while fetching receipts.
+#[derive(Debug)]
+pub enum ReceiptError {
+    /// The node does not know the receipt, e.g. because it is not an archival node and the receipt
+    /// was garbage collected.
+    ReceiptNotFound(CryptoHash),
+    /// The receipt could not be fetched.
+    Receipt(JsonRpcError<RpcReceiptError>),
+    /// The block containing the receipts could not be fetched.
+    Block(JsonRpcError<RpcBlockError>),
+    /// A chunk of the block could not be fetched.
+    Chunk(JsonRpcError<RpcChunkError>),
+}
+
+impl fmt::Display for ReceiptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReceiptError::ReceiptNotFound(receipt_id) => {
+                write!(f, "Receipt {} not found", receipt_id)
+            }
+            ReceiptError::Receipt(error) => write!(f, "Failed to fetch receipt: {}", error),
+            ReceiptError::Block(error) => write!(f, "Failed to fetch block: {}", error),
+            ReceiptError::Chunk(error) => write!(f, "Failed to fetch chunk: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ReceiptError {}
+
+impl From<JsonRpcError<RpcReceiptError>> for ReceiptError {
+    fn from(error: JsonRpcError<RpcReceiptError>) -> Self {
+        match error {
+            JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                RpcReceiptError::UnknownReceipt { receipt_id },
+            )) => ReceiptError::ReceiptNotFound(receipt_id),
+            error => ReceiptError::Receipt(error),
+        }
+    }
+}
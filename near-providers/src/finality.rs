@@ -0,0 +1,247 @@
+//! Waiting for a sent transaction to reach a chosen finality, e.g. proceeding optimistically once it is
+//! included in a block and confirming it is final later.
+
+use crate::jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use crate::types::transactions::{RpcTransactionError, TransactionInfo};
+use crate::Provider;
+use near_primitives::errors::TxExecutionError;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::AccountId;
+use near_primitives::views::{FinalExecutionOutcomeView, FinalExecutionStatus, TxExecutionStatus};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How long to wait before asking again for the status of a transaction that hasn't reached the
+/// requested finality yet.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The reason waiting for the finality of a transaction failed.
+#[derive(Debug)]
+pub enum FinalityError {
+    /// The transaction did not reach the requested finality before the timeout. It may still do so later.
+    Timeout,
+    /// The transaction was executed and failed.
+    Failed(TxExecutionError),
+    /// The status of the transaction could not be fetched.
+    Rpc(JsonRpcError<RpcTransactionError>),
+}
+
+impl fmt::Display for FinalityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FinalityError::Timeout => {
+                write!(f, "Timed out waiting for the transaction to reach finality")
+            }
+            FinalityError::Failed(error) => write!(f, "Transaction failed: {}", error),
+            FinalityError::Rpc(error) => {
+                write!(f, "Failed to fetch transaction status: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FinalityError {}
+
+/// Polls the status of the transaction `tx_hash` sent by `sender_id` until it reaches `finality`, or
+/// until `timeout` has passed.
+///
+/// Transactions the node doesn't know yet, e.g. because they are still being forwarded, are polled again
+/// until the timeout.
+///
+/// # Returns
+///
+/// The final execution outcome of the transaction, or `None` if `finality` doesn't require the
+/// transaction to be executed (`None`, `Included` and `IncludedFinal`) and the node didn't return an
+/// outcome yet. Returns `FinalityError::Failed` if the outcome shows the transaction failed.
+pub async fn await_finality(
+    tx_hash: CryptoHash,
+    sender_id: AccountId,
+    provider: &dyn Provider,
+    finality: TxExecutionStatus,
+    timeout: Duration,
+) -> Result<Option<FinalExecutionOutcomeView>, FinalityError> {
+    let deadline = Instant::now() + timeout;
+    let transaction_info = TransactionInfo::TransactionId {
+        tx_hash,
+        sender_account_id: sender_id,
+    };
+    loop {
+        let status = tokio::time::timeout_at(
+            deadline,
+            provider.tx_status(transaction_info.clone(), finality.clone()),
+        )
+        .await
+        .map_err(|_| FinalityError::Timeout)?;
+        match status {
+            Ok(response) if has_reached(&response.final_execution_status, &finality) => {
+                let outcome = response
+                    .final_execution_outcome
+                    .map(|outcome| outcome.into_outcome());
+                if let Some(FinalExecutionStatus::Failure(error)) =
+                    outcome.as_ref().map(|outcome| &outcome.status)
+                {
+                    return Err(FinalityError::Failed(error.clone()));
+                }
+                return Ok(outcome);
+            }
+            Ok(_)
+            | Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                RpcTransactionError::UnknownTransaction { .. } | RpcTransactionError::TimeoutError,
+            ))) => {}
+            Err(error) => return Err(FinalityError::Rpc(error)),
+        }
+        if Instant::now() + POLL_INTERVAL >= deadline {
+            return Err(FinalityError::Timeout);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Returns true if a transaction with status `status` is at least as final as `finality`.
+///
+/// Statuses are not totally ordered: `ExecutedOptimistic` transactions are executed but not necessarily
+/// in a final block, `IncludedFinal` ones are in a final block but not necessarily executed.
+fn has_reached(status: &TxExecutionStatus, finality: &TxExecutionStatus) -> bool {
+    match finality {
+        TxExecutionStatus::None => true,
+        TxExecutionStatus::Included => *status != TxExecutionStatus::None,
+        TxExecutionStatus::ExecutedOptimistic => matches!(
+            status,
+            TxExecutionStatus::ExecutedOptimistic
+                | TxExecutionStatus::Executed
+                | TxExecutionStatus::Final
+        ),
+        TxExecutionStatus::IncludedFinal => matches!(
+            status,
+            TxExecutionStatus::IncludedFinal
+                | TxExecutionStatus::Executed
+                | TxExecutionStatus::Final
+        ),
+        TxExecutionStatus::Executed => {
+            matches!(
+                status,
+                TxExecutionStatus::Executed | TxExecutionStatus::Final
+            )
+        }
+        TxExecutionStatus::Final => *status == TxExecutionStatus::Final,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc_client::methods::tx::RpcTransactionResponse;
+    use crate::test_utils::{outcome, MockProvider};
+    use near_primitives::errors::InvalidTxError;
+    use near_primitives::views::FinalExecutionOutcomeViewEnum;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    /// Returns a provider answering each status request with the next of `statuses`, regardless of the
+    /// requested finality, as a node does when its own timeout expires. Once all statuses are used up,
+    /// it doesn't know the transaction. Also returns the finality of every request it answered.
+    fn staged_provider(
+        statuses: Vec<TxExecutionStatus>,
+        outcome: FinalExecutionOutcomeView,
+    ) -> (MockProvider, Arc<Mutex<Vec<TxExecutionStatus>>>) {
+        let statuses = Mutex::new(VecDeque::from(statuses));
+        let requests = Arc::new(Mutex::default());
+        let recorded = requests.clone();
+        let provider = MockProvider::default().on_tx_status(move |_, wait_until| {
+            recorded.lock().unwrap().push(wait_until);
+            let result = match statuses.lock().unwrap().pop_front() {
+                Some(status) => {
+                    let executed = matches!(
+                        status,
+                        TxExecutionStatus::ExecutedOptimistic
+                            | TxExecutionStatus::Executed
+                            | TxExecutionStatus::Final
+                    );
+                    Ok(RpcTransactionResponse {
+                        final_execution_outcome: executed.then(|| {
+                            FinalExecutionOutcomeViewEnum::FinalExecutionOutcome(outcome.clone())
+                        }),
+                        final_execution_status: status,
+                    })
+                }
+                None => Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                    RpcTransactionError::UnknownTransaction {
+                        requested_transaction_hash: CryptoHash::default(),
+                    },
+                ))),
+            };
+            async move { result }
+        });
+        (provider, requests)
+    }
+
+    async fn await_with(
+        provider: &MockProvider,
+        finality: TxExecutionStatus,
+    ) -> Result<Option<FinalExecutionOutcomeView>, FinalityError> {
+        await_finality(
+            CryptoHash::default(),
+            "alice.near".parse().unwrap(),
+            provider,
+            finality,
+            Duration::from_secs(5),
+        )
+        .await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_await_included() {
+        let (provider, requests) = staged_provider(
+            vec![
+                TxExecutionStatus::None,
+                TxExecutionStatus::Included,
+                TxExecutionStatus::Final,
+            ],
+            outcome(1),
+        );
+
+        let result = await_with(&provider, TxExecutionStatus::Included).await;
+        assert!(matches!(result, Ok(None)));
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![TxExecutionStatus::Included; 2]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_await_final() {
+        let (provider, requests) = staged_provider(
+            vec![
+                TxExecutionStatus::Included,
+                TxExecutionStatus::IncludedFinal,
+                TxExecutionStatus::ExecutedOptimistic,
+                TxExecutionStatus::Final,
+            ],
+            outcome(1),
+        );
+
+        let result = await_with(&provider, TxExecutionStatus::Final).await;
+        assert_eq!(result.unwrap(), Some(outcome(1)));
+        assert_eq!(requests.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_await_times_out() {
+        let (provider, _) = staged_provider(vec![TxExecutionStatus::Included], outcome(1));
+
+        let result = await_with(&provider, TxExecutionStatus::Final).await;
+        assert!(matches!(result, Err(FinalityError::Timeout)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_await_failed() {
+        let error = TxExecutionError::InvalidTxError(InvalidTxError::InvalidSignature);
+        let mut failed = outcome(1);
+        failed.status = FinalExecutionStatus::Failure(error.clone());
+        let (provider, _) = staged_provider(vec![TxExecutionStatus::Final], failed);
+
+        let result = await_with(&provider, TxExecutionStatus::Final).await;
+        assert!(matches!(result, Err(FinalityError::Failed(failed)) if failed == error));
+    }
+}
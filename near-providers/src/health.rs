@@ -0,0 +1,88 @@
+//! Health of a NEAR node beyond a simple ping, e.g. for load balancers choosing between RPC nodes.
+
+use crate::jsonrpc_client::errors::JsonRpcError;
+use crate::jsonrpc_client::methods::status::RpcStatusResponse;
+use crate::types::status::RpcStatusError;
+use crate::Provider;
+use chrono::{DateTime, Utc};
+use near_crypto::PublicKey;
+use near_primitives::types::{AccountId, BlockHeight};
+
+/// The health information of a node, see `HealthCheck::check`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthStatus {
+    /// Whether the node is still catching up with the chain.
+    pub syncing: bool,
+    /// The height of the latest block the node knows.
+    pub latest_block_height: BlockHeight,
+    /// The time the latest block was produced.
+    pub latest_block_time: DateTime<Utc>,
+    /// The account of the validator running the node, if it is a validator.
+    pub validator_account_id: Option<AccountId>,
+    /// The protocol version the node is running.
+    pub protocol_version: u32,
+    /// The public key identifying the node in the network.
+    pub node_key: PublicKey,
+    /// How many seconds the latest block was produced before the health check.
+    pub lag_seconds: f64,
+}
+
+impl HealthStatus {
+    /// Reads the health information of `status`, computing the lag relative to `now`.
+    fn from_status(status: RpcStatusResponse, now: DateTime<Utc>) -> Self {
+        let latest_block_time = status.sync_info.latest_block_time;
+        Self {
+            syncing: status.sync_info.syncing,
+            latest_block_height: status.sync_info.latest_block_height,
+            latest_block_time,
+            validator_account_id: status.validator_account_id,
+            protocol_version: status.protocol_version,
+            node_key: status.node_public_key,
+            lag_seconds: (now - latest_block_time).num_milliseconds() as f64 / 1000.0,
+        }
+    }
+
+    /// Returns true if the node is not syncing and its latest block is at most `max_lag_seconds` old.
+    pub fn is_healthy(&self, max_lag_seconds: f64) -> bool {
+        !self.syncing && self.lag_seconds <= max_lag_seconds
+    }
+}
+
+/// Checks the health of a node.
+pub struct HealthCheck;
+
+impl HealthCheck {
+    /// Fetches the status of the node behind `provider` and summarizes its health.
+    pub async fn check(
+        provider: &dyn Provider,
+    ) -> Result<HealthStatus, JsonRpcError<RpcStatusError>> {
+        let status = provider.status().await?;
+        Ok(HealthStatus::from_status(status, Utc::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use near_crypto::KeyType;
+
+    fn health_status(syncing: bool, lag_seconds: f64) -> HealthStatus {
+        HealthStatus {
+            syncing,
+            latest_block_height: 42,
+            latest_block_time: Utc::now() - Duration::milliseconds((lag_seconds * 1000.0) as i64),
+            validator_account_id: None,
+            protocol_version: 64,
+            node_key: PublicKey::empty(KeyType::ED25519),
+            lag_seconds,
+        }
+    }
+
+    #[test]
+    fn test_is_healthy() {
+        assert!(health_status(false, 1.5).is_healthy(5.0));
+        assert!(!health_status(false, 12.0).is_healthy(5.0));
+        assert!(!health_status(true, 1.5).is_healthy(5.0));
+    }
+}
@@ -1,45 +1,456 @@
 //! The `JsonRpcProvider` module offers a concrete implementation of the `Provider` trait, utilizing JSON RPC to communicate with the NEAR blockchain.
 //! This provider enables applications to query blockchain status, submit transactions, and fetch various blockchain data in an asynchronous manner.
 
+use crate::batch_query::{BatchRequest, BatchResponse};
+use crate::block_cache::{BlockCache, DEFAULT_BLOCK_CACHE_SIZE};
+use crate::error::{parse_result, BatchError, ReceiptError};
 use crate::jsonrpc_client::{
     errors::JsonRpcError,
-    methods::{self, status::RpcStatusResponse},
-    JsonRpcClient,
+    methods::{self, status::RpcStatusResponse, RpcMethod},
+    JsonRpcClient, MethodCallResult,
 };
+use crate::jsonrpc_primitives::errors::RpcError;
+use crate::state_changes::StateChangesRequest;
 use crate::types::{
-    blocks::RpcBlockError,
+    blocks::{RpcBlockError, RpcBlockRequest},
+    changes::RpcStateChangesError,
     chunks::{ChunkReference, RpcChunkError},
     config::RpcProtocolConfigError,
     query::{RpcQueryError, RpcQueryRequest, RpcQueryResponse},
+    receipts::ReceiptReference,
     status::RpcStatusError,
     transactions::{RpcTransactionError, TransactionInfo},
     validator::RpcValidatorError,
 };
-use crate::Provider;
+use crate::{
+    block_stream, BlockStreamOptions, JsonRpcProviderBuilder, Provider, RetryPolicy, RpcMiddleware,
+};
 use async_trait::async_trait;
+use futures::Stream;
 use near_chain_configs::ProtocolConfigView;
 use near_jsonrpc_client::methods::tx::RpcTransactionResponse;
 use near_primitives::{
     hash::CryptoHash,
     transaction::SignedTransaction,
-    types::{BlockReference, EpochReference, Finality},
+    types::{AccountId, BlockHeight, BlockId, BlockReference, EpochReference, Finality, StoreKey},
     views::{
         BlockView, ChunkView, EpochValidatorInfo, FinalExecutionOutcomeView, QueryRequest,
+        ReceiptView, StateChangeKindView, StateChangesRequestView, StateChangesView,
         TxExecutionStatus,
     },
 };
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Configuration of the HTTP requests sent by a `JsonRpcProvider`, e.g. for paid RPC endpoints.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderConfig {
+    /// Headers attached to every request, such as an API key.
+    pub headers: HashMap<String, String>,
+    /// Timeout applied to every request. No timeout when `None`.
+    pub timeout: Option<Duration>,
+}
+
+impl ProviderConfig {
+    /// Adds an `Authorization: Bearer <token>` header to the configuration.
+    pub fn bearer_token(mut self, token: &str) -> Self {
+        self.headers
+            .insert("Authorization".to_string(), format!("Bearer {}", token));
+        self
+    }
+}
 
 /// Represents a provider that uses JSON RPC to interact with the NEAR blockchain.
 pub struct JsonRpcProvider {
     client: JsonRpcClient,
+    http_client: reqwest::Client,
+    rpc_endpoint: String,
+    retry_policy: RetryPolicy,
+    middleware: Vec<Arc<dyn RpcMiddleware>>,
+    block_cache: Option<BlockCache>,
 }
 
 impl JsonRpcProvider {
     /// Constructs a new `JsonRpcProvider` with the specified RPC endpoint.
     pub fn new(rpc_endpoint: &str) -> Self {
+        Self::from_parts(
+            rpc_endpoint,
+            reqwest::Client::new(),
+            RetryPolicy::no_retry(),
+            Vec::new(),
+            DEFAULT_BLOCK_CACHE_SIZE,
+        )
+    }
+
+    /// Constructs a new `JsonRpcProvider` whose requests carry the headers and timeout of `config`.
+    ///
+    /// Use `JsonRpcProviderBuilder` for further options.
+    ///
+    /// # Returns
+    ///
+    /// A new `JsonRpcProvider`, or an error if a configured header is not a valid HTTP header.
+    pub fn with_config(
+        rpc_endpoint: &str,
+        config: ProviderConfig,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut builder = JsonRpcProviderBuilder::new(rpc_endpoint);
+        for (name, value) in &config.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build()
+    }
+
+    pub(crate) fn from_parts(
+        rpc_endpoint: &str,
+        http_client: reqwest::Client,
+        retry_policy: RetryPolicy,
+        middleware: Vec<Arc<dyn RpcMiddleware>>,
+        block_cache_size: usize,
+    ) -> Self {
         Self {
-            client: JsonRpcClient::connect(rpc_endpoint),
+            client: JsonRpcClient::with(http_client.clone()).connect(rpc_endpoint),
+            http_client,
+            rpc_endpoint: rpc_endpoint.to_string(),
+            retry_policy,
+            middleware,
+            block_cache: BlockCache::new(block_cache_size),
+        }
+    }
+
+    /// Calls a JSON RPC method, retrying transport failures according to the retry policy and
+    /// notifying the middleware around every attempt.
+    async fn call<M: RpcMethod>(&self, method: M) -> MethodCallResult<M::Response, M::Error> {
+        let method = &method;
+        self.retry_policy
+            .retry(
+                move || async move {
+                    for middleware in &self.middleware {
+                        middleware.on_request(method.method_name());
+                    }
+                    let started = Instant::now();
+                    let result = self.client.call(method).await;
+                    for middleware in &self.middleware {
+                        middleware.on_response(
+                            method.method_name(),
+                            result.is_ok(),
+                            started.elapsed(),
+                        );
+                    }
+                    result
+                },
+                |err| matches!(err, JsonRpcError::TransportError(_)),
+            )
+            .await
+    }
+
+    /// Retrieves the block at `height`, serving it from the block cache when possible.
+    ///
+    /// Only blocks at or below the last final block should be looked up by height, since a more
+    /// recent height may still be taken by a different block.
+    pub async fn get_block_by_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<BlockView, JsonRpcError<RpcBlockError>> {
+        if let Some(block) = self
+            .block_cache
+            .as_ref()
+            .and_then(|cache| cache.get_by_height(height))
+        {
+            return Ok(block);
+        }
+        self.fetch_block(BlockId::Height(height)).await
+    }
+
+    /// Retrieves the block with the given `hash`, serving it from the block cache when possible.
+    pub async fn get_block_by_hash(
+        &self,
+        hash: CryptoHash,
+    ) -> Result<BlockView, JsonRpcError<RpcBlockError>> {
+        if let Some(block) = self
+            .block_cache
+            .as_ref()
+            .and_then(|cache| cache.get_by_hash(&hash))
+        {
+            return Ok(block);
+        }
+        self.fetch_block(BlockId::Hash(hash)).await
+    }
+
+    /// Returns a stream of the blocks from height `start` to `end` (inclusive), in height order.
+    ///
+    /// Blocks are ordered by height, not by finality: blocks above the latest final height may be
+    /// yielded before they are final. See `block_stream` for how missing blocks are retried and how
+    /// `options` bounds the requests in flight.
+    pub fn get_block_stream(
+        self: Arc<Self>,
+        start: BlockHeight,
+        end: BlockHeight,
+        options: BlockStreamOptions,
+    ) -> impl Stream<Item = Result<BlockView, JsonRpcError<RpcBlockError>>> {
+        block_stream(self, start, end, options)
+    }
+
+    /// Fetches a block from the node and adds it to the block cache.
+    async fn fetch_block(
+        &self,
+        block_id: BlockId,
+    ) -> Result<BlockView, JsonRpcError<RpcBlockError>> {
+        let block = self.block(BlockReference::BlockId(block_id)).await?;
+        if let Some(cache) = &self.block_cache {
+            cache.insert(&block);
         }
+        Ok(block)
+    }
+
+    /// Fetches the receipt `receipt_id` with the `EXPERIMENTAL_receipt` method.
+    ///
+    /// Non-archival nodes only keep receipts of recent epochs, so older receipts surface as
+    /// `ReceiptError::ReceiptNotFound` unless the provider points at an archival node.
+    pub async fn get_receipt_by_id(
+        &self,
+        receipt_id: CryptoHash,
+    ) -> Result<ReceiptView, ReceiptError> {
+        let request = methods::EXPERIMENTAL_receipt::RpcReceiptRequest {
+            receipt_reference: ReceiptReference { receipt_id },
+        };
+        Ok(self.call(request).await?)
+    }
+
+    /// Fetches the receipts included in all chunks of the block `block_hash`.
+    ///
+    /// Chunks missing from the block, whose header is carried over from an earlier block, are skipped
+    /// so their receipts are not returned twice. As with `get_receipt_by_id`, the chunks of old blocks
+    /// are only available from archival nodes.
+    pub async fn get_receipts_in_block(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Vec<ReceiptView>, ReceiptError> {
+        let block = self
+            .get_block_by_hash(block_hash)
+            .await
+            .map_err(ReceiptError::Block)?;
+        let mut receipts = Vec::new();
+        for chunk in block
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.height_included == block.header.height)
+        {
+            let chunk = self
+                .chunk(ChunkReference::ChunkHash {
+                    chunk_id: chunk.chunk_hash,
+                })
+                .await
+                .map_err(ReceiptError::Chunk)?;
+            receipts.extend(chunk.receipts);
+        }
+        Ok(receipts)
+    }
+
+    /// Fetches the state changes of the block `block_hash` selected by `changes_request`, with the
+    /// `EXPERIMENTAL_changes` method.
+    ///
+    /// `StateChangesRequest::AllChanges` first lists the accounts touched in the block with
+    /// `EXPERIMENTAL_changes_in_block`, then fetches their account, access key, contract code and data
+    /// changes, so it takes up to five requests.
+    pub async fn get_state_changes_in_block(
+        &self,
+        block_hash: CryptoHash,
+        changes_request: StateChangesRequest,
+    ) -> Result<StateChangesView, JsonRpcError<RpcStateChangesError>> {
+        let block_reference = BlockReference::BlockId(BlockId::Hash(block_hash));
+        let requests = match changes_request.to_view() {
+            Some(request) => vec![request],
+            None => {
+                let request =
+                    methods::EXPERIMENTAL_changes_in_block::RpcStateChangesInBlockRequest {
+                        block_reference: block_reference.clone(),
+                    };
+                let mut account_ids: Vec<AccountId> = Vec::new();
+                for kind in self.call(request).await?.changes {
+                    let account_id = match kind {
+                        StateChangeKindView::AccountTouched { account_id }
+                        | StateChangeKindView::AccessKeyTouched { account_id }
+                        | StateChangeKindView::DataTouched { account_id }
+                        | StateChangeKindView::ContractCodeTouched { account_id } => account_id,
+                    };
+                    if !account_ids.contains(&account_id) {
+                        account_ids.push(account_id);
+                    }
+                }
+                if account_ids.is_empty() {
+                    return Ok(Vec::new());
+                }
+                vec![
+                    StateChangesRequestView::AccountChanges {
+                        account_ids: account_ids.clone(),
+                    },
+                    StateChangesRequestView::AllAccessKeyChanges {
+                        account_ids: account_ids.clone(),
+                    },
+                    StateChangesRequestView::ContractCodeChanges {
+                        account_ids: account_ids.clone(),
+                    },
+                    StateChangesRequestView::DataChanges {
+                        account_ids,
+                        key_prefix: StoreKey::from(Vec::new()),
+                    },
+                ]
+            }
+        };
+
+        let mut changes = Vec::new();
+        for state_changes_request in requests {
+            let request = methods::EXPERIMENTAL_changes::RpcStateChangesInBlockByTypeRequest {
+                block_reference: block_reference.clone(),
+                state_changes_request,
+            };
+            changes.extend(self.call(request).await?.changes);
+        }
+        Ok(changes)
+    }
+
+    /// Sends several requests to the NEAR blockchain in a single HTTP request, using the JSON RPC batch format.
+    ///
+    /// This saves round trips when several independent pieces of data are needed at once, such as the
+    /// access key (for the nonce) and a recent block (for the block hash) when building a transaction.
+    ///
+    /// # Returns
+    ///
+    /// The response to every request in the order of `requests`, or an error if the batch request itself failed.
+    pub async fn batch(
+        &self,
+        requests: Vec<BatchRequest>,
+    ) -> Result<Vec<Result<BatchResponse, BatchError>>, reqwest::Error> {
+        let calls = requests
+            .iter()
+            .map(|request| match request {
+                BatchRequest::Query(request) => (
+                    "query",
+                    json!(RpcQueryRequest {
+                        block_reference: BlockReference::Finality(Finality::Final),
+                        request: request.clone(),
+                    }),
+                ),
+                BatchRequest::Block(block_reference) => (
+                    "block",
+                    json!(RpcBlockRequest {
+                        block_reference: block_reference.clone(),
+                    }),
+                ),
+            })
+            .collect();
+
+        let responses = self.call_batch(calls).await?;
+        Ok(requests
+            .iter()
+            .zip(responses)
+            .map(|(request, response)| {
+                let value = response?;
+                let parsed = match request {
+                    BatchRequest::Query(_) => {
+                        parse_result("query", value).map(BatchResponse::Query)
+                    }
+                    BatchRequest::Block(_) => {
+                        parse_result("block", value).map(BatchResponse::Block)
+                    }
+                };
+                parsed.map_err(BatchError::Parse)
+            })
+            .collect())
+    }
+
+    /// Sends several JSON RPC calls, given as method name and params, in a single batch request.
+    ///
+    /// Like single calls, the request is retried according to the retry policy if it failed to reach
+    /// the node, and every middleware observes each call of the batch on every attempt.
+    ///
+    /// The results are returned in the order of `calls`, regardless of the order in which the node answered them.
+    pub(crate) async fn call_batch(
+        &self,
+        calls: Vec<(&str, Value)>,
+    ) -> Result<Vec<Result<Value, BatchError>>, reqwest::Error> {
+        let methods: Vec<&str> = calls.iter().map(|(method, _)| *method).collect();
+        let body: Vec<Value> = calls
+            .into_iter()
+            .enumerate()
+            .map(|(id, (method, params))| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": method,
+                    "params": params,
+                })
+            })
+            .collect();
+        let (methods, body) = (&methods, &body);
+        self.retry_policy
+            .retry(
+                move || async move {
+                    for middleware in &self.middleware {
+                        for method in methods {
+                            middleware.on_request(method);
+                        }
+                    }
+                    let started = Instant::now();
+                    let results = self.send_batch(methods, body).await;
+                    for middleware in &self.middleware {
+                        for (id, method) in methods.iter().enumerate() {
+                            let success = results.as_ref().is_ok_and(|results| results[id].is_ok());
+                            middleware.on_response(method, success, started.elapsed());
+                        }
+                    }
+                    results
+                },
+                |err: &reqwest::Error| err.is_connect() || err.is_timeout() || err.is_request(),
+            )
+            .await
+    }
+
+    /// Sends the batch request `body` once and matches the responses to the calls of `methods`.
+    async fn send_batch(
+        &self,
+        methods: &[&str],
+        body: &[Value],
+    ) -> Result<Vec<Result<Value, BatchError>>, reqwest::Error> {
+        let count = methods.len();
+        let responses: Vec<Value> = self
+            .http_client
+            .post(&self.rpc_endpoint)
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let mut results: Vec<Result<Value, BatchError>> = (0..count)
+            .map(|_| {
+                Err(BatchError::Rpc(RpcError::parse_error(
+                    "Missing response in batch".to_string(),
+                )))
+            })
+            .collect();
+        for mut response in responses {
+            let Some(id) = response["id"].as_u64().map(|id| id as usize) else {
+                continue;
+            };
+            if id >= count {
+                continue;
+            }
+            results[id] = match response.get_mut("error").map(Value::take) {
+                Some(error) => Err(match parse_result(methods[id], error) {
+                    Ok(error) => BatchError::Rpc(error),
+                    Err(error) => BatchError::Parse(error),
+                }),
+                None => Ok(response["result"].take()),
+            };
+        }
+        Ok(results)
     }
 }
 
@@ -48,7 +459,7 @@ impl Provider for JsonRpcProvider {
     /// Retrieves the current status of the NEAR blockchain.
     async fn status(&self) -> Result<RpcStatusResponse, JsonRpcError<RpcStatusError>> {
         let request = methods::status::RpcStatusRequest; // No params needed
-        self.client.call(request).await
+        self.call(request).await
     }
 
     /// Executes a query on the NEAR blockchain using a given `QueryRequest`.
@@ -60,7 +471,7 @@ impl Provider for JsonRpcProvider {
             block_reference: BlockReference::Finality(Finality::Final),
             request,
         };
-        self.client.call(query_request).await
+        self.call(query_request).await
     }
 
     /// Sends a signed transaction to the NEAR blockchain, waiting for its final execution outcome.
@@ -70,7 +481,7 @@ impl Provider for JsonRpcProvider {
     ) -> Result<FinalExecutionOutcomeView, JsonRpcError<RpcTransactionError>> {
         let request =
             methods::broadcast_tx_commit::RpcBroadcastTxCommitRequest { signed_transaction };
-        self.client.call(request).await
+        self.call(request).await
     }
 
     /// Sends a signed transaction to the NEAR blockchain asynchronously, without waiting for its final execution outcome.
@@ -81,7 +492,7 @@ impl Provider for JsonRpcProvider {
     {
         let request =
             methods::broadcast_tx_async::RpcBroadcastTxAsyncRequest { signed_transaction };
-        self.client.call(request).await
+        self.call(request).await
     }
 
     /// Retrieves the status of a transaction on the NEAR blockchain, identified by `TransactionInfo`.
@@ -95,7 +506,7 @@ impl Provider for JsonRpcProvider {
             wait_until,
         };
 
-        self.client.call(request).await
+        self.call(request).await
     }
 
     /// Fetches details of a specific chunk from the NEAR blockchain, identified by `ChunkReference`.
@@ -105,7 +516,7 @@ impl Provider for JsonRpcProvider {
     ) -> Result<ChunkView, JsonRpcError<RpcChunkError>> {
         let request = methods::chunk::RpcChunkRequest { chunk_reference };
 
-        self.client.call(request).await
+        self.call(request).await
     }
 
     /// Retrieves a block from the NEAR blockchain, specified by its `BlockReference`.
@@ -115,7 +526,7 @@ impl Provider for JsonRpcProvider {
     ) -> Result<BlockView, JsonRpcError<RpcBlockError>> {
         let request = methods::block::RpcBlockRequest { block_reference };
 
-        self.client.call(request).await
+        self.call(request).await
     }
 
     /// Fetches the experimental protocol configuration for a specific block, identified by `BlockReference`.
@@ -125,7 +536,7 @@ impl Provider for JsonRpcProvider {
     ) -> Result<ProtocolConfigView, JsonRpcError<RpcProtocolConfigError>> {
         let request =
             methods::EXPERIMENTAL_protocol_config::RpcProtocolConfigRequest { block_reference };
-        self.client.call(request).await
+        self.call(request).await
     }
 
     /// Retrieves information about validators for a given epoch, specified by `EpochReference`.
@@ -135,7 +546,7 @@ impl Provider for JsonRpcProvider {
     ) -> Result<EpochValidatorInfo, JsonRpcError<RpcValidatorError>> {
         let request = methods::validators::RpcValidatorRequest { epoch_reference };
 
-        self.client.call(request).await
+        self.call(request).await
     }
 }
 
@@ -157,6 +568,245 @@ async fn test_status() {
     }
 }
 
+/// Starts a minimal HTTP server answering one request with `body`, returning its URL and a handle
+/// resolving to the raw (lowercased) request it received.
+#[cfg(test)]
+pub(crate) async fn mock_rpc_server(
+    body: &'static str,
+) -> (String, tokio::task::JoinHandle<String>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buffer = vec![0; 4096];
+        let read = stream.read(&mut buffer).await.unwrap();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8_lossy(&buffer[..read]).to_lowercase()
+    });
+    (format!("http://{}", address), server)
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_config_headers() {
+    let (url, server) = mock_rpc_server("[]").await;
+
+    let mut config = ProviderConfig::default().bearer_token("token");
+    config
+        .headers
+        .insert("x-api-key".to_string(), "secret".to_string());
+    let provider = JsonRpcProvider::with_config(&url, config).unwrap();
+    provider.call_batch(Vec::new()).await.unwrap();
+
+    let request = server.await.unwrap();
+    assert!(request.contains("x-api-key: secret"));
+    assert!(request.contains("authorization: bearer token"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_batch_orders_responses() {
+    use crate::types::query::QueryResponseKind;
+
+    // The node answers the second request first
+    let (url, _server) = mock_rpc_server(
+        r#"[
+            {"jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "Server error", "data": "unknown block"}},
+            {"jsonrpc": "2.0", "id": 0, "result": {"nonce": 5, "permission": "FullAccess", "block_height": 1, "block_hash": "11111111111111111111111111111111"}}
+        ]"#,
+    )
+    .await;
+
+    let provider = JsonRpcProvider::new(&url);
+    let responses = provider
+        .batch(vec![
+            BatchRequest::Query(QueryRequest::ViewAccessKey {
+                account_id: "alice.testnet".parse().unwrap(),
+                public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+                    .parse()
+                    .unwrap(),
+            }),
+            BatchRequest::Block(BlockReference::Finality(Finality::Final)),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(responses.len(), 2);
+    match &responses[0] {
+        Ok(BatchResponse::Query(response)) => match &response.kind {
+            QueryResponseKind::AccessKey(access_key) => assert_eq!(access_key.nonce, 5),
+            kind => panic!("Unexpected query response {:?}", kind),
+        },
+        response => panic!("Unexpected batch response {:?}", response),
+    }
+    assert!(responses[1].is_err());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_batch_runs_middleware() {
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, Option<bool>)>>);
+
+    impl RpcMiddleware for Recorder {
+        fn on_request(&self, method: &str) {
+            self.0.lock().unwrap().push((method.to_string(), None));
+        }
+
+        fn on_response(&self, method: &str, success: bool, _elapsed: Duration) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((method.to_string(), Some(success)));
+        }
+    }
+
+    let (url, _server) = mock_rpc_server(
+        r#"[
+            {"jsonrpc": "2.0", "id": 0, "result": {}},
+            {"jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "Server error", "data": "unknown block"}}
+        ]"#,
+    )
+    .await;
+    let recorder = Arc::new(Recorder::default());
+    let provider = JsonRpcProviderBuilder::new(&url)
+        .middleware(recorder.clone())
+        .build()
+        .unwrap();
+    provider
+        .call_batch(vec![("query", json!({})), ("block", json!({}))])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [
+            ("query".to_string(), None),
+            ("block".to_string(), None),
+            ("query".to_string(), Some(true)),
+            ("block".to_string(), Some(false)),
+        ]
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_batch_keeps_unparseable_response() {
+    let (url, _server) =
+        mock_rpc_server(r#"[{"jsonrpc": "2.0", "id": 0, "result": {"unexpected": true}}]"#).await;
+
+    let provider = JsonRpcProvider::new(&url);
+    let responses = provider
+        .batch(vec![BatchRequest::Block(BlockReference::Finality(
+            Finality::Final,
+        ))])
+        .await
+        .unwrap();
+
+    match &responses[0] {
+        Err(BatchError::Parse(error)) => {
+            assert_eq!(error.method, "block");
+            assert_eq!(error.raw, json!({"unexpected": true}));
+        }
+        response => panic!("Unexpected batch response {:?}", response),
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_get_block_by_height_and_hash() {
+    use crate::test_utils::{block, block_hash};
+
+    // The mock server answers a single request, so the second lookup must be served from the cache
+    let body = json!({"jsonrpc": "2.0", "id": "dontcare", "result": block(42)}).to_string();
+    let (url, server) = mock_rpc_server(Box::leak(body.into_boxed_str())).await;
+    let provider = JsonRpcProvider::new(&url);
+
+    let by_height = provider.get_block_by_height(42).await.unwrap();
+    assert_eq!(by_height.header.hash, block_hash(42));
+    assert!(server.await.unwrap().contains(r#""method":"block""#));
+
+    // The cache is keyed by both height and hash
+    let by_hash = provider.get_block_by_hash(block_hash(42)).await.unwrap();
+    assert_eq!(by_hash.header.height, 42);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_get_receipt_by_id_not_found() {
+    let (url, _server) = mock_rpc_server(
+        r#"{
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "error": {
+                "name": "HANDLER_ERROR",
+                "cause": {
+                    "name": "UNKNOWN_RECEIPT",
+                    "info": { "receipt_id": "7tkzFg8RHBmMw1ncRJZCCZAizgq4rwCftTKYLce8RU8t" }
+                },
+                "code": -32000,
+                "message": "Server error",
+                "data": "Receipt not found"
+            }
+        }"#,
+    )
+    .await;
+    let provider = JsonRpcProvider::new(&url);
+    let receipt_id: CryptoHash = "7tkzFg8RHBmMw1ncRJZCCZAizgq4rwCftTKYLce8RU8t"
+        .parse()
+        .unwrap();
+
+    match provider.get_receipt_by_id(receipt_id).await {
+        Err(ReceiptError::ReceiptNotFound(id)) => assert_eq!(id, receipt_id),
+        other => panic!("Expected ReceiptNotFound, got {:?}", other),
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_get_state_changes_in_block_empty() {
+    let block_hash: CryptoHash = "7tkzFg8RHBmMw1ncRJZCCZAizgq4rwCftTKYLce8RU8t"
+        .parse()
+        .unwrap();
+    let body = r#"{
+        "jsonrpc": "2.0",
+        "id": "dontcare",
+        "result": {
+            "block_hash": "7tkzFg8RHBmMw1ncRJZCCZAizgq4rwCftTKYLce8RU8t",
+            "changes": []
+        }
+    }"#;
+
+    let (url, _server) = mock_rpc_server(body).await;
+    let changes = JsonRpcProvider::new(&url)
+        .get_state_changes_in_block(
+            block_hash,
+            StateChangesRequest::AccountChanges {
+                account_ids: vec!["alice.near".parse().unwrap()],
+            },
+        )
+        .await
+        .unwrap();
+    assert!(changes.is_empty());
+
+    // Without touched accounts, no further request is sent
+    let (url, _server) = mock_rpc_server(body).await;
+    let changes = JsonRpcProvider::new(&url)
+        .get_state_changes_in_block(block_hash, StateChangesRequest::AllChanges)
+        .await
+        .unwrap();
+    assert!(changes.is_empty());
+}
+
 #[cfg(test)]
 #[tokio::test]
 async fn test_block() {
@@ -11,10 +11,44 @@
 //! This crate is designed to be easily extendable with more providers and to offer a
 //! straightforward way to integrate NEAR blockchain functionalities into Rust applications.
 
+/// Re-export the batched view queries
+pub use crate::batch_query::{BatchQuery, BatchRequest, BatchResponse};
+/// Re-export the default size of the block cache
+pub use crate::block_cache::DEFAULT_BLOCK_CACHE_SIZE;
+/// Re-export the block range stream
+pub use crate::block_stream::{block_stream, BlockStreamOptions};
+/// Re-export the concurrent broadcast of signed transactions
+pub use crate::broadcast::broadcast_all;
+/// Re-export the errors of batch requests
+pub use crate::error::{BatchError, ReceiptError, RpcParseError};
+/// Re-export waiting for the finality of a transaction
+pub use crate::finality::{await_finality, FinalityError};
 /// Re-export the JsonRpcProvider
-pub use crate::json_rpc_provider::JsonRpcProvider;
+pub use crate::json_rpc_provider::{JsonRpcProvider, ProviderConfig};
+/// Re-export the RpcMiddleware trait
+pub use crate::middleware::RpcMiddleware;
 /// Re-export the Provider trait
 pub use crate::provider::Provider;
+/// Re-export the JsonRpcProvider builder
+pub use crate::provider_builder::JsonRpcProviderBuilder;
+/// Re-export the retry policy
+pub use crate::retry::RetryPolicy;
+/// Re-export the batch of signed transactions
+pub use crate::signed_batch::{BatchWaitError, SignedTransactionBatch};
+
+/// Convenience functions for querying blocks
+pub mod blocks;
+/// Health of a node beyond a simple ping
+pub mod health;
+/// Shard assignment of accounts and the shard layout
+pub mod shards;
+/// Typed requests for the state changes of a block
+pub mod state_changes;
+/// A mock provider and fixtures for tests
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+/// Convenience functions for querying validators
+pub mod validators;
 
 /// Re-exporting jsonrpc_primitives types so users of near-providers don't need
 /// to keep track of multiple jsonrpc crates. For now we export them as types
@@ -25,5 +59,15 @@ pub use near_jsonrpc_primitives::types;
 pub use near_jsonrpc_client as jsonrpc_client;
 pub use near_jsonrpc_primitives as jsonrpc_primitives;
 
+mod batch_query;
+mod block_cache;
+mod block_stream;
+mod broadcast;
+mod error;
+mod finality;
 mod json_rpc_provider;
+mod middleware;
 mod provider;
+mod provider_builder;
+mod retry;
+mod signed_batch;
@@ -0,0 +1,15 @@
+//! Defines the `RpcMiddleware` trait for observing the JSON RPC calls made by a `JsonRpcProvider`,
+//! e.g. for logging or collecting metrics.
+
+use std::time::Duration;
+
+/// Hooks invoked around every JSON RPC call made through the `Provider` methods of a `JsonRpcProvider`.
+///
+/// Both hooks are called once per attempt, so retried calls are observed multiple times.
+pub trait RpcMiddleware: Send + Sync {
+    /// Called before a call to the JSON RPC `method` is sent.
+    fn on_request(&self, _method: &str) {}
+
+    /// Called once a call to the JSON RPC `method` completed, with whether it succeeded and how long it took.
+    fn on_response(&self, _method: &str, _success: bool, _elapsed: Duration) {}
+}
@@ -0,0 +1,141 @@
+//! Provides `JsonRpcProviderBuilder` for configuring every option of a `JsonRpcProvider` with a fluent API.
+
+use crate::{JsonRpcProvider, RetryPolicy, RpcMiddleware, DEFAULT_BLOCK_CACHE_SIZE};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Url;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builds a `JsonRpcProvider` with custom HTTP, retry and middleware settings.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// use near_providers::{JsonRpcProviderBuilder, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let provider = JsonRpcProviderBuilder::new("https://rpc.testnet.near.org")
+///     .timeout(Duration::from_secs(10))
+///     .retry_policy(RetryPolicy::default())
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct JsonRpcProviderBuilder {
+    rpc_endpoint: String,
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    middleware: Vec<Arc<dyn RpcMiddleware>>,
+    proxy: Option<String>,
+    accept_invalid_certs: bool,
+    block_cache_size: usize,
+}
+
+impl JsonRpcProviderBuilder {
+    /// Starts building a provider for the given RPC endpoint.
+    pub fn new(rpc_endpoint: &str) -> Self {
+        Self {
+            rpc_endpoint: rpc_endpoint.to_string(),
+            headers: Vec::new(),
+            timeout: None,
+            retry_policy: RetryPolicy::no_retry(),
+            middleware: Vec::new(),
+            proxy: None,
+            accept_invalid_certs: false,
+            block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+        }
+    }
+
+    /// Adds a header attached to every request, such as an API key.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the timeout applied to every request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the policy for retrying calls, including batch requests, that failed to reach the node. Calls
+    /// are not retried by default.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Adds a middleware observing every call, including each call of a batch request. Middleware is
+    /// invoked in the order it was added.
+    pub fn middleware(mut self, middleware: Arc<dyn RpcMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Routes all requests through the proxy at `url`.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    /// Disables TLS certificate validation. Only meant for local development nodes.
+    pub fn accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Sets how many blocks `get_block_by_height` and `get_block_by_hash` keep cached, 128 by default.
+    /// A size of zero disables the cache.
+    pub fn with_block_cache_size(mut self, block_cache_size: usize) -> Self {
+        self.block_cache_size = block_cache_size;
+        self
+    }
+
+    /// Builds the provider.
+    ///
+    /// # Returns
+    ///
+    /// The configured `JsonRpcProvider`, or an error if the endpoint is not an HTTP(S) URL or any other
+    /// option is invalid.
+    pub fn build(self) -> Result<JsonRpcProvider, Box<dyn std::error::Error>> {
+        let url = Url::parse(&self.rpc_endpoint)?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(format!("RPC endpoint must be an HTTP(S) URL, got {}", url).into());
+        }
+        if self.accept_invalid_certs && url.host_str().is_some_and(|host| host.contains("mainnet"))
+        {
+            log::warn!(
+                "Accepting invalid TLS certificates for mainnet endpoint {}",
+                url
+            );
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .default_headers(headers)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        Ok(JsonRpcProvider::from_parts(
+            &self.rpc_endpoint,
+            builder.build()?,
+            self.retry_policy,
+            self.middleware,
+            self.block_cache_size,
+        ))
+    }
+}
@@ -0,0 +1,114 @@
+//! The `retry` module provides a `RetryPolicy` for retrying failed requests to the NEAR blockchain.
+//!
+//! Delays between attempts grow exponentially and are randomized with jitter, so that many clients
+//! failing at the same time don't retry in lockstep.
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Describes how often and how long to wait between attempts of a failing request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every further retry.
+    pub base_delay: Duration,
+    /// Upper bound for the delay between two attempts.
+    pub max_delay: Duration,
+    /// Fraction of the delay, between `0.0` and `1.0`, that is randomly subtracted from it.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt and never retries.
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the delay to wait before retry number `attempt`, counting from zero.
+    ///
+    /// The delay is `base_delay * 2^attempt`, capped at `max_delay`, and then reduced by a random
+    /// amount of up to `jitter` times its value.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponential = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+        let capped = exponential.min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return capped;
+        }
+        capped.mul_f64(1.0 - jitter * rand::random::<f64>())
+    }
+
+    /// Runs `operation` until it succeeds, fails with an error for which `should_retry` returns `false`,
+    /// or `max_attempts` is reached, sleeping according to `delay` in between.
+    ///
+    /// # Returns
+    ///
+    /// The result of the last attempt.
+    pub async fn retry<T, E, F, Fut>(
+        &self,
+        mut operation: F,
+        should_retry: impl Fn(&E) -> bool,
+    ) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation().await {
+                Err(err) if attempt + 1 < self.max_attempts && should_retry(&err) => {
+                    tokio::time::sleep(self.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_grows_exponentially() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            jitter: 0.0,
+        };
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(400));
+        assert_eq!(policy.delay(3), Duration::from_millis(800));
+        assert_eq!(policy.delay(4), Duration::from_secs(1));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_delay_with_jitter_stays_within_max_delay() {
+        let policy = RetryPolicy {
+            jitter: 1.0,
+            ..RetryPolicy::default()
+        };
+        for attempt in 0..20 {
+            assert!(policy.delay(attempt) <= policy.max_delay);
+        }
+    }
+}
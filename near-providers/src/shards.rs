@@ -0,0 +1,64 @@
+//! Shard assignment of accounts and the shard layout of the chain.
+//!
+//! The assignment algorithm is part of the protocol and may change across protocol upgrades. Newer
+//! shard layouts split shards by account boundaries rather than by hash, so `shard_for_account` is only
+//! exact for chains still using the original hash-based layout; use the layout returned by
+//! `get_shard_layout` otherwise.
+
+use crate::jsonrpc_client::errors::JsonRpcError;
+use crate::types::config::RpcProtocolConfigError;
+use crate::Provider;
+use near_primitives::hash::CryptoHash;
+use near_primitives::shard_layout::ShardLayout;
+use near_primitives::types::{AccountId, BlockId, BlockReference, Finality};
+use std::fmt;
+
+/// The index of a shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShardId(pub u64);
+
+impl ShardId {
+    /// Computes the shard of `account_id` with the hash-based assignment: the first 8 bytes of the
+    /// sha256 hash of the account ID, read as a little-endian integer, modulo `num_shards`.
+    pub fn shard_for_account(account_id: &AccountId, num_shards: u64) -> ShardId {
+        let hash = CryptoHash::hash_bytes(account_id.as_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hash.as_bytes()[..8]);
+        ShardId(u64::from_le_bytes(bytes) % num_shards)
+    }
+}
+
+impl fmt::Display for ShardId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Fetches the shard layout in effect at the block `block_hash`, or at the latest final block when `None`.
+pub async fn get_shard_layout(
+    provider: &dyn Provider,
+    block_hash: Option<CryptoHash>,
+) -> Result<ShardLayout, JsonRpcError<RpcProtocolConfigError>> {
+    let block_reference = match block_hash {
+        Some(block_hash) => BlockReference::BlockId(BlockId::Hash(block_hash)),
+        None => BlockReference::Finality(Finality::Final),
+    };
+    let config = provider
+        .experimental_protocol_config(block_reference)
+        .await?;
+    Ok(config.shard_layout)
+}
+
+#[cfg(test)]
+#[test]
+fn test_shard_for_account() {
+    let account_id: AccountId = "alice.near".parse().unwrap();
+    let hash = CryptoHash::hash_bytes(b"alice.near");
+    let expected = u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap()) % 4;
+
+    assert_eq!(
+        ShardId::shard_for_account(&account_id, 4),
+        ShardId(expected)
+    );
+    assert_eq!(ShardId::shard_for_account(&account_id, 1), ShardId(0));
+}
@@ -0,0 +1,197 @@
+//! Broadcasting a batch of signed transactions concurrently and waiting for all of them to complete.
+
+use crate::jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use crate::jsonrpc_client::methods::broadcast_tx_async::RpcBroadcastTxAsyncError;
+use crate::types::transactions::{RpcTransactionError, TransactionInfo};
+use crate::Provider;
+use futures::stream::{FuturesUnordered, StreamExt};
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::views::{FinalExecutionOutcomeView, TxExecutionStatus};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How long to wait before asking again for the status of a transaction the node doesn't know yet.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The reason waiting for a transaction of a batch failed.
+#[derive(Debug)]
+pub enum BatchWaitError {
+    /// The transaction was not final before the timeout.
+    Timeout,
+    /// The status of the transaction could not be fetched.
+    Rpc(JsonRpcError<RpcTransactionError>),
+}
+
+impl fmt::Display for BatchWaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchWaitError::Timeout => write!(f, "Timed out waiting for the transaction"),
+            BatchWaitError::Rpc(error) => {
+                write!(f, "Failed to fetch transaction status: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchWaitError {}
+
+/// Signed transactions to be broadcast together, e.g. the parts of a transaction split by
+/// `TransactionBuilder::split_into_transactions` and signed with `TransactionBuilder::sign_all`.
+///
+/// The transactions are sent concurrently, so they are not executed atomically and not necessarily in
+/// order. Transactions of the same access key with too far apart nonces may be rejected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignedTransactionBatch {
+    transactions: Vec<SignedTransaction>,
+}
+
+impl SignedTransactionBatch {
+    /// Creates a batch of `transactions`.
+    pub fn new(transactions: Vec<SignedTransaction>) -> Self {
+        Self { transactions }
+    }
+
+    /// Returns the transactions of the batch.
+    pub fn transactions(&self) -> &[SignedTransaction] {
+        &self.transactions
+    }
+
+    /// Returns the hashes of the transactions, in order.
+    pub fn hashes(&self) -> Vec<CryptoHash> {
+        self.transactions
+            .iter()
+            .map(SignedTransaction::get_hash)
+            .collect()
+    }
+
+    /// Sends all transactions concurrently without waiting for their execution.
+    ///
+    /// # Returns
+    ///
+    /// The hash of each transaction, or the error sending it, at the index of the transaction.
+    pub async fn broadcast(
+        &self,
+        provider: &dyn Provider,
+    ) -> Vec<Result<CryptoHash, JsonRpcError<RpcBroadcastTxAsyncError>>> {
+        let mut results: Vec<_> = self
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(index, transaction)| async move {
+                (
+                    index,
+                    provider.send_transaction_async(transaction.clone()).await,
+                )
+            })
+            .collect::<FuturesUnordered<_>>()
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Waits until every transaction of the batch is final, or until `timeout` has passed.
+    ///
+    /// Transactions the node doesn't know yet, e.g. because they are still being forwarded, are polled
+    /// again until the timeout.
+    ///
+    /// # Returns
+    ///
+    /// The final execution outcome of each transaction, or why it is missing, at the index of the
+    /// transaction.
+    pub async fn wait_all(
+        &self,
+        provider: &dyn Provider,
+        timeout: Duration,
+    ) -> Vec<Result<FinalExecutionOutcomeView, BatchWaitError>> {
+        let deadline = Instant::now() + timeout;
+        let mut results: Vec<_> = self
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(index, transaction)| async move {
+                (index, wait_for(provider, transaction, deadline).await)
+            })
+            .collect::<FuturesUnordered<_>>()
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+impl From<Vec<SignedTransaction>> for SignedTransactionBatch {
+    fn from(transactions: Vec<SignedTransaction>) -> Self {
+        Self::new(transactions)
+    }
+}
+
+async fn wait_for(
+    provider: &dyn Provider,
+    transaction: &SignedTransaction,
+    deadline: Instant,
+) -> Result<FinalExecutionOutcomeView, BatchWaitError> {
+    let transaction_info = TransactionInfo::TransactionId {
+        tx_hash: transaction.get_hash(),
+        sender_account_id: transaction.transaction.signer_id.clone(),
+    };
+    loop {
+        let status = tokio::time::timeout_at(
+            deadline,
+            provider.tx_status(transaction_info.clone(), TxExecutionStatus::Final),
+        )
+        .await
+        .map_err(|_| BatchWaitError::Timeout)?;
+        match status {
+            Ok(response) => {
+                if let Some(outcome) = response.final_execution_outcome {
+                    return Ok(outcome.into_outcome());
+                }
+            }
+            Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                RpcTransactionError::UnknownTransaction { .. } | RpcTransactionError::TimeoutError,
+            ))) => {}
+            Err(error) => return Err(BatchWaitError::Rpc(error)),
+        }
+        if Instant::now() + POLL_INTERVAL >= deadline {
+            return Err(BatchWaitError::Timeout);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast::tests::flaky_provider;
+    use crate::test_utils::signed_transaction;
+
+    #[tokio::test]
+    async fn test_broadcast_keeps_order() {
+        let batch = SignedTransactionBatch::new((1..10).map(signed_transaction).collect());
+        let results = batch.broadcast(&flaky_provider()).await;
+
+        assert_eq!(results.len(), 9);
+        for ((result, hash), nonce) in results.iter().zip(batch.hashes()).zip(1..) {
+            match result {
+                Ok(sent) => assert_eq!(*sent, hash),
+                Err(_) => assert_eq!(nonce % 3, 0),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_all_times_out() {
+        let batch = SignedTransactionBatch::new(vec![signed_transaction(1), signed_transaction(2)]);
+        let results = batch
+            .wait_all(&flaky_provider(), Duration::from_millis(50))
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, Err(BatchWaitError::Timeout))));
+    }
+}
@@ -0,0 +1,51 @@
+//! Typed requests for the state changes of a block, see `JsonRpcProvider::get_state_changes_in_block`.
+
+use near_crypto::PublicKey;
+use near_primitives::types::{AccountId, AccountWithPublicKey};
+use near_primitives::views::StateChangesRequestView;
+
+/// The state changes of a block to fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateChangesRequest {
+    /// Every change of every account touched in the block: account, access key, contract code and
+    /// contract data changes.
+    AllChanges,
+    /// Changes of the account records, such as balances, of `account_ids`.
+    AccountChanges { account_ids: Vec<AccountId> },
+    /// Contract deployments to `account_ids`.
+    ContractCodeChanges { account_ids: Vec<AccountId> },
+    /// Changes of the single access key `public_key` of `account_id`.
+    AccessKeyChanges {
+        account_id: AccountId,
+        public_key: PublicKey,
+    },
+}
+
+impl StateChangesRequest {
+    /// Returns the request of the `EXPERIMENTAL_changes` method, or `None` for `AllChanges`, which first
+    /// needs the accounts touched in the block.
+    pub(crate) fn to_view(&self) -> Option<StateChangesRequestView> {
+        match self {
+            StateChangesRequest::AllChanges => None,
+            StateChangesRequest::AccountChanges { account_ids } => {
+                Some(StateChangesRequestView::AccountChanges {
+                    account_ids: account_ids.clone(),
+                })
+            }
+            StateChangesRequest::ContractCodeChanges { account_ids } => {
+                Some(StateChangesRequestView::ContractCodeChanges {
+                    account_ids: account_ids.clone(),
+                })
+            }
+            StateChangesRequest::AccessKeyChanges {
+                account_id,
+                public_key,
+            } => Some(StateChangesRequestView::SingleAccessKeyChanges {
+                keys: vec![AccountWithPublicKey {
+                    account_id: account_id.clone(),
+                    public_key: public_key.clone(),
+                }],
+            }),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! A `Provider` whose answers are set by the test, and fixtures of the views it returns.
+
+use crate::jsonrpc_client::{
+    errors::JsonRpcError,
+    methods::{broadcast_tx_async::RpcBroadcastTxAsyncError, status::RpcStatusResponse},
+};
+use crate::types::{
+    blocks::RpcBlockError,
+    chunks::{ChunkReference, RpcChunkError},
+    config::RpcProtocolConfigError,
+    query::{RpcQueryError, RpcQueryResponse},
+    status::RpcStatusError,
+    transactions::{RpcTransactionError, TransactionInfo},
+    validator::RpcValidatorError,
+};
+use crate::Provider;
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt};
+use near_chain_configs::ProtocolConfigView;
+use near_crypto::{KeyType, PublicKey, Signature};
+use near_jsonrpc_client::methods::tx::RpcTransactionResponse;
+use near_primitives::{
+    hash::CryptoHash,
+    transaction::{SignedTransaction, Transaction},
+    types::{BlockHeight, BlockReference, EpochReference},
+    views::{
+        BlockView, ChunkView, EpochValidatorInfo, FinalExecutionOutcomeView, QueryRequest,
+        TxExecutionStatus,
+    },
+};
+use serde_json::json;
+use std::future::Future;
+
+type Handler<Req, Resp> = Box<dyn Fn(Req) -> BoxFuture<'static, Resp> + Send + Sync>;
+
+/// A provider answering each method with the handler the test set for it, so code built on top of a
+/// `Provider` can be tested without a node. Calling a method without a handler panics.
+#[derive(Default)]
+pub struct MockProvider {
+    status: Option<Handler<(), Result<RpcStatusResponse, JsonRpcError<RpcStatusError>>>>,
+    send_transaction: Option<
+        Handler<
+            SignedTransaction,
+            Result<FinalExecutionOutcomeView, JsonRpcError<RpcTransactionError>>,
+        >,
+    >,
+    send_transaction_async: Option<
+        Handler<SignedTransaction, Result<CryptoHash, JsonRpcError<RpcBroadcastTxAsyncError>>>,
+    >,
+    tx_status: Option<
+        Handler<
+            (TransactionInfo, TxExecutionStatus),
+            Result<RpcTransactionResponse, JsonRpcError<RpcTransactionError>>,
+        >,
+    >,
+    chunk: Option<Handler<ChunkReference, Result<ChunkView, JsonRpcError<RpcChunkError>>>>,
+    block: Option<Handler<BlockReference, Result<BlockView, JsonRpcError<RpcBlockError>>>>,
+    validators: Option<
+        Handler<EpochReference, Result<EpochValidatorInfo, JsonRpcError<RpcValidatorError>>>,
+    >,
+    query: Option<Handler<QueryRequest, Result<RpcQueryResponse, JsonRpcError<RpcQueryError>>>>,
+    experimental_protocol_config: Option<
+        Handler<BlockReference, Result<ProtocolConfigView, JsonRpcError<RpcProtocolConfigError>>>,
+    >,
+}
+
+impl MockProvider {
+    /// Answers `status` with `handler`.
+    pub fn on_status<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<RpcStatusResponse, JsonRpcError<RpcStatusError>>>
+            + Send
+            + 'static,
+    {
+        self.status = Some(Box::new(move |()| handler().boxed()));
+        self
+    }
+
+    /// Answers `send_transaction` with `handler`.
+    pub fn on_send_transaction<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(SignedTransaction) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<FinalExecutionOutcomeView, JsonRpcError<RpcTransactionError>>>
+            + Send
+            + 'static,
+    {
+        self.send_transaction = Some(Box::new(move |tx| handler(tx).boxed()));
+        self
+    }
+
+    /// Answers `send_transaction_async` with `handler`.
+    pub fn on_send_transaction_async<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(SignedTransaction) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<CryptoHash, JsonRpcError<RpcBroadcastTxAsyncError>>>
+            + Send
+            + 'static,
+    {
+        self.send_transaction_async = Some(Box::new(move |tx| handler(tx).boxed()));
+        self
+    }
+
+    /// Answers `tx_status` with `handler`.
+    pub fn on_tx_status<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(TransactionInfo, TxExecutionStatus) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<RpcTransactionResponse, JsonRpcError<RpcTransactionError>>>
+            + Send
+            + 'static,
+    {
+        self.tx_status = Some(Box::new(move |(transaction_info, wait_until)| {
+            handler(transaction_info, wait_until).boxed()
+        }));
+        self
+    }
+
+    /// Answers `chunk` with `handler`.
+    pub fn on_chunk<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(ChunkReference) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ChunkView, JsonRpcError<RpcChunkError>>> + Send + 'static,
+    {
+        self.chunk = Some(Box::new(move |reference| handler(reference).boxed()));
+        self
+    }
+
+    /// Answers `block` with `handler`.
+    pub fn on_block<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(BlockReference) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<BlockView, JsonRpcError<RpcBlockError>>> + Send + 'static,
+    {
+        self.block = Some(Box::new(move |reference| handler(reference).boxed()));
+        self
+    }
+
+    /// Answers `validators` with `handler`.
+    pub fn on_validators<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(EpochReference) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<EpochValidatorInfo, JsonRpcError<RpcValidatorError>>>
+            + Send
+            + 'static,
+    {
+        self.validators = Some(Box::new(move |reference| handler(reference).boxed()));
+        self
+    }
+
+    /// Answers `query` with `handler`.
+    pub fn on_query<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(QueryRequest) -> Fut + Send + Sync + 'static,
+        Fut:
+            Future<Output = Result<RpcQueryResponse, JsonRpcError<RpcQueryError>>> + Send + 'static,
+    {
+        self.query = Some(Box::new(move |request| handler(request).boxed()));
+        self
+    }
+
+    /// Answers `experimental_protocol_config` with `handler`.
+    pub fn on_experimental_protocol_config<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(BlockReference) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ProtocolConfigView, JsonRpcError<RpcProtocolConfigError>>>
+            + Send
+            + 'static,
+    {
+        self.experimental_protocol_config =
+            Some(Box::new(move |reference| handler(reference).boxed()));
+        self
+    }
+}
+
+/// Calls the handler set for `method`, panicking if the test didn't set one.
+fn handle<Req, Resp>(
+    handler: &Option<Handler<Req, Resp>>,
+    method: &str,
+    request: Req,
+) -> BoxFuture<'static, Resp> {
+    match handler {
+        Some(handler) => handler(request),
+        None => panic!("Unexpected call to `{method}` on MockProvider"),
+    }
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    async fn status(&self) -> Result<RpcStatusResponse, JsonRpcError<RpcStatusError>> {
+        handle(&self.status, "status", ()).await
+    }
+
+    async fn send_transaction(
+        &self,
+        signed_transaction: SignedTransaction,
+    ) -> Result<FinalExecutionOutcomeView, JsonRpcError<RpcTransactionError>> {
+        handle(
+            &self.send_transaction,
+            "send_transaction",
+            signed_transaction,
+        )
+        .await
+    }
+
+    async fn send_transaction_async(
+        &self,
+        signed_transaction: SignedTransaction,
+    ) -> Result<CryptoHash, JsonRpcError<RpcBroadcastTxAsyncError>> {
+        handle(
+            &self.send_transaction_async,
+            "send_transaction_async",
+            signed_transaction,
+        )
+        .await
+    }
+
+    async fn tx_status(
+        &self,
+        transaction_info: TransactionInfo,
+        wait_until: TxExecutionStatus,
+    ) -> Result<RpcTransactionResponse, JsonRpcError<RpcTransactionError>> {
+        handle(&self.tx_status, "tx_status", (transaction_info, wait_until)).await
+    }
+
+    async fn chunk(
+        &self,
+        chunk_reference: ChunkReference,
+    ) -> Result<ChunkView, JsonRpcError<RpcChunkError>> {
+        handle(&self.chunk, "chunk", chunk_reference).await
+    }
+
+    async fn block(
+        &self,
+        block_reference: BlockReference,
+    ) -> Result<BlockView, JsonRpcError<RpcBlockError>> {
+        handle(&self.block, "block", block_reference).await
+    }
+
+    async fn validators(
+        &self,
+        epoch_reference: EpochReference,
+    ) -> Result<EpochValidatorInfo, JsonRpcError<RpcValidatorError>> {
+        handle(&self.validators, "validators", epoch_reference).await
+    }
+
+    async fn query(
+        &self,
+        request: QueryRequest,
+    ) -> Result<RpcQueryResponse, JsonRpcError<RpcQueryError>> {
+        handle(&self.query, "query", request).await
+    }
+
+    async fn experimental_protocol_config(
+        &self,
+        block_reference: BlockReference,
+    ) -> Result<ProtocolConfigView, JsonRpcError<RpcProtocolConfigError>> {
+        handle(
+            &self.experimental_protocol_config,
+            "experimental_protocol_config",
+            block_reference,
+        )
+        .await
+    }
+}
+
+/// Returns a transaction from `alice.near` to `bob.near` with nonce `nonce` and an empty signature.
+pub fn signed_transaction(nonce: u64) -> SignedTransaction {
+    SignedTransaction::new(
+        Signature::empty(KeyType::ED25519),
+        Transaction::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            nonce,
+            CryptoHash::default(),
+        ),
+    )
+}
+
+/// Returns a successful outcome of the transaction `signed_transaction(nonce)`.
+pub fn outcome(nonce: u64) -> FinalExecutionOutcomeView {
+    serde_json::from_value(json!({
+        "status": { "SuccessValue": "" },
+        "transaction": {
+            "signer_id": "alice.near",
+            "public_key": "ed25519:11111111111111111111111111111111",
+            "nonce": nonce,
+            "receiver_id": "bob.near",
+            "actions": [],
+            "signature": "ed25519:1111111111111111111111111111111111111111111111111111111111111111",
+            "hash": "11111111111111111111111111111111"
+        },
+        "transaction_outcome": {
+            "proof": [],
+            "block_hash": "11111111111111111111111111111111",
+            "id": "11111111111111111111111111111111",
+            "outcome": {
+                "logs": [],
+                "receipt_ids": [],
+                "gas_burnt": 0,
+                "tokens_burnt": "0",
+                "executor_id": "alice.near",
+                "status": { "SuccessValue": "" },
+                "metadata": { "version": 1, "gas_profile": null }
+            }
+        },
+        "receipts_outcome": []
+    }))
+    .unwrap()
+}
+
+/// Returns the hash of the block `block(height)`.
+pub fn block_hash(height: BlockHeight) -> CryptoHash {
+    CryptoHash::hash_bytes(&height.to_le_bytes())
+}
+
+/// Returns a block at height `height` whose hash is `block_hash(height)`.
+pub fn block(height: BlockHeight) -> BlockView {
+    serde_json::from_value(json!({
+        "author": "node0",
+        "header": {
+            "height": height,
+            "prev_height": height.saturating_sub(1),
+            "epoch_id": "11111111111111111111111111111111",
+            "next_epoch_id": "11111111111111111111111111111111",
+            "hash": block_hash(height),
+            "prev_hash": block_hash(height.saturating_sub(1)),
+            "prev_state_root": "11111111111111111111111111111111",
+            "block_body_hash": null,
+            "chunk_receipts_root": "11111111111111111111111111111111",
+            "chunk_headers_root": "11111111111111111111111111111111",
+            "chunk_tx_root": "11111111111111111111111111111111",
+            "outcome_root": "11111111111111111111111111111111",
+            "chunks_included": 0,
+            "challenges_root": "11111111111111111111111111111111",
+            "timestamp": 1700000000000000000u64,
+            "timestamp_nanosec": "1700000000000000000",
+            "random_value": "11111111111111111111111111111111",
+            "validator_proposals": [],
+            "chunk_mask": [],
+            "gas_price": "100000000",
+            "block_ordinal": null,
+            "rent_paid": "0",
+            "validator_reward": "0",
+            "total_supply": "0",
+            "challenges_result": [],
+            "last_final_block": "11111111111111111111111111111111",
+            "last_ds_final_block": "11111111111111111111111111111111",
+            "next_bp_hash": "11111111111111111111111111111111",
+            "block_merkle_root": "11111111111111111111111111111111",
+            "epoch_sync_data_hash": null,
+            "approvals": [],
+            "signature": "ed25519:1111111111111111111111111111111111111111111111111111111111111111",
+            "latest_protocol_version": 64
+        },
+        "chunks": []
+    }))
+    .unwrap()
+}
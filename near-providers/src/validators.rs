@@ -0,0 +1,187 @@
+//! Convenience functions around the `validators` JSON RPC method for staking analytics, such as listing
+//! the validator set of an epoch or estimating staking returns.
+
+use crate::jsonrpc_client::errors::JsonRpcError;
+use crate::types::status::RpcStatusError;
+use crate::types::validator::RpcValidatorError;
+use crate::Provider;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::{
+    AccountId, Balance, BlockHeight, BlockId, EpochId, EpochReference, ValidatorKickoutReason,
+};
+use near_primitives::views::validator_stake_view::{ValidatorStakeView, ValidatorStakeViewV1};
+use near_primitives::views::{
+    CurrentEpochValidatorInfo, EpochValidatorInfo, NextEpochValidatorInfo,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Approximate number of epochs per year, with the mainnet epoch length of roughly 12 hours.
+pub const EPOCHS_PER_YEAR: f64 = 730.0;
+
+/// Fetches the validator information of the epoch `epoch_id`, or of the latest epoch when `None`.
+pub async fn get_validators(
+    provider: &dyn Provider,
+    epoch_id: Option<CryptoHash>,
+) -> Result<EpochValidatorInfo, JsonRpcError<RpcValidatorError>> {
+    let epoch_reference = match epoch_id {
+        Some(epoch_id) => EpochReference::EpochId(EpochId(epoch_id)),
+        None => EpochReference::Latest,
+    };
+    provider.validators(epoch_reference).await
+}
+
+/// Fetches the validators of the current epoch.
+pub async fn current_validators(
+    provider: &dyn Provider,
+) -> Result<Vec<CurrentEpochValidatorInfo>, JsonRpcError<RpcValidatorError>> {
+    Ok(get_validators(provider, None).await?.current_validators)
+}
+
+/// Fetches the validators selected for the next epoch.
+pub async fn next_validators(
+    provider: &dyn Provider,
+) -> Result<Vec<NextEpochValidatorInfo>, JsonRpcError<RpcValidatorError>> {
+    Ok(get_validators(provider, None).await?.next_validators)
+}
+
+/// Errors that can occur while fetching validators at the node's latest block.
+#[derive(Debug)]
+pub enum LatestValidatorsError {
+    /// The node's status, which holds its latest block hash, could not be fetched.
+    Status(JsonRpcError<RpcStatusError>),
+    /// The validators could not be fetched.
+    Validators(JsonRpcError<RpcValidatorError>),
+}
+
+impl fmt::Display for LatestValidatorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LatestValidatorsError::Status(error) => write!(f, "Failed to fetch status: {}", error),
+            LatestValidatorsError::Validators(error) => {
+                write!(f, "Failed to fetch validators: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LatestValidatorsError {}
+
+/// Fetches the validators selected for the next epoch at the node's latest block hash, which may be more
+/// recent than the latest final block, e.g. to show upcoming validator changes.
+pub async fn get_next_epoch_validators(
+    provider: &dyn Provider,
+) -> Result<Vec<NextEpochValidatorInfo>, LatestValidatorsError> {
+    let status = provider
+        .status()
+        .await
+        .map_err(LatestValidatorsError::Status)?;
+    let epoch_reference =
+        EpochReference::BlockId(BlockId::Hash(status.sync_info.latest_block_hash));
+    let validators = provider
+        .validators(epoch_reference)
+        .await
+        .map_err(LatestValidatorsError::Validators)?;
+    Ok(validators.next_validators)
+}
+
+/// Fetches why validators were kicked out at the end of the epoch preceding the epoch `epoch_id`.
+pub async fn get_kickout_reasons(
+    provider: &dyn Provider,
+    epoch_id: CryptoHash,
+) -> Result<HashMap<AccountId, ValidatorKickoutReason>, JsonRpcError<RpcValidatorError>> {
+    let kickouts = get_validators(provider, Some(epoch_id))
+        .await?
+        .prev_epoch_kickout
+        .into_iter()
+        .map(|kickout| (kickout.account_id, kickout.reason))
+        .collect();
+    Ok(kickouts)
+}
+
+/// Estimates the height of the last block of the epoch containing `current_height`, assuming epochs
+/// start at multiples of `epoch_length`.
+///
+/// Skipped blocks and the actual epoch start height, see `EpochValidatorInfo::epoch_start_height`, make
+/// the real end differ, so this is only suitable for display.
+pub fn estimated_epoch_end_block(current_height: BlockHeight, epoch_length: u64) -> BlockHeight {
+    if epoch_length == 0 {
+        return current_height;
+    }
+    (current_height / epoch_length + 1) * epoch_length - 1
+}
+
+/// Fetches the staking proposals submitted in the current epoch.
+pub async fn current_proposals(
+    provider: &dyn Provider,
+) -> Result<Vec<ValidatorStakeView>, JsonRpcError<RpcValidatorError>> {
+    Ok(get_validators(provider, None).await?.current_proposals)
+}
+
+/// Looks up `account_id` among the validators of the current epoch.
+///
+/// # Returns
+///
+/// The validator's stake, or `None` if the account is not validating in the current epoch.
+pub async fn validator_by_account_id(
+    provider: &dyn Provider,
+    account_id: &AccountId,
+) -> Result<Option<ValidatorStakeView>, JsonRpcError<RpcValidatorError>> {
+    let validator = current_validators(provider)
+        .await?
+        .into_iter()
+        .find(|validator| &validator.account_id == account_id)
+        .map(|validator| {
+            ValidatorStakeView::V1(ValidatorStakeViewV1 {
+                account_id: validator.account_id,
+                public_key: validator.public_key,
+                stake: validator.stake,
+            })
+        });
+    Ok(validator)
+}
+
+/// Estimates the annual return of a validator from its stake and the reward it earned in one epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidatorApyEstimate {
+    /// The stake the reward was earned with.
+    pub stake: Balance,
+    /// The reward earned in one epoch.
+    pub epoch_reward: Balance,
+    /// The number of epochs per year, usually `EPOCHS_PER_YEAR`.
+    pub epochs_per_year: f64,
+}
+
+impl ValidatorApyEstimate {
+    /// Creates an estimate assuming `EPOCHS_PER_YEAR` epochs per year.
+    pub fn new(stake: Balance, epoch_reward: Balance) -> Self {
+        Self {
+            stake,
+            epoch_reward,
+            epochs_per_year: EPOCHS_PER_YEAR,
+        }
+    }
+
+    /// Returns the reward of one epoch relative to the stake.
+    pub fn epoch_rate(&self) -> f64 {
+        if self.stake == 0 {
+            return 0.0;
+        }
+        self.epoch_reward as f64 / self.stake as f64
+    }
+
+    /// Returns the annual percentage yield as a fraction (e.g. `0.1` for 10%), assuming rewards are
+    /// restaked every epoch and the epoch rate stays constant.
+    pub fn apy(&self) -> f64 {
+        (1.0 + self.epoch_rate()).powf(self.epochs_per_year) - 1.0
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_estimated_epoch_end_block() {
+    assert_eq!(estimated_epoch_end_block(0, 43_200), 43_199);
+    assert_eq!(estimated_epoch_end_block(43_199, 43_200), 43_199);
+    assert_eq!(estimated_epoch_end_block(43_200, 43_200), 86_399);
+    assert_eq!(estimated_epoch_end_block(100, 0), 100);
+}
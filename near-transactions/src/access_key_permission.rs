@@ -0,0 +1,130 @@
+//! Helpers for reasoning about what an access key is allowed to do.
+
+use near_primitives::account::{AccessKeyPermission, FunctionCallPermission};
+use near_primitives::types::{AccountId, Balance};
+
+/// Extension methods for `AccessKeyPermission`.
+pub trait AccessKeyPermissionExt {
+    /// Returns true for a full access key.
+    fn is_full_access(&self) -> bool;
+
+    /// Returns true if the key may call `method` on `receiver`.
+    ///
+    /// Function call keys can't attach a deposit, so calls requiring one are not allowed even if this
+    /// returns true.
+    fn allows_method(&self, receiver: &AccountId, method: &str) -> bool;
+
+    /// Returns the amount the key may still spend on gas, or `None` if it is unlimited, as for full
+    /// access keys and function call keys without allowance.
+    fn remaining_allowance(&self) -> Option<Balance>;
+
+    /// Returns true if the key allows everything `other` allows, and more.
+    ///
+    /// Function call keys for different receivers are not comparable, so neither is more permissive
+    /// than the other.
+    fn is_more_permissive_than(&self, other: &AccessKeyPermission) -> bool;
+}
+
+impl AccessKeyPermissionExt for AccessKeyPermission {
+    fn is_full_access(&self) -> bool {
+        matches!(self, AccessKeyPermission::FullAccess)
+    }
+
+    fn allows_method(&self, receiver: &AccountId, method: &str) -> bool {
+        match self {
+            AccessKeyPermission::FullAccess => true,
+            AccessKeyPermission::FunctionCall(permission) => {
+                permission.receiver_id == receiver.as_str()
+                    && (permission.method_names.is_empty()
+                        || permission.method_names.iter().any(|name| name == method))
+            }
+        }
+    }
+
+    fn remaining_allowance(&self) -> Option<Balance> {
+        match self {
+            AccessKeyPermission::FullAccess => None,
+            AccessKeyPermission::FunctionCall(permission) => permission.allowance,
+        }
+    }
+
+    fn is_more_permissive_than(&self, other: &AccessKeyPermission) -> bool {
+        match (self, other) {
+            (AccessKeyPermission::FullAccess, other) => !other.is_full_access(),
+            (AccessKeyPermission::FunctionCall(_), AccessKeyPermission::FullAccess) => false,
+            (AccessKeyPermission::FunctionCall(this), AccessKeyPermission::FunctionCall(other)) => {
+                covers(this, other) && !covers(other, this)
+            }
+        }
+    }
+}
+
+/// Returns true if `this` allows every call `other` allows, with at least the same allowance.
+fn covers(this: &FunctionCallPermission, other: &FunctionCallPermission) -> bool {
+    let methods_covered = this.method_names.is_empty()
+        || (!other.method_names.is_empty()
+            && other
+                .method_names
+                .iter()
+                .all(|method| this.method_names.contains(method)));
+    let allowance_covered = match (this.allowance, other.allowance) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(this), Some(other)) => this >= other,
+    };
+    this.receiver_id == other.receiver_id && methods_covered && allowance_covered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function_call(
+        receiver_id: &str,
+        method_names: &[&str],
+        allowance: Option<Balance>,
+    ) -> AccessKeyPermission {
+        AccessKeyPermission::FunctionCall(FunctionCallPermission {
+            allowance,
+            receiver_id: receiver_id.to_string(),
+            method_names: method_names.iter().map(|name| name.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_allows_method_and_allowance() {
+        let contract: AccountId = "contract.near".parse().unwrap();
+        let other: AccountId = "other.near".parse().unwrap();
+        let key = function_call("contract.near", &["a"], Some(10));
+
+        assert!(!key.is_full_access());
+        assert!(key.allows_method(&contract, "a"));
+        assert!(!key.allows_method(&contract, "b"));
+        assert!(!key.allows_method(&other, "a"));
+        assert!(function_call("contract.near", &[], None).allows_method(&contract, "b"));
+        assert!(AccessKeyPermission::FullAccess.allows_method(&other, "b"));
+
+        assert_eq!(key.remaining_allowance(), Some(10));
+        assert_eq!(AccessKeyPermission::FullAccess.remaining_allowance(), None);
+    }
+
+    #[test]
+    fn test_is_more_permissive_than() {
+        let full = AccessKeyPermission::FullAccess;
+        let one_method = function_call("contract.near", &["a"], Some(10));
+        let two_methods = function_call("contract.near", &["a", "b"], Some(10));
+        let any_method = function_call("contract.near", &[], Some(10));
+        let unlimited = function_call("contract.near", &["a"], None);
+        let other_receiver = function_call("other.near", &[], None);
+
+        assert!(full.is_more_permissive_than(&one_method));
+        assert!(!full.is_more_permissive_than(&full));
+        assert!(!one_method.is_more_permissive_than(&full));
+        assert!(two_methods.is_more_permissive_than(&one_method));
+        assert!(any_method.is_more_permissive_than(&two_methods));
+        assert!(unlimited.is_more_permissive_than(&one_method));
+        assert!(!one_method.is_more_permissive_than(&one_method));
+        assert!(!two_methods.is_more_permissive_than(&unlimited));
+        assert!(!other_receiver.is_more_permissive_than(&one_method));
+    }
+}
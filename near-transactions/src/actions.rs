@@ -0,0 +1,258 @@
+//! Inspection of the actions of a `TransactionBuilder`, e.g. to find and replace an action of a given
+//! type.
+
+use crate::TransactionBuilder;
+use near_primitives::account::AccessKeyPermission;
+use near_primitives::action::delegate::SignedDelegateAction;
+use near_primitives::transaction::{
+    Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
+    DeployContractAction, FunctionCallAction, StakeAction, TransferAction,
+};
+use near_primitives::types::{AccountId, Balance, Gas};
+use std::borrow::Borrow;
+
+/// A summary of an action for rendering, independent of the layout of `Action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind<'a> {
+    /// Creates the receiver account.
+    CreateAccount,
+    /// Deploys a contract of `code_len` bytes to the receiver.
+    DeployContract { code_len: usize },
+    /// Calls `method` on the receiver.
+    FunctionCall {
+        method: &'a str,
+        gas: Gas,
+        deposit: Balance,
+    },
+    /// Transfers `deposit` to the receiver.
+    Transfer { deposit: Balance },
+    /// Stakes `stake` with the receiver's validator key.
+    Stake { stake: Balance },
+    /// Adds an access key, with full access or restricted to function calls.
+    AddKey { full_access: bool },
+    /// Deletes an access key.
+    DeleteKey,
+    /// Deletes the receiver account, sending its balance to `beneficiary_id`.
+    DeleteAccount { beneficiary_id: &'a AccountId },
+    /// Executes actions on behalf of another account.
+    Delegate { sender_id: &'a AccountId },
+    /// Any other action, such as deploying or using a global contract.
+    Other,
+}
+
+impl<'a> From<&'a Action> for ActionKind<'a> {
+    fn from(action: &'a Action) -> Self {
+        match action {
+            Action::CreateAccount(_) => ActionKind::CreateAccount,
+            Action::DeployContract(deploy) => ActionKind::DeployContract {
+                code_len: deploy.code.len(),
+            },
+            Action::FunctionCall(call) => ActionKind::FunctionCall {
+                method: &call.method_name,
+                gas: call.gas,
+                deposit: call.deposit,
+            },
+            Action::Transfer(transfer) => ActionKind::Transfer {
+                deposit: transfer.deposit,
+            },
+            Action::Stake(stake) => ActionKind::Stake { stake: stake.stake },
+            Action::AddKey(add_key) => ActionKind::AddKey {
+                full_access: matches!(
+                    add_key.access_key.permission,
+                    AccessKeyPermission::FullAccess
+                ),
+            },
+            Action::DeleteKey(_) => ActionKind::DeleteKey,
+            Action::DeleteAccount(delete) => ActionKind::DeleteAccount {
+                beneficiary_id: &delete.beneficiary_id,
+            },
+            Action::Delegate(delegate) => ActionKind::Delegate {
+                sender_id: &delegate.delegate_action.sender_id,
+            },
+            _ => ActionKind::Other,
+        }
+    }
+}
+
+/// The data of one `Action` variant, e.g. `FunctionCallAction` for `Action::FunctionCall`.
+pub trait ActionData {
+    /// Returns the data of `action` if it is of this variant.
+    fn from_action(action: &Action) -> Option<&Self>;
+}
+
+macro_rules! action_data {
+    ($($variant:ident($data:ty) => $getter:ident),* $(,)?) => {
+        $(
+            impl ActionData for $data {
+                fn from_action(action: &Action) -> Option<&Self> {
+                    match action {
+                        Action::$variant(data) => Some(data.borrow()),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+
+        impl TransactionBuilder {
+            $(
+                #[doc = concat!("Returns the data of all `Action::", stringify!($variant), "` actions, in order.")]
+                pub fn $getter(&self) -> Vec<&$data> {
+                    self.actions_of_type::<$data>()
+                }
+            )*
+        }
+    };
+}
+
+action_data! {
+    CreateAccount(CreateAccountAction) => create_account_actions,
+    DeployContract(DeployContractAction) => deploy_contract_actions,
+    FunctionCall(FunctionCallAction) => function_call_actions,
+    Transfer(TransferAction) => transfer_actions,
+    Stake(StakeAction) => stake_actions,
+    AddKey(AddKeyAction) => add_key_actions,
+    DeleteKey(DeleteKeyAction) => delete_key_actions,
+    DeleteAccount(DeleteAccountAction) => delete_account_actions,
+    Delegate(SignedDelegateAction) => delegate_actions,
+}
+
+impl TransactionBuilder {
+    /// Returns a summary of each action, in order, e.g. to render actions with type-specific widgets.
+    pub fn action_kinds(&self) -> Vec<ActionKind<'_>> {
+        self.transaction
+            .actions()
+            .iter()
+            .map(ActionKind::from)
+            .collect()
+    }
+
+    /// Returns the positions of all actions for which `predicate` returns true.
+    pub fn action_positions_of(&self, predicate: impl Fn(&Action) -> bool) -> Vec<usize> {
+        self.transaction
+            .actions()
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| predicate(action))
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Returns the position and data of the first action of the variant holding `T`, e.g.
+    /// `first_action_of_type::<FunctionCallAction>()`.
+    pub fn first_action_of_type<T: ActionData>(&self) -> Option<(usize, &T)> {
+        self.transaction
+            .actions()
+            .iter()
+            .enumerate()
+            .find_map(|(position, action)| T::from_action(action).map(|data| (position, data)))
+    }
+
+    /// Returns the data of all actions of the variant holding `T`, in order.
+    pub fn actions_of_type<T: ActionData>(&self) -> Vec<&T> {
+        self.transaction
+            .actions()
+            .iter()
+            .filter_map(T::from_action)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, PublicKey};
+    use near_primitives::account::AccessKey;
+    use near_primitives::hash::CryptoHash;
+
+    fn builder() -> TransactionBuilder {
+        let mut builder = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "contract.near".parse().unwrap(),
+            1,
+            CryptoHash::default(),
+        );
+        builder
+            .transfer(1)
+            .function_call("a".to_string(), vec![], 10, 0)
+            .unwrap()
+            .transfer(2)
+            .function_call("b".to_string(), vec![], 20, 0)
+            .unwrap();
+        builder
+    }
+
+    #[test]
+    fn test_action_kinds() {
+        let mut builder = builder();
+        builder
+            .add_key(PublicKey::empty(KeyType::ED25519), AccessKey::full_access())
+            .delete_account("bob.near".parse().unwrap());
+
+        let beneficiary_id: AccountId = "bob.near".parse().unwrap();
+        assert_eq!(
+            builder.action_kinds(),
+            vec![
+                ActionKind::Transfer { deposit: 1 },
+                ActionKind::FunctionCall {
+                    method: "a",
+                    gas: 10,
+                    deposit: 0
+                },
+                ActionKind::Transfer { deposit: 2 },
+                ActionKind::FunctionCall {
+                    method: "b",
+                    gas: 20,
+                    deposit: 0
+                },
+                ActionKind::AddKey { full_access: true },
+                ActionKind::DeleteAccount {
+                    beneficiary_id: &beneficiary_id
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_action_positions_of() {
+        let builder = builder();
+        assert_eq!(
+            builder.action_positions_of(|action| matches!(action, Action::Transfer(_))),
+            vec![0, 2]
+        );
+        assert!(
+            builder
+                .action_positions_of(|action| matches!(action, Action::Stake(_)))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_first_action_of_type() {
+        let builder = builder();
+        let (position, call) = builder
+            .first_action_of_type::<FunctionCallAction>()
+            .unwrap();
+        assert_eq!(position, 1);
+        assert_eq!(call.method_name, "a");
+        assert_eq!(builder.first_action_of_type::<StakeAction>(), None);
+    }
+
+    #[test]
+    fn test_per_type_getters() {
+        let builder = builder();
+        let methods: Vec<&str> = builder
+            .function_call_actions()
+            .iter()
+            .map(|call| call.method_name.as_str())
+            .collect();
+        assert_eq!(methods, ["a", "b"]);
+        let deposits: Vec<u128> = builder
+            .transfer_actions()
+            .iter()
+            .map(|transfer| transfer.deposit)
+            .collect();
+        assert_eq!(deposits, [1, 2]);
+        assert!(builder.delete_account_actions().is_empty());
+    }
+}
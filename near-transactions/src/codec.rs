@@ -0,0 +1,122 @@
+//! Binary encodings of transactions.
+//!
+//! Borsh is the canonical NEAR format: it is what nodes accept and what signatures are computed over.
+//! CBOR, behind the `cbor` feature, is for interop only, e.g. storing transactions on IPFS or handing
+//! them to systems that already consume CBOR. It encodes the JSON representation of
+//! `TransactionBuilder::to_json`, including the version and priority fee of `V1` transactions.
+
+#[cfg(feature = "cbor")]
+use crate::TransactionBuilder;
+use crate::error::CodecError;
+use near_primitives::transaction::Transaction;
+
+/// A binary encoding of transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionCodec {
+    /// The canonical borsh serialization.
+    #[default]
+    Borsh,
+    /// CBOR, for interop with systems that don't speak borsh.
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl TransactionCodec {
+    /// Encodes `transaction` with this codec.
+    pub fn encode(&self, transaction: &Transaction) -> Result<Vec<u8>, CodecError> {
+        match self {
+            Self::Borsh => {
+                borsh::to_vec(transaction).map_err(|err| CodecError::Encode(err.to_string()))
+            }
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let json = TransactionBuilder {
+                    transaction: transaction.clone(),
+                }
+                .to_json();
+                let mut bytes = Vec::new();
+                ciborium::into_writer(&json, &mut bytes)
+                    .map_err(|err| CodecError::Encode(err.to_string()))?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Decodes a transaction encoded with this codec.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Transaction, CodecError> {
+        match self {
+            Self::Borsh => {
+                borsh::from_slice(bytes).map_err(|err| CodecError::Decode(err.to_string()))
+            }
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let json: serde_json::Value = ciborium::from_reader(bytes)
+                    .map_err(|err| CodecError::Decode(err.to_string()))?;
+                let builder = TransactionBuilder::from_json(json).map_err(CodecError::Parse)?;
+                Ok(builder.build())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransactionBuilder;
+    use near_crypto::{KeyType, PublicKey};
+    use near_primitives::hash::CryptoHash;
+
+    fn transaction() -> Transaction {
+        let mut builder = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            1,
+            CryptoHash::hash_bytes(b"block"),
+        );
+        builder
+            .transfer(1)
+            .function_call("a".to_string(), b"{}".to_vec(), 10, 0)
+            .unwrap();
+        builder.build()
+    }
+
+    #[test]
+    fn test_borsh_round_trip() {
+        let transaction = transaction();
+        let bytes = TransactionCodec::Borsh.encode(&transaction).unwrap();
+        assert_eq!(bytes, borsh::to_vec(&transaction).unwrap());
+        assert_eq!(TransactionCodec::Borsh.decode(&bytes).unwrap(), transaction);
+        assert!(TransactionCodec::Borsh.decode(&bytes[1..]).is_err());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_round_trip() {
+        let transaction = transaction();
+        let bytes = TransactionCodec::Cbor.encode(&transaction).unwrap();
+        assert_eq!(TransactionCodec::Cbor.decode(&bytes).unwrap(), transaction);
+        assert!(TransactionCodec::Cbor.decode(&[0xff]).is_err());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_round_trip_v1() {
+        let mut builder = TransactionBuilder::new_with_version(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            1,
+            CryptoHash::hash_bytes(b"block"),
+            crate::TransactionVersion::V1,
+        );
+        builder.transfer(1);
+        if let Transaction::V1(v1) = &mut builder.transaction {
+            v1.priority_fee = 3;
+        }
+        let transaction = builder.build();
+
+        let bytes = TransactionCodec::Cbor.encode(&transaction).unwrap();
+        assert_eq!(TransactionCodec::Cbor.decode(&bytes).unwrap(), transaction);
+    }
+}
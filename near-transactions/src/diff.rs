@@ -0,0 +1,204 @@
+//! Differences between two transactions, e.g. to show what modifying a transaction changed.
+
+use near_primitives::transaction::{Action, Transaction};
+use std::fmt;
+
+/// A header field of a transaction that differs between two transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// The name of the field, such as `receiver_id`.
+    pub field: &'static str,
+    /// The value before the change.
+    pub before: String,
+    /// The value after the change.
+    pub after: String,
+}
+
+/// An action that differs between two transactions, compared by position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionChange {
+    /// The action at `index` only exists in the second transaction.
+    Added { index: usize, action: Action },
+    /// The action at `index` only exists in the first transaction.
+    Removed { index: usize, action: Action },
+    /// The actions at `index` differ.
+    Modified {
+        index: usize,
+        before: Action,
+        after: Action,
+    },
+}
+
+/// The differences between two transactions, see `TransactionSummary::diff`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionDiff {
+    /// The changed header fields.
+    pub fields: Vec<FieldChange>,
+    /// The changed actions, in order of their index.
+    pub actions: Vec<ActionChange>,
+}
+
+impl TransactionDiff {
+    /// Returns true if the transactions are equal.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.actions.is_empty()
+    }
+}
+
+impl fmt::Display for TransactionDiff {
+    /// Formats the differences like a unified diff, with removed lines prefixed by `-`, added lines by `+`
+    /// and a `@@ action <index> @@` header before each changed action.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.fields {
+            writeln!(f, "-{}: {}", change.field, change.before)?;
+            writeln!(f, "+{}: {}", change.field, change.after)?;
+        }
+        for change in &self.actions {
+            match change {
+                ActionChange::Added { index, action } => {
+                    writeln!(f, "@@ action {} @@", index)?;
+                    writeln!(f, "+{:?}", action)?;
+                }
+                ActionChange::Removed { index, action } => {
+                    writeln!(f, "@@ action {} @@", index)?;
+                    writeln!(f, "-{:?}", action)?;
+                }
+                ActionChange::Modified {
+                    index,
+                    before,
+                    after,
+                } => {
+                    writeln!(f, "@@ action {} @@", index)?;
+                    writeln!(f, "-{:?}", before)?;
+                    writeln!(f, "+{:?}", after)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares transactions without any RPC call.
+pub struct TransactionSummary;
+
+impl TransactionSummary {
+    /// Lists the header fields and actions that differ between `before` and `after`.
+    ///
+    /// Actions are compared by position, so inserting an action shows every following action as
+    /// modified and the last one as added.
+    pub fn diff(before: &Transaction, after: &Transaction) -> TransactionDiff {
+        let mut diff = TransactionDiff::default();
+        let mut compare = |field, before: String, after: String| {
+            if before != after {
+                diff.fields.push(FieldChange {
+                    field,
+                    before,
+                    after,
+                });
+            }
+        };
+        compare(
+            "signer_id",
+            before.signer_id().to_string(),
+            after.signer_id().to_string(),
+        );
+        compare(
+            "public_key",
+            before.public_key().to_string(),
+            after.public_key().to_string(),
+        );
+        compare(
+            "nonce",
+            before.nonce().to_string(),
+            after.nonce().to_string(),
+        );
+        compare(
+            "receiver_id",
+            before.receiver_id().to_string(),
+            after.receiver_id().to_string(),
+        );
+        compare(
+            "block_hash",
+            before.block_hash().to_string(),
+            after.block_hash().to_string(),
+        );
+
+        let (before, after) = (before.actions(), after.actions());
+        for index in 0..before.len().max(after.len()) {
+            let change = match (before.get(index), after.get(index)) {
+                (Some(before), Some(after)) if before == after => continue,
+                (Some(before), Some(after)) => ActionChange::Modified {
+                    index,
+                    before: before.clone(),
+                    after: after.clone(),
+                },
+                (Some(action), None) => ActionChange::Removed {
+                    index,
+                    action: action.clone(),
+                },
+                (None, Some(action)) => ActionChange::Added {
+                    index,
+                    action: action.clone(),
+                },
+                (None, None) => unreachable!(),
+            };
+            diff.actions.push(change);
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransactionBuilder;
+    use crate::test_utils::{TEST_SIGNER_ID, dummy_builder, test_account, test_public_key};
+    use near_primitives::hash::CryptoHash;
+
+    #[test]
+    fn test_diff() {
+        let mut before = dummy_builder();
+        before.transfer(1).transfer(2);
+        let mut after = TransactionBuilder::new(
+            test_account(TEST_SIGNER_ID),
+            test_public_key(),
+            test_account("other.test.near"),
+            1,
+            CryptoHash::default(),
+        );
+        after.transfer(1).transfer(3).create_account();
+        let (before, after) = (before.build(), after.build());
+
+        let diff = TransactionSummary::diff(&before, &after);
+        assert_eq!(
+            diff.fields,
+            vec![FieldChange {
+                field: "receiver_id",
+                before: "receiver.test.near".to_string(),
+                after: "other.test.near".to_string(),
+            }]
+        );
+        assert_eq!(diff.actions.len(), 2);
+        assert!(matches!(
+            diff.actions[0],
+            ActionChange::Modified { index: 1, .. }
+        ));
+        assert!(matches!(
+            diff.actions[1],
+            ActionChange::Added {
+                index: 2,
+                action: Action::CreateAccount(_)
+            }
+        ));
+
+        let rendered = diff.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "-receiver_id: receiver.test.near");
+        assert_eq!(lines[1], "+receiver_id: other.test.near");
+        assert_eq!(lines[2], "@@ action 1 @@");
+        assert!(lines[3].starts_with("-Transfer"));
+        assert!(lines[4].starts_with("+Transfer"));
+
+        assert!(TransactionSummary::diff(&before, &before).is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+//! Error types shared by the `near-transactions` crate.
+
+use crate::near_token::ParseNearTokenError;
+use near_crypto::PublicKey;
+use near_primitives::types::Gas;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Errors that can occur while parsing a transaction from an external representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input is not valid JSON.
+    InvalidJson(String),
+    /// A required field is missing.
+    MissingField(String),
+    /// A field is present but its value is invalid.
+    InvalidField {
+        /// The name of the field.
+        field: String,
+        /// Why the value was rejected.
+        reason: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(reason) => write!(f, "invalid JSON: {}", reason),
+            Self::MissingField(field) => write!(f, "missing field `{}`", field),
+            Self::InvalidField { field, reason } => {
+                write!(f, "invalid field `{}`: {}", field, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Errors that can occur while encoding or decoding a transaction with a `TransactionCodec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    /// The transaction could not be encoded.
+    Encode(String),
+    /// The bytes are not a valid encoding.
+    Decode(String),
+    /// The decoded data does not describe a valid transaction.
+    Parse(ParseError),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(reason) => write!(f, "failed to encode transaction: {}", reason),
+            Self::Decode(reason) => write!(f, "failed to decode transaction: {}", reason),
+            Self::Parse(err) => write!(f, "invalid transaction: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// Errors that can occur while building a transaction or delegate action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionBuilderError {
+    /// A delegate action was placed inside another delegate action.
+    NestedDelegateAction,
+    /// A function call method name is empty or contains non-ASCII or control characters.
+    InvalidMethodName(String),
+    /// A function call attaches more gas than a single call may use.
+    GasExceedsMaximum {
+        /// The gas attached to the call.
+        gas: Gas,
+        /// The maximum gas of a single call.
+        max: Gas,
+    },
+    /// The transaction would contain more actions than the protocol allows.
+    TooManyActions {
+        /// The number of actions the transaction would contain.
+        count: usize,
+        /// The maximum number of actions.
+        max: usize,
+    },
+    /// A `CreateAccount` action is not the first action of the transaction.
+    CreateAccountNotFirst {
+        /// The index of the action.
+        index: usize,
+    },
+    /// A `DeleteAccount` action is not the last action of the transaction.
+    DeleteAccountNotLast {
+        /// The index of the action.
+        index: usize,
+    },
+    /// A sub account label contains a dot or does not form a valid account ID.
+    InvalidSubAccountLabel(String),
+    /// A contract's code is empty.
+    EmptyContractCode,
+    /// A contract's code is larger than the protocol allows.
+    ContractCodeTooLarge {
+        /// The size of the code in bytes.
+        size: usize,
+        /// The maximum size in bytes.
+        max: usize,
+    },
+    /// A gas reserve is larger than the total gas it is subtracted from.
+    GasReserveExceedsTotal {
+        /// The total gas.
+        total: Gas,
+        /// The reserved gas.
+        reserve: Gas,
+    },
+    /// A batch of key deletions included the key the transaction is signed with.
+    DeletesSigningKey(PublicKey),
+    /// The args file of a function call could not be read or is not valid JSON.
+    ArgsFile(ArgsFileError),
+    /// A deposit given in NEAR could not be parsed.
+    InvalidDeposit(ParseNearTokenError),
+}
+
+impl fmt::Display for TransactionBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NestedDelegateAction => write!(f, "delegate actions cannot be nested"),
+            Self::InvalidMethodName(method_name) => {
+                write!(f, "invalid method name {:?}", method_name)
+            }
+            Self::GasExceedsMaximum { gas, max } => {
+                write!(
+                    f,
+                    "function call gas {} exceeds the maximum of {}",
+                    gas, max
+                )
+            }
+            Self::TooManyActions { count, max } => {
+                write!(
+                    f,
+                    "transaction would contain {} actions, more than the maximum of {}",
+                    count, max
+                )
+            }
+            Self::CreateAccountNotFirst { index } => write!(
+                f,
+                "CreateAccount action at index {} must be the first action",
+                index
+            ),
+            Self::DeleteAccountNotLast { index } => write!(
+                f,
+                "DeleteAccount action at index {} must be the last action",
+                index
+            ),
+            Self::InvalidSubAccountLabel(label) => {
+                write!(f, "invalid sub account label {:?}", label)
+            }
+            Self::EmptyContractCode => write!(f, "contract code is empty"),
+            Self::ContractCodeTooLarge { size, max } => {
+                write!(
+                    f,
+                    "contract code of {} bytes exceeds the maximum of {} bytes",
+                    size, max
+                )
+            }
+            Self::GasReserveExceedsTotal { total, reserve } => {
+                write!(
+                    f,
+                    "gas reserve {} exceeds the total gas of {}",
+                    reserve, total
+                )
+            }
+            Self::DeletesSigningKey(public_key) => {
+                write!(f, "refusing to delete the signing key {}", public_key)
+            }
+            Self::ArgsFile(err) => write!(f, "{}", err),
+            Self::InvalidDeposit(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for TransactionBuilderError {}
+
+/// Errors that can occur while reading function call arguments from a file, see
+/// `TransactionBuilder::function_call_from_file`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgsFileError {
+    /// The file does not exist.
+    NotFound(PathBuf),
+    /// The file exists but could not be read.
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+        /// Why the file could not be read.
+        reason: String,
+    },
+    /// The file does not contain valid JSON.
+    InvalidJson {
+        /// The path of the file.
+        path: PathBuf,
+        /// Why the content was rejected.
+        reason: String,
+    },
+}
+
+impl fmt::Display for ArgsFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "args file {} not found", path.display()),
+            Self::Io { path, reason } => {
+                write!(f, "failed to read args file {}: {}", path.display(), reason)
+            }
+            Self::InvalidJson { path, reason } => {
+                write!(
+                    f,
+                    "args file {} is not valid JSON: {}",
+                    path.display(),
+                    reason
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgsFileError {}
+
+/// A transaction did not contain the expected number of actions, see
+/// `TransactionBuilder::assert_action_count_range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrongActionCount {
+    /// The number of actions in the transaction.
+    pub actual: usize,
+    /// The minimum number of actions expected.
+    pub min: usize,
+    /// The maximum number of actions expected.
+    pub max: usize,
+}
+
+impl fmt::Display for WrongActionCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "expected {} actions, found {}", self.min, self.actual)
+        } else {
+            write!(
+                f,
+                "expected {} to {} actions, found {}",
+                self.min, self.max, self.actual
+            )
+        }
+    }
+}
+
+impl std::error::Error for WrongActionCount {}
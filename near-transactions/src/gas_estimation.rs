@@ -0,0 +1,117 @@
+//! Estimates the gas a transaction burns from the per-action fees of the protocol's `RuntimeConfig`.
+
+use crate::TransactionBuilder;
+use near_parameters::{ActionCosts, RuntimeConfig};
+use near_primitives::account::AccessKeyPermission;
+use near_primitives::transaction::Action;
+use near_primitives::types::Gas;
+
+impl TransactionBuilder {
+    /// Estimates the gas needed by the transaction: the send and execution fees of its receipt and each
+    /// of its actions according to `config`, plus the gas prepaid to every function call.
+    ///
+    /// The estimate does not include the extra fees of transfers that implicitly create an account, nor
+    /// gas burnt by receipts the called contracts create themselves.
+    ///
+    /// The config a network currently runs with is
+    /// `RuntimeConfigStore::for_chain_id(chain_id).get_config(protocol_version)`, for the chain id and
+    /// protocol version reported by a node's status.
+    pub fn estimate_action_gas(&self, config: &RuntimeConfig) -> Gas {
+        let sender_is_receiver = self.transaction.signer_id() == self.transaction.receiver_id();
+        let fee = |cost: ActionCosts, count: u64| {
+            let fee = config.fees.fee(cost);
+            fee.send_fee(sender_is_receiver)
+                .saturating_add(fee.exec_fee())
+                .saturating_mul(count)
+        };
+
+        self.transaction
+            .actions()
+            .iter()
+            .map(|action| match action {
+                Action::CreateAccount(_) => fee(ActionCosts::create_account, 1),
+                Action::DeployContract(deploy) => fee(ActionCosts::deploy_contract_base, 1)
+                    .saturating_add(fee(
+                        ActionCosts::deploy_contract_byte,
+                        deploy.code.len() as u64,
+                    )),
+                Action::FunctionCall(call) => fee(ActionCosts::function_call_base, 1)
+                    .saturating_add(fee(
+                        ActionCosts::function_call_byte,
+                        (call.method_name.len() + call.args.len()) as u64,
+                    ))
+                    .saturating_add(call.gas),
+                Action::Transfer(_) => fee(ActionCosts::transfer, 1),
+                Action::Stake(_) => fee(ActionCosts::stake, 1),
+                Action::AddKey(add_key) => match &add_key.access_key.permission {
+                    AccessKeyPermission::FunctionCall(permission) => {
+                        let method_bytes: usize =
+                            permission.method_names.iter().map(String::len).sum();
+                        fee(ActionCosts::add_function_call_key_base, 1).saturating_add(fee(
+                            ActionCosts::add_function_call_key_byte,
+                            method_bytes as u64,
+                        ))
+                    }
+                    AccessKeyPermission::FullAccess => fee(ActionCosts::add_full_access_key, 1),
+                },
+                Action::DeleteKey(_) => fee(ActionCosts::delete_key, 1),
+                Action::DeleteAccount(_) => fee(ActionCosts::delete_account, 1),
+                Action::Delegate(_) => fee(ActionCosts::delegate, 1),
+                // Global contract actions are not covered by the estimate.
+                _ => 0,
+            })
+            .fold(fee(ActionCosts::new_action_receipt, 1), Gas::saturating_add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, PublicKey};
+    use near_primitives::hash::CryptoHash;
+
+    fn builder() -> TransactionBuilder {
+        TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            1,
+            CryptoHash::default(),
+        )
+    }
+
+    #[test]
+    fn test_estimate_action_gas() {
+        let config = RuntimeConfig::test();
+        let total_fee = |cost: ActionCosts| {
+            let fee = config.fees.fee(cost);
+            fee.send_fee(false) + fee.exec_fee()
+        };
+
+        let mut builder = builder();
+        builder
+            .transfer(1)
+            .function_call("set".to_string(), b"{}".to_vec(), 10_000_000_000_000, 0)
+            .unwrap();
+
+        let expected = total_fee(ActionCosts::new_action_receipt)
+            + total_fee(ActionCosts::transfer)
+            + total_fee(ActionCosts::function_call_base)
+            + 5 * total_fee(ActionCosts::function_call_byte)
+            + 10_000_000_000_000;
+        assert_eq!(builder.estimate_action_gas(&config), expected);
+    }
+
+    #[test]
+    fn test_estimate_action_gas_free_config() {
+        let mut builder = builder();
+        builder
+            .transfer(1)
+            .function_call("set".to_string(), Vec::new(), 10_000_000_000_000, 0)
+            .unwrap();
+        assert_eq!(
+            builder.estimate_action_gas(&RuntimeConfig::free()),
+            10_000_000_000_000
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! JSON representation of transactions for debugging and JSON RPC tooling.
+//!
+//! The format mirrors the transactions returned by the NEAR JSON RPC (e.g. by `tx` or `block`):
+//! snake_case fields, public keys as `ed25519:...` strings, the block hash in base58 and actions as
+//! `ActionView`s. `V1` transactions additionally carry `"version": 1` and their `priority_fee`, so they
+//! round-trip without changing their hash. Note that the canonical wire format of transactions is borsh, not JSON; signatures are
+//! always computed over the borsh serialization.
+
+use crate::{ParseError, TransactionBuilder, TransactionVersion};
+use near_crypto::PublicKey;
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::{Action, Transaction};
+use near_primitives::types::{AccountId, Nonce};
+use near_primitives::views::ActionView;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Serialize, Deserialize)]
+struct TransactionJson {
+    signer_id: AccountId,
+    public_key: PublicKey,
+    nonce: Nonce,
+    receiver_id: AccountId,
+    block_hash: CryptoHash,
+    actions: Vec<ActionView>,
+    /// `0` for `Transaction::V0` and `1` for `Transaction::V1`.
+    #[serde(default, skip_serializing_if = "is_v0")]
+    version: u8,
+    /// The priority fee of a `Transaction::V1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority_fee: Option<u64>,
+}
+
+fn is_v0(version: &u8) -> bool {
+    *version == 0
+}
+
+impl TransactionBuilder {
+    /// Serializes the transaction to JSON in the format used by the NEAR JSON RPC.
+    ///
+    /// This is meant for debugging and tooling only; the canonical wire format is borsh.
+    pub fn to_json(&self) -> Value {
+        let mut transaction = TransactionJson {
+            signer_id: self.transaction.signer_id().clone(),
+            public_key: self.transaction.public_key().clone(),
+            nonce: self.transaction.nonce(),
+            receiver_id: self.transaction.receiver_id().clone(),
+            block_hash: *self.transaction.block_hash(),
+            actions: self
+                .transaction
+                .actions()
+                .iter()
+                .cloned()
+                .map(ActionView::from)
+                .collect(),
+            version: 0,
+            priority_fee: None,
+        };
+        if let Transaction::V1(v1) = &self.transaction {
+            transaction.version = 1;
+            transaction.priority_fee = Some(v1.priority_fee);
+        }
+        json!(transaction)
+    }
+
+    /// Parses a transaction in the JSON format produced by `to_json`.
+    ///
+    /// # Returns
+    ///
+    /// A `TransactionBuilder` holding the parsed transaction, or a `ParseError` if the JSON does not
+    /// describe a valid transaction.
+    pub fn from_json(value: Value) -> Result<Self, ParseError> {
+        let transaction: TransactionJson = serde_json::from_value(value)
+            .map_err(|err| ParseError::InvalidJson(err.to_string()))?;
+
+        let version = match (transaction.version, transaction.priority_fee) {
+            (0, None) => TransactionVersion::V0,
+            (1, _) => TransactionVersion::V1,
+            (0, Some(_)) => {
+                return Err(ParseError::InvalidField {
+                    field: "priority_fee".to_string(),
+                    reason: "only version 1 transactions have a priority fee".to_string(),
+                });
+            }
+            (version, _) => {
+                return Err(ParseError::InvalidField {
+                    field: "version".to_string(),
+                    reason: format!("unsupported transaction version {}", version),
+                });
+            }
+        };
+        let mut builder = TransactionBuilder::new_with_version(
+            transaction.signer_id,
+            transaction.public_key,
+            transaction.receiver_id,
+            transaction.nonce,
+            transaction.block_hash,
+            version,
+        );
+        if let Transaction::V1(v1) = &mut builder.transaction {
+            v1.priority_fee = transaction.priority_fee.unwrap_or_default();
+        }
+        for action in transaction.actions {
+            let action = Action::try_from(action).map_err(|err| ParseError::InvalidField {
+                field: "actions".to_string(),
+                reason: err.to_string(),
+            })?;
+            builder.add_action(action);
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::account::AccessKey;
+
+    fn assert_round_trip(add_action: impl FnOnce(&mut TransactionBuilder)) {
+        let mut builder = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            7,
+            CryptoHash::default(),
+        );
+        add_action(&mut builder);
+
+        let parsed = TransactionBuilder::from_json(builder.to_json()).unwrap();
+        assert_eq!(parsed, builder);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let public_key = PublicKey::empty(KeyType::ED25519);
+        assert_round_trip(|builder| {
+            builder.create_account();
+        });
+        assert_round_trip(|builder| {
+            builder.deploy_contract(&[0, 97, 115, 109]);
+        });
+        assert_round_trip(|builder| {
+            builder
+                .function_call("set".to_string(), b"{}".to_vec(), 30_000_000_000_000, 1)
+                .unwrap();
+        });
+        assert_round_trip(|builder| {
+            builder.transfer(1_000_000_000_000_000_000_000_000);
+        });
+        assert_round_trip(|builder| {
+            builder.stake(1_000, public_key.clone());
+        });
+        assert_round_trip(|builder| {
+            builder.add_key(public_key.clone(), AccessKey::full_access());
+        });
+        assert_round_trip(|builder| {
+            builder.add_single_method_key(
+                public_key.clone(),
+                "app.near".parse().unwrap(),
+                "set",
+                Some(1),
+            );
+        });
+        assert_round_trip(|builder| {
+            builder.delete_key(public_key.clone());
+        });
+        assert_round_trip(|builder| {
+            builder.delete_account("carol.near".parse().unwrap());
+        });
+    }
+
+    #[test]
+    fn test_json_round_trip_v1() {
+        let mut builder = TransactionBuilder::new_with_version(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            7,
+            CryptoHash::default(),
+            TransactionVersion::V1,
+        );
+        builder.transfer(1);
+        if let Transaction::V1(v1) = &mut builder.transaction {
+            v1.priority_fee = 5;
+        }
+
+        let json = builder.to_json();
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["priority_fee"], 5);
+        let parsed = TransactionBuilder::from_json(json).unwrap();
+        assert_eq!(parsed, builder);
+        assert_eq!(
+            parsed.build().get_hash_and_size(),
+            builder.build().get_hash_and_size()
+        );
+
+        let mut invalid = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            7,
+            CryptoHash::default(),
+        )
+        .to_json();
+        invalid["priority_fee"] = json!(5);
+        assert!(TransactionBuilder::from_json(invalid.clone()).is_err());
+        invalid["version"] = json!(2);
+        assert!(TransactionBuilder::from_json(invalid).is_err());
+    }
+
+    #[test]
+    fn test_to_json_format() {
+        let mut builder = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            7,
+            CryptoHash::default(),
+        );
+        builder.transfer(1);
+
+        let json = builder.to_json();
+        assert_eq!(json["signer_id"], "alice.near");
+        assert_eq!(json["nonce"], 7);
+        assert_eq!(json["actions"][0]["Transfer"]["deposit"], "1");
+        assert!(json.get("version").is_none());
+        assert!(json.get("priority_fee").is_none());
+    }
+}
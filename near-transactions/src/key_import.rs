@@ -0,0 +1,187 @@
+//! Imports raw ed25519 secret keys, e.g. exported by NEAR wallets.
+//!
+//! Wallets export either the 32-byte ed25519 seed or the 64-byte expanded form, which is the seed
+//! followed by the public key. The latter is the form NEAR uses in `ed25519:...` secret key strings.
+
+use near_crypto::{ED25519SecretKey, PublicKey, SecretKey};
+use std::fmt;
+
+/// Errors that can occur while importing a raw secret key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyImportError {
+    /// The key is neither 32 nor 64 bytes long.
+    InvalidLength(usize),
+    /// The public key half of an expanded secret key does not belong to its seed half.
+    PublicKeyMismatch,
+    /// A `<curve>:<base58>` private key string could not be parsed.
+    InvalidPrivateKey {
+        /// Why the key was rejected.
+        reason: String,
+    },
+}
+
+impl fmt::Display for KeyImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "expected a 32 or 64 byte secret key, got {} bytes", len)
+            }
+            Self::PublicKeyMismatch => {
+                write!(f, "the public key does not match the secret key")
+            }
+            Self::InvalidPrivateKey { reason } => write!(f, "invalid private key: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for KeyImportError {}
+
+/// Checks that the public key half of a 64-byte expanded ed25519 secret key matches its seed half.
+fn check_expanded_secret_key(bytes: &[u8; 64]) -> Result<(), KeyImportError> {
+    ed25519_dalek::SigningKey::from_keypair_bytes(bytes)
+        .map(|_| ())
+        .map_err(|_| KeyImportError::PublicKeyMismatch)
+}
+
+/// A secret key together with its public key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyPair {
+    pub secret_key: SecretKey,
+    pub public_key: PublicKey,
+}
+
+impl fmt::Debug for KeyPair {
+    /// Formats the public key, leaving out the secret key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyPair {
+    /// Imports a 64-byte expanded ed25519 secret key, checking that its public key half matches its
+    /// seed half.
+    pub fn from_expanded_secret_key(bytes: &[u8; 64]) -> Result<Self, KeyImportError> {
+        check_expanded_secret_key(bytes)?;
+        Ok(Self::from_secret_key(SecretKey::ED25519(ED25519SecretKey(
+            *bytes,
+        ))))
+    }
+
+    /// Imports an ed25519 secret key given either as a 32-byte seed or as a 64-byte expanded secret
+    /// key. Expanded keys are checked like in `from_expanded_secret_key`.
+    pub fn from_near_implicit_bytes(bytes: &[u8]) -> Result<Self, KeyImportError> {
+        if let Ok(seed) = <&[u8; 32]>::try_from(bytes) {
+            let signing_key = ed25519_dalek::SigningKey::from_bytes(seed);
+            Ok(Self::from_secret_key(SecretKey::ED25519(ED25519SecretKey(
+                signing_key.to_keypair_bytes(),
+            ))))
+        } else if let Ok(expanded) = <&[u8; 64]>::try_from(bytes) {
+            Self::from_expanded_secret_key(expanded)
+        } else {
+            Err(KeyImportError::InvalidLength(bytes.len()))
+        }
+    }
+
+    /// Parses a private key in NEAR's `<curve>:<base58>` string format, e.g. `ed25519:...` as found in
+    /// `near-cli` credential files.
+    ///
+    /// For ed25519 keys, the public key half of the expanded secret key must match its seed half.
+    pub fn from_near_key_string(s: &str) -> Result<Self, KeyImportError> {
+        let secret_key =
+            s.trim()
+                .parse::<SecretKey>()
+                .map_err(|err| KeyImportError::InvalidPrivateKey {
+                    reason: err.to_string(),
+                })?;
+        if let SecretKey::ED25519(ED25519SecretKey(bytes)) = &secret_key {
+            check_expanded_secret_key(bytes).map_err(|err| KeyImportError::InvalidPrivateKey {
+                reason: err.to_string(),
+            })?;
+        }
+        Ok(Self::from_secret_key(secret_key))
+    }
+
+    fn from_secret_key(secret_key: SecretKey) -> Self {
+        Self {
+            public_key: secret_key.public_key(),
+            secret_key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Exported by the NEAR wallet for the seed phrase
+    // "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+    // derived with the wallet's path m/44'/397'/0'.
+    const WALLET_SECRET_KEY: &str = "ed25519:F1kPR175szkGxEL52A9H6Z5ocS2BtaipprK2Hiob9DjGzHTkScrBb1yt44baXPZ3LxyHcsTPdBjHmn6zx147txH";
+    const WALLET_PUBLIC_KEY: &str = "ed25519:6j4b6zUaty6fD1awqcGCCU9JYGCWYUgdJhQrzfZhqE25";
+
+    fn wallet_key_bytes() -> [u8; 64] {
+        let SecretKey::ED25519(ED25519SecretKey(bytes)) = WALLET_SECRET_KEY.parse().unwrap() else {
+            panic!("Expected an ed25519 secret key");
+        };
+        bytes
+    }
+
+    #[test]
+    fn test_import_seed_and_expanded_key() {
+        let expanded = wallet_key_bytes();
+        let from_seed = KeyPair::from_near_implicit_bytes(&expanded[..32]).unwrap();
+        let from_expanded = KeyPair::from_expanded_secret_key(&expanded).unwrap();
+
+        assert_eq!(from_seed, from_expanded);
+        assert_eq!(from_seed.public_key, WALLET_PUBLIC_KEY.parse().unwrap());
+        assert_eq!(
+            KeyPair::from_near_implicit_bytes(&expanded).unwrap(),
+            from_expanded
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_keys() {
+        let mut expanded = wallet_key_bytes();
+        expanded[63] ^= 1;
+        assert_eq!(
+            KeyPair::from_expanded_secret_key(&expanded),
+            Err(KeyImportError::PublicKeyMismatch)
+        );
+        assert_eq!(
+            KeyPair::from_near_implicit_bytes(&expanded),
+            Err(KeyImportError::PublicKeyMismatch)
+        );
+        assert_eq!(
+            KeyPair::from_near_implicit_bytes(&[0; 48]),
+            Err(KeyImportError::InvalidLength(48))
+        );
+    }
+
+    #[test]
+    fn test_key_pair_from_near_key_string() {
+        let key_pair = KeyPair::from_near_key_string(WALLET_SECRET_KEY).unwrap();
+        assert_eq!(
+            key_pair,
+            KeyPair::from_expanded_secret_key(&wallet_key_bytes()).unwrap()
+        );
+        assert_eq!(key_pair.public_key, WALLET_PUBLIC_KEY.parse().unwrap());
+
+        for invalid in ["", "ed25519", "ed25519:0OIl", "rsa:abc", "ed25519:3yZe7d"] {
+            assert!(matches!(
+                KeyPair::from_near_key_string(invalid),
+                Err(KeyImportError::InvalidPrivateKey { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_debug_omits_secret_key() {
+        let key_pair = KeyPair::from_near_key_string(WALLET_SECRET_KEY).unwrap();
+        let debug = format!("{:?}", key_pair);
+        assert!(!debug.contains(WALLET_SECRET_KEY.trim_start_matches("ed25519:")));
+        assert!(debug.contains(WALLET_PUBLIC_KEY.trim_start_matches("ed25519:")));
+    }
+}
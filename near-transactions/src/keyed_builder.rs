@@ -0,0 +1,126 @@
+//! A `TransactionBuilder` holding the key pair of its signer, so the transaction can be signed without
+//! passing the key again.
+
+use crate::TransactionBuilder;
+use crate::key_import::KeyPair;
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::{SignedTransaction, Transaction};
+use near_primitives::types::{AccountId, Nonce};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// A `TransactionBuilder` together with the key pair its transaction is signed with, see
+/// `TransactionBuilder::with_signer_and_key`.
+///
+/// All builder methods are available through `Deref`. With the `zeroize` feature, the bytes of an
+/// ed25519 secret key are wiped from memory when the builder is dropped.
+#[derive(Clone)]
+pub struct KeyedTransactionBuilder {
+    builder: TransactionBuilder,
+    key_pair: KeyPair,
+}
+
+impl TransactionBuilder {
+    /// Creates a builder for a transaction from `signer_id` signed with `key_pair`, which is kept so
+    /// `build_and_sign` needs no signer.
+    pub fn with_signer_and_key(
+        signer_id: AccountId,
+        key_pair: KeyPair,
+        receiver_id: AccountId,
+        nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> KeyedTransactionBuilder {
+        KeyedTransactionBuilder {
+            builder: TransactionBuilder::new(
+                signer_id,
+                key_pair.public_key.clone(),
+                receiver_id,
+                nonce,
+                block_hash,
+            ),
+            key_pair,
+        }
+    }
+}
+
+impl KeyedTransactionBuilder {
+    /// Returns the unsigned transaction.
+    pub fn build(&self) -> Transaction {
+        self.builder.transaction.clone()
+    }
+
+    /// Signs the transaction with the stored key pair.
+    pub fn build_and_sign(&self) -> SignedTransaction {
+        let secret_key = &self.key_pair.secret_key;
+        self.builder.sign_with(|hash| secret_key.sign(hash))
+    }
+}
+
+impl Deref for KeyedTransactionBuilder {
+    type Target = TransactionBuilder;
+
+    fn deref(&self) -> &TransactionBuilder {
+        &self.builder
+    }
+}
+
+impl DerefMut for KeyedTransactionBuilder {
+    fn deref_mut(&mut self) -> &mut TransactionBuilder {
+        &mut self.builder
+    }
+}
+
+impl fmt::Debug for KeyedTransactionBuilder {
+    /// Formats the builder and the public key, leaving out the secret key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedTransactionBuilder")
+            .field("builder", &self.builder)
+            .field("public_key", &self.key_pair.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for KeyedTransactionBuilder {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        if let near_crypto::SecretKey::ED25519(key) = &mut self.key_pair.secret_key {
+            key.0.zeroize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{TEST_RECEIVER_ID, TEST_SIGNER_ID, test_account, test_secret_key};
+    use crate::{KeyPair, TransactionBuilder};
+    use near_primitives::hash::CryptoHash;
+
+    #[test]
+    fn test_build_and_sign() {
+        let secret_key = test_secret_key();
+        let key_pair = KeyPair {
+            public_key: secret_key.public_key(),
+            secret_key,
+        };
+        let mut builder = TransactionBuilder::with_signer_and_key(
+            test_account(TEST_SIGNER_ID),
+            key_pair.clone(),
+            test_account(TEST_RECEIVER_ID),
+            1,
+            CryptoHash::default(),
+        );
+        builder.transfer(1);
+
+        let signed = builder.build_and_sign();
+        assert_eq!(signed.transaction, builder.build());
+        assert_eq!(signed.transaction.public_key(), &key_pair.public_key);
+        assert!(
+            signed
+                .signature
+                .verify(signed.get_hash().as_ref(), &key_pair.public_key)
+        );
+        assert!(!format!("{:?}", builder).contains(&key_pair.secret_key.to_string()));
+    }
+}
@@ -0,0 +1,520 @@
+//! Provides `LedgerSigner`, an `AsyncSigner` backed by the NEAR app on a Ledger hardware wallet.
+//!
+//! The device is accessed over HID, or any other `LedgerTransport`. Signing requires the user to review
+//! and confirm the transaction on the device, which can take a while; the signer therefore waits up to
+//! `CONFIRMATION_TIMEOUT` and reports its progress through `LedgerSigner::subscribe`.
+//!
+//! HID exchanges block and cannot be cancelled. When the timeout expires, the pending exchange keeps
+//! running until the device answers or is disconnected, and the signer rejects further requests with
+//! `LedgerError::Busy` until then.
+
+use crate::signer::AsyncSigner;
+use async_trait::async_trait;
+use ledger_apdu::APDUCommand;
+use ledger_transport_hid::{TransportNativeHID, hidapi::HidApi};
+use near_crypto::{ED25519PublicKey, PublicKey, Signature};
+use near_primitives::transaction::Transaction;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedMutexGuard, watch};
+
+/// The default HD path of NEAR accounts on Ledger devices.
+pub const DEFAULT_LEDGER_PATH: &str = "44'/397'/0'/0'/1'";
+
+/// How long to wait for the user to confirm a transaction on the device.
+pub const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+const CLA: u8 = 0x80;
+const INS_GET_PUBLIC_KEY: u8 = 0x04;
+const INS_SIGN_TRANSACTION: u8 = 0x02;
+const P1_GET_PUBLIC_KEY_SILENT: u8 = 0x00;
+const P1_SIGN_MORE_CHUNKS: u8 = 0x00;
+const P1_SIGN_LAST_CHUNK: u8 = 0x80;
+const NETWORK_ID: u8 = b'W';
+const CHUNK_SIZE: usize = 250;
+const SW_OK: u16 = 0x9000;
+const SW_USER_REJECTED: u16 = 0x6985;
+
+/// Errors that can occur while communicating with a Ledger device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    /// The device could not be reached over HID.
+    Transport(String),
+    /// The HD path could not be parsed.
+    InvalidPath(String),
+    /// The user rejected the request on the device.
+    Rejected,
+    /// The user did not confirm the request within `CONFIRMATION_TIMEOUT`.
+    Timeout,
+    /// The NEAR app answered with an unexpected status word.
+    Status(u16),
+    /// The NEAR app answered with a malformed payload.
+    UnexpectedResponse(String),
+    /// Another request to the device, possibly one that timed out, has not completed yet.
+    Busy,
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(reason) => write!(f, "ledger transport error: {}", reason),
+            Self::InvalidPath(path) => write!(f, "invalid HD path: {}", path),
+            Self::Rejected => write!(f, "request rejected on the ledger device"),
+            Self::Timeout => write!(f, "timed out waiting for confirmation on the ledger device"),
+            Self::Status(status) => write!(f, "ledger returned status {:#06x}", status),
+            Self::UnexpectedResponse(reason) => write!(f, "unexpected ledger response: {}", reason),
+            Self::Busy => write!(f, "another request to the ledger device is still pending"),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+/// The progress of a signing request, as reported by `LedgerSigner::subscribe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningState {
+    /// No signing request is in progress.
+    Idle,
+    /// The transaction is being sent to the device.
+    Sending,
+    /// The device is waiting for the user to confirm the transaction.
+    WaitingForConfirmation,
+    /// The transaction was signed.
+    Signed,
+    /// Signing failed or was rejected.
+    Failed,
+}
+
+/// The channel over which APDU commands reach a Ledger device.
+///
+/// Implemented for `TransportNativeHID`. Exchanges may block, they are run on tokio's blocking pool.
+pub trait LedgerTransport: Send + Sync {
+    /// Sends `command` to the device and returns the data and status word of its answer.
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<(Vec<u8>, u16), LedgerError>;
+}
+
+impl LedgerTransport for TransportNativeHID {
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<(Vec<u8>, u16), LedgerError> {
+        let answer = TransportNativeHID::exchange(self, command)
+            .map_err(|err| LedgerError::Transport(err.to_string()))?;
+        Ok((answer.data().to_vec(), answer.retcode()))
+    }
+}
+
+/// Signs transactions with the NEAR app on a Ledger hardware wallet.
+pub struct LedgerSigner {
+    transport: Arc<dyn LedgerTransport>,
+    /// Held for as long as an exchange with the device is running, including after a timeout.
+    pending: Arc<Mutex<()>>,
+    path: Vec<u8>,
+    public_key: PublicKey,
+    state: Arc<watch::Sender<SigningState>>,
+}
+
+impl LedgerSigner {
+    /// Connects to the first Ledger device found and loads the public key at `DEFAULT_LEDGER_PATH`.
+    ///
+    /// The NEAR app must be open on the device.
+    pub async fn connect() -> Result<Self, LedgerError> {
+        Self::connect_with_path(DEFAULT_LEDGER_PATH).await
+    }
+
+    /// Connects to the first Ledger device found and signs with the key at the HD path `path`, e.g.
+    /// `44'/397'/0'/0'/2'` for a second account.
+    ///
+    /// The NEAR app must be open on the device.
+    pub async fn connect_with_path(path: &str) -> Result<Self, LedgerError> {
+        let api = HidApi::new().map_err(|err| LedgerError::Transport(err.to_string()))?;
+        let transport =
+            TransportNativeHID::new(&api).map_err(|err| LedgerError::Transport(err.to_string()))?;
+        Self::with_transport(Arc::new(transport), path).await
+    }
+
+    /// Signs with the key at the HD path `path` of the device reached through `transport`.
+    ///
+    /// The NEAR app must be open on the device.
+    pub async fn with_transport(
+        transport: Arc<dyn LedgerTransport>,
+        path: &str,
+    ) -> Result<Self, LedgerError> {
+        let path = encode_path(path)?;
+        let pending = Arc::new(Mutex::new(()));
+        let guard = pending
+            .clone()
+            .try_lock_owned()
+            .expect("a new mutex is unlocked");
+        let public_key = fetch_public_key(transport.clone(), guard, path.clone()).await?;
+        let (state, _) = watch::channel(SigningState::Idle);
+        Ok(Self {
+            transport,
+            pending,
+            path,
+            public_key,
+            state: Arc::new(state),
+        })
+    }
+
+    /// Fetches the public key of the account at the HD path `account_path`, e.g. `44'/397'/0'/0'/1'`.
+    ///
+    /// Fails with `LedgerError::Busy` while a signing request is pending.
+    pub async fn get_public_key(&self, account_path: &str) -> Result<PublicKey, LedgerError> {
+        let path = encode_path(account_path)?;
+        fetch_public_key(self.transport.clone(), self.lock()?, path).await
+    }
+
+    /// Reserves the device for one request, failing if another one has not completed yet.
+    fn lock(&self) -> Result<OwnedMutexGuard<()>, LedgerError> {
+        self.pending
+            .clone()
+            .try_lock_owned()
+            .map_err(|_| LedgerError::Busy)
+    }
+
+    /// Returns a receiver reporting the progress of signing requests.
+    pub fn subscribe(&self) -> watch::Receiver<SigningState> {
+        self.state.subscribe()
+    }
+}
+
+#[async_trait]
+impl AsyncSigner for LedgerSigner {
+    type Error = LedgerError;
+
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    /// Sends the borsh-serialized transaction to the device, which displays it, hashes it and signs the
+    /// hash once the user confirms.
+    ///
+    /// Fails with `LedgerError::Busy` while another request is pending. A request that timed out stays
+    /// pending until the device answers it, since the blocking HID exchange cannot be cancelled.
+    async fn sign_transaction(&self, transaction: &Transaction) -> Result<Signature, Self::Error> {
+        let guard = self.lock()?;
+        self.state.send_replace(SigningState::Sending);
+
+        let mut payload = self.path.clone();
+        payload.extend(
+            borsh::to_vec(transaction)
+                .map_err(|err| LedgerError::UnexpectedResponse(err.to_string()))?,
+        );
+        let transport = self.transport.clone();
+        let state = self.state.clone();
+        let request = tokio::task::spawn_blocking(move || {
+            let _guard = guard;
+            let chunks: Vec<&[u8]> = payload.chunks(CHUNK_SIZE).collect();
+            let mut response = Vec::new();
+            for (index, chunk) in chunks.iter().enumerate() {
+                let last = index + 1 == chunks.len();
+                if last {
+                    state.send_replace(SigningState::WaitingForConfirmation);
+                }
+                let p1 = if last {
+                    P1_SIGN_LAST_CHUNK
+                } else {
+                    P1_SIGN_MORE_CHUNKS
+                };
+                response = exchange(&*transport, INS_SIGN_TRANSACTION, p1, chunk.to_vec())?;
+            }
+            Ok::<_, LedgerError>(response)
+        });
+
+        let result = match tokio::time::timeout(CONFIRMATION_TIMEOUT, request).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(err)) => Err(LedgerError::Transport(err.to_string())),
+            Err(_) => Err(LedgerError::Timeout),
+        }
+        .and_then(|response| {
+            Signature::from_parts(near_crypto::KeyType::ED25519, &response)
+                .map_err(|err| LedgerError::UnexpectedResponse(err.to_string()))
+        });
+
+        self.state.send_replace(match result {
+            Ok(_) => SigningState::Signed,
+            Err(_) => SigningState::Failed,
+        });
+        result
+    }
+}
+
+async fn fetch_public_key(
+    transport: Arc<dyn LedgerTransport>,
+    guard: OwnedMutexGuard<()>,
+    path: Vec<u8>,
+) -> Result<PublicKey, LedgerError> {
+    let response = tokio::task::spawn_blocking(move || {
+        let _guard = guard;
+        exchange(
+            &*transport,
+            INS_GET_PUBLIC_KEY,
+            P1_GET_PUBLIC_KEY_SILENT,
+            path,
+        )
+    })
+    .await
+    .map_err(|err| LedgerError::Transport(err.to_string()))??;
+
+    let bytes: [u8; 32] = response.as_slice().try_into().map_err(|_| {
+        LedgerError::UnexpectedResponse(format!("public key of {} bytes", response.len()))
+    })?;
+    Ok(PublicKey::ED25519(ED25519PublicKey(bytes)))
+}
+
+fn exchange(
+    transport: &dyn LedgerTransport,
+    ins: u8,
+    p1: u8,
+    data: Vec<u8>,
+) -> Result<Vec<u8>, LedgerError> {
+    let command = APDUCommand {
+        cla: CLA,
+        ins,
+        p1,
+        p2: NETWORK_ID,
+        data,
+    };
+    let (data, status) = transport.exchange(&command)?;
+    match status {
+        SW_OK => Ok(data),
+        SW_USER_REJECTED => Err(LedgerError::Rejected),
+        status => Err(LedgerError::Status(status)),
+    }
+}
+
+/// Encodes an HD path like `44'/397'/0'/0'/1'` as the big-endian `u32` components expected by the device.
+///
+/// Every component must be hardened, since ed25519 keys can only be derived along hardened paths.
+fn encode_path(path: &str) -> Result<Vec<u8>, LedgerError> {
+    let invalid = || LedgerError::InvalidPath(path.to_string());
+    let components = path.strip_prefix("m/").unwrap_or(path);
+
+    let mut encoded = Vec::new();
+    for component in components.split('/') {
+        let index: u32 = component
+            .strip_suffix('\'')
+            .ok_or_else(invalid)?
+            .parse()
+            .map_err(|_| invalid())?;
+        if index >= 0x8000_0000 {
+            return Err(invalid());
+        }
+        encoded.extend((index | 0x8000_0000).to_be_bytes());
+    }
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dummy_builder, test_public_key};
+    use std::sync::mpsc;
+
+    /// A command received by `MockTransport`.
+    #[derive(Debug, PartialEq)]
+    struct Command {
+        cla: u8,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: Vec<u8>,
+    }
+
+    /// Records every command and answers it with the next of `answers`. While `release` is set, each
+    /// exchange blocks until a message is received on it.
+    struct MockTransport {
+        commands: std::sync::Mutex<Vec<Command>>,
+        answers: std::sync::Mutex<Vec<(Vec<u8>, u16)>>,
+        release: Option<std::sync::Mutex<mpsc::Receiver<()>>>,
+    }
+
+    impl MockTransport {
+        fn new(mut answers: Vec<(Vec<u8>, u16)>) -> Self {
+            answers.reverse();
+            Self {
+                commands: std::sync::Mutex::new(Vec::new()),
+                answers: std::sync::Mutex::new(answers),
+                release: None,
+            }
+        }
+    }
+
+    impl LedgerTransport for MockTransport {
+        fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<(Vec<u8>, u16), LedgerError> {
+            if let Some(release) = &self.release {
+                release.lock().unwrap().recv().unwrap();
+            }
+            self.commands.lock().unwrap().push(Command {
+                cla: command.cla,
+                ins: command.ins,
+                p1: command.p1,
+                p2: command.p2,
+                data: command.data.clone(),
+            });
+            Ok(self
+                .answers
+                .lock()
+                .unwrap()
+                .pop()
+                .expect("Unexpected command"))
+        }
+    }
+
+    fn public_key_answer() -> (Vec<u8>, u16) {
+        (test_public_key().key_data().to_vec(), SW_OK)
+    }
+
+    /// Returns a transaction whose serialization spans several chunks.
+    fn large_transaction() -> Transaction {
+        let mut builder = dummy_builder();
+        builder
+            .function_call("set".to_string(), vec![0; 400], 10, 0)
+            .unwrap();
+        builder.build()
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_chunks_payload() {
+        let transport = Arc::new(MockTransport::new(vec![
+            public_key_answer(),
+            (Vec::new(), SW_OK),
+            (Vec::new(), SW_OK),
+            (vec![7; 64], SW_OK),
+        ]));
+        let signer = LedgerSigner::with_transport(transport.clone(), DEFAULT_LEDGER_PATH)
+            .await
+            .unwrap();
+        assert_eq!(signer.public_key(), test_public_key());
+
+        let transaction = large_transaction();
+        let signature = signer.sign_transaction(&transaction).await.unwrap();
+        assert_eq!(
+            signature,
+            Signature::from_parts(near_crypto::KeyType::ED25519, &[7; 64]).unwrap()
+        );
+        assert_eq!(*signer.subscribe().borrow(), SigningState::Signed);
+
+        let commands = transport.commands.lock().unwrap();
+        let path = encode_path(DEFAULT_LEDGER_PATH).unwrap();
+        assert_eq!(
+            commands[0],
+            Command {
+                cla: CLA,
+                ins: INS_GET_PUBLIC_KEY,
+                p1: P1_GET_PUBLIC_KEY_SILENT,
+                p2: NETWORK_ID,
+                data: path.clone(),
+            }
+        );
+
+        let chunks = &commands[1..];
+        let mut payload = path;
+        payload.extend(borsh::to_vec(&transaction).unwrap());
+        assert_eq!(chunks.len(), payload.len().div_ceil(CHUNK_SIZE));
+        assert!(chunks.iter().all(|chunk| chunk.cla == CLA
+            && chunk.ins == INS_SIGN_TRANSACTION
+            && chunk.p2 == NETWORK_ID
+            && chunk.data.len() <= CHUNK_SIZE));
+        let flags: Vec<u8> = chunks.iter().map(|chunk| chunk.p1).collect();
+        assert_eq!(
+            flags,
+            [P1_SIGN_MORE_CHUNKS, P1_SIGN_MORE_CHUNKS, P1_SIGN_LAST_CHUNK]
+        );
+        assert_eq!(
+            chunks
+                .iter()
+                .flat_map(|chunk| chunk.data.clone())
+                .collect::<Vec<_>>(),
+            payload
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_status_words() {
+        let transport = Arc::new(MockTransport::new(vec![
+            public_key_answer(),
+            (Vec::new(), SW_USER_REJECTED),
+            (Vec::new(), 0x6e00),
+        ]));
+        let signer = LedgerSigner::with_transport(transport, DEFAULT_LEDGER_PATH)
+            .await
+            .unwrap();
+        let transaction = dummy_builder().build();
+
+        assert_eq!(
+            signer.sign_transaction(&transaction).await,
+            Err(LedgerError::Rejected)
+        );
+        assert_eq!(*signer.subscribe().borrow(), SigningState::Failed);
+        assert_eq!(
+            signer.sign_transaction(&transaction).await,
+            Err(LedgerError::Status(0x6e00))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_requests_while_pending() {
+        let (release, receiver) = mpsc::channel();
+        let mut transport = MockTransport::new(vec![public_key_answer(), (vec![7; 64], SW_OK)]);
+        transport.release = Some(std::sync::Mutex::new(receiver));
+        release.send(()).unwrap();
+        let signer = Arc::new(
+            LedgerSigner::with_transport(Arc::new(transport), DEFAULT_LEDGER_PATH)
+                .await
+                .unwrap(),
+        );
+
+        let mut state = signer.subscribe();
+        let pending = tokio::spawn({
+            let signer = signer.clone();
+            async move { signer.sign_transaction(&dummy_builder().build()).await }
+        });
+        state
+            .wait_for(|state| *state == SigningState::WaitingForConfirmation)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            signer.sign_transaction(&dummy_builder().build()).await,
+            Err(LedgerError::Busy)
+        );
+        assert_eq!(
+            signer.get_public_key(DEFAULT_LEDGER_PATH).await,
+            Err(LedgerError::Busy)
+        );
+
+        release.send(()).unwrap();
+        assert!(pending.await.unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(
+            encode_path("44'/397'/0'/0'/1'").unwrap(),
+            encode_path("m/44'/397'/0'/0'/1'").unwrap()
+        );
+        assert_eq!(
+            encode_path("44'/397'/0'/0'/1'").unwrap(),
+            [
+                0x80, 0, 0, 44, 0x80, 0, 0x01, 0x8d, 0x80, 0, 0, 0, 0x80, 0, 0, 0, 0x80, 0, 0, 1
+            ]
+            .to_vec()
+        );
+        for path in ["", "m/", "44'//1'", "44'/x'", "2147483648'"] {
+            assert_eq!(
+                encode_path(path),
+                Err(LedgerError::InvalidPath(path.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_path_rejects_non_hardened_components() {
+        for path in ["44'/1", "44/397'/0'/0'/1'", "m/44'/397'/0'/0'/1"] {
+            assert_eq!(
+                encode_path(path),
+                Err(LedgerError::InvalidPath(path.to_string()))
+            );
+        }
+    }
+}
@@ -26,6 +26,84 @@
 //! This crate aims to simplify transaction creation and management, making it more accessible for developers to
 //! interact with the NEAR blockchain programmatically.
 
-pub use crate::transaction_builder::TransactionBuilder;
+pub use crate::access_key_permission::AccessKeyPermissionExt;
+pub use crate::actions::{ActionData, ActionKind};
+pub use crate::codec::TransactionCodec;
+pub use crate::diff::{ActionChange, FieldChange, TransactionDiff, TransactionSummary};
+pub use crate::error::{
+    ArgsFileError, CodecError, ParseError, TransactionBuilderError, WrongActionCount,
+};
+pub use crate::key_import::{KeyImportError, KeyPair};
+pub use crate::keyed_builder::KeyedTransactionBuilder;
+#[cfg(feature = "ledger")]
+pub use crate::ledger::{
+    CONFIRMATION_TIMEOUT, DEFAULT_LEDGER_PATH, LedgerError, LedgerSigner, LedgerTransport,
+    SigningState,
+};
+pub use crate::meta_tx::MetaTxBuilder;
+pub use crate::method_names::MethodNames;
+pub use crate::near_token::{NEAR_DECIMALS, NearToken, ONE_NEAR, ParseNearTokenError};
+pub use crate::nonce_manager::{NonceManager, SharedNonce};
+pub use crate::receipt_replay::ActionReceiptExt;
+#[cfg(feature = "seed-phrase")]
+pub use crate::seed_phrase::{DEFAULT_DERIVATION_PATH, SeedPhraseError, from_mnemonic};
+pub use crate::signed_transaction::{
+    SignedTransactionExt, signed_tx_from_base64, signed_tx_version,
+};
+pub use crate::signer::AsyncSigner;
+pub use crate::signing_request::SigningRequest;
+pub use crate::storage::{
+    STORAGE_MANAGEMENT_GAS, StorageBalance, StorageBalanceBounds, StorageDepositBuilder,
+    access_key_storage_cost, parse_storage_balance, parse_storage_balance_bounds,
+};
+#[cfg(any(test, feature = "test-utils"))]
+pub use crate::test_utils::{
+    TEST_RECEIVER_ID, TEST_SIGNER_ID, TransactionAssertions, dummy_builder, test_account,
+    test_public_key, test_secret_key, test_signer,
+};
+#[cfg(feature = "price-oracle")]
+pub use crate::token_price::{
+    NEAR_USD_PRICE_FEED, PYTH_ORACLE_MAINNET, format_usd, parse_pyth_price,
+};
+pub use crate::tracer::{BuildStep, TransactionTracer};
+#[cfg(any(test, feature = "test-utils"))]
+pub use crate::transaction_builder::PROMISE_BATCH_TEST_CONTRACTS;
+pub use crate::transaction_builder::{
+    MAX_ACTIONS_PER_RECEIPT, MAX_CONTRACT_SIZE, MAX_GAS, MAX_TRANSACTION_SIZE, TGAS,
+    TransactionBuilder, TransactionVersion,
+};
+#[cfg(feature = "wallet-connect")]
+pub use crate::wallet_connect::{WalletConnect, WalletConnectError};
 
+mod access_key_permission;
+mod actions;
+mod codec;
+mod diff;
+mod error;
+mod gas_estimation;
+mod json;
+mod key_import;
+mod keyed_builder;
+#[cfg(feature = "ledger")]
+mod ledger;
+mod meta_tx;
+mod method_names;
+mod near_api_js;
+mod near_cli;
+mod near_token;
+mod nonce_manager;
+mod receipt_replay;
+#[cfg(feature = "seed-phrase")]
+mod seed_phrase;
+mod signed_transaction;
+mod signer;
+mod signing_request;
+mod storage;
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
+#[cfg(feature = "price-oracle")]
+mod token_price;
+mod tracer;
 mod transaction_builder;
+#[cfg(feature = "wallet-connect")]
+mod wallet_connect;
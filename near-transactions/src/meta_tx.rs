@@ -0,0 +1,286 @@
+//! Building and relaying NEP-366 meta transactions.
+//!
+//! A meta transaction lets a user sign a list of actions off-chain as a `SignedDelegateAction`, which a
+//! relayer then wraps into a regular transaction and pays the gas for. `MetaTxBuilder` builds and signs
+//! the inner delegate action, which `near_accounts::relayer::RelayerClient` wraps and broadcasts.
+use crate::error::TransactionBuilderError;
+use crate::transaction_builder::TransactionBuilder;
+use near_crypto::{InMemorySigner, PublicKey};
+use near_primitives::{
+    account::AccessKey,
+    action::delegate::{DelegateAction, NonDelegateAction, SignedDelegateAction},
+    transaction::{
+        Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
+        DeployContractAction, FunctionCallAction, StakeAction, TransferAction,
+    },
+    types::{AccountId, Balance, BlockHeight, Gas, Nonce},
+};
+
+/// Builder for the delegate action signed by the sender of a meta transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaTxBuilder {
+    sender_id: AccountId,
+    receiver_id: AccountId,
+    nonce: Nonce,
+    max_block_height: BlockHeight,
+    actions: Vec<NonDelegateAction>,
+}
+
+impl MetaTxBuilder {
+    /// Initialize a new MetaTxBuilder.
+    ///
+    /// `nonce` must be greater than the nonce of the sender's access key, and the delegate action is
+    /// rejected once the chain is past `max_block_height`.
+    pub fn new(
+        sender_id: AccountId,
+        nonce: Nonce,
+        max_block_height: BlockHeight,
+        receiver_id: AccountId,
+    ) -> Self {
+        Self {
+            sender_id,
+            receiver_id,
+            nonce,
+            max_block_height,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Adds an arbitrary action to the delegate action.
+    ///
+    /// Panics if `action` is itself a delegate action, since delegate actions cannot be nested.
+    pub fn add_action(&mut self, action: Action) -> &mut Self {
+        let action =
+            NonDelegateAction::try_from(action).expect("Delegate actions cannot be nested");
+        self.actions.push(action);
+        self
+    }
+
+    /// Adds several actions to the delegate action.
+    ///
+    /// The actions are only added if all of them are allowed in a delegate action, so the relayer does
+    /// not reject the meta transaction later.
+    pub fn add_actions(
+        &mut self,
+        actions: impl IntoIterator<Item = Action>,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let actions = actions
+            .into_iter()
+            .map(to_non_delegate_action)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.actions.extend(actions);
+        Ok(self)
+    }
+
+    pub fn create_account(&mut self) -> &mut Self {
+        self.add_action(Action::CreateAccount(CreateAccountAction {}))
+    }
+
+    pub fn deploy_contract(&mut self, code: &[u8]) -> &mut Self {
+        self.add_action(Action::DeployContract(DeployContractAction {
+            code: code.to_vec(),
+        }))
+    }
+
+    pub fn function_call(
+        &mut self,
+        method_name: String,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit: Balance,
+    ) -> &mut Self {
+        self.add_action(Action::FunctionCall(Box::new(FunctionCallAction {
+            method_name,
+            args,
+            gas,
+            deposit,
+        })))
+    }
+
+    pub fn transfer(&mut self, deposit: Balance) -> &mut Self {
+        self.add_action(Action::Transfer(TransferAction { deposit }))
+    }
+
+    pub fn stake(&mut self, stake: Balance, public_key: PublicKey) -> &mut Self {
+        self.add_action(Action::Stake(Box::new(StakeAction { stake, public_key })))
+    }
+
+    pub fn add_key(&mut self, public_key: PublicKey, access_key: AccessKey) -> &mut Self {
+        self.add_action(Action::AddKey(Box::new(AddKeyAction {
+            public_key,
+            access_key,
+        })))
+    }
+
+    pub fn delete_key(&mut self, public_key: PublicKey) -> &mut Self {
+        self.add_action(Action::DeleteKey(Box::new(DeleteKeyAction { public_key })))
+    }
+
+    pub fn delete_account(&mut self, beneficiary_id: AccountId) -> &mut Self {
+        self.add_action(Action::DeleteAccount(DeleteAccountAction {
+            beneficiary_id,
+        }))
+    }
+
+    /// Signs the delegate action with the sender's key.
+    pub fn sign(&self, signer: &InMemorySigner) -> SignedDelegateAction {
+        let delegate_action = DelegateAction {
+            sender_id: self.sender_id.clone(),
+            receiver_id: self.receiver_id.clone(),
+            actions: self.actions.clone(),
+            nonce: self.nonce,
+            max_block_height: self.max_block_height,
+            public_key: signer.public_key(),
+        };
+        let signature = signer.sign(delegate_action.get_nep461_hash().as_ref());
+        SignedDelegateAction {
+            delegate_action,
+            signature,
+        }
+    }
+}
+
+impl TransactionBuilder {
+    /// Converts the builder's actions into a delegate action signed by `signer`, the client half of a
+    /// NEP-366 meta transaction.
+    ///
+    /// The builder's receiver and nonce become the delegate action's receiver and nonce, and the
+    /// signer's public key replaces the builder's public key.
+    pub fn into_signed_delegate(
+        self,
+        signer: &InMemorySigner,
+        sender_id: AccountId,
+        max_block_height: BlockHeight,
+    ) -> Result<SignedDelegateAction, TransactionBuilderError> {
+        let actions = self
+            .transaction
+            .actions()
+            .iter()
+            .cloned()
+            .map(to_non_delegate_action)
+            .collect::<Result<Vec<_>, _>>()?;
+        let meta_tx = MetaTxBuilder {
+            sender_id,
+            receiver_id: self.transaction.receiver_id().clone(),
+            nonce: self.transaction.nonce(),
+            max_block_height,
+            actions,
+        };
+        Ok(meta_tx.sign(signer))
+    }
+}
+
+/// Checks that `action` may be part of a delegate action. NEP-366 allows every action except another
+/// delegate action.
+fn to_non_delegate_action(action: Action) -> Result<NonDelegateAction, TransactionBuilderError> {
+    NonDelegateAction::try_from(action).map_err(|_| TransactionBuilderError::NestedDelegateAction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_signer;
+    use near_primitives::hash::CryptoHash;
+
+    #[test]
+    fn test_sign_delegate_action() {
+        let signer = test_signer();
+
+        let signed_delegate = MetaTxBuilder::new(
+            "alice.near".parse().unwrap(),
+            5,
+            1_000,
+            "token.near".parse().unwrap(),
+        )
+        .function_call("ft_transfer".to_string(), vec![], 30_000_000_000_000, 1)
+        .transfer(10)
+        .sign(&signer);
+
+        let delegate_action = &signed_delegate.delegate_action;
+        assert_eq!(delegate_action.public_key, signer.public_key());
+        assert_eq!(delegate_action.nonce, 5);
+        assert_eq!(delegate_action.max_block_height, 1_000);
+        assert_eq!(delegate_action.get_actions().len(), 2);
+        assert!(signed_delegate.verify());
+    }
+
+    #[test]
+    fn test_into_signed_delegate() {
+        let signer = test_signer();
+        let mut builder = TransactionBuilder::new_unsigned(
+            "alice.near".parse().unwrap(),
+            "token.near".parse().unwrap(),
+            9,
+            CryptoHash::default(),
+        );
+        builder.transfer(10);
+
+        let signed_delegate = builder
+            .into_signed_delegate(&signer, "alice.near".parse().unwrap(), 1_000)
+            .unwrap();
+
+        let delegate_action = &signed_delegate.delegate_action;
+        assert_eq!(delegate_action.sender_id.as_str(), "alice.near");
+        assert_eq!(delegate_action.receiver_id.as_str(), "token.near");
+        assert_eq!(delegate_action.nonce, 9);
+        assert_eq!(delegate_action.max_block_height, 1_000);
+        assert_eq!(delegate_action.public_key, signer.public_key());
+        assert_eq!(
+            delegate_action.get_actions(),
+            vec![Action::Transfer(TransferAction { deposit: 10 })]
+        );
+        assert!(signed_delegate.verify());
+    }
+
+    #[test]
+    fn test_add_actions_validates_every_action() {
+        let signer = test_signer();
+        let inner = MetaTxBuilder::new(
+            "alice.near".parse().unwrap(),
+            1,
+            1_000,
+            "bob.near".parse().unwrap(),
+        )
+        .sign(&signer);
+
+        let mut builder = MetaTxBuilder::new(
+            "alice.near".parse().unwrap(),
+            2,
+            1_000,
+            "bob.near".parse().unwrap(),
+        );
+        let transfer = Action::Transfer(TransferAction { deposit: 1 });
+        let deploy = Action::DeployContract(DeployContractAction { code: vec![0] });
+        assert_eq!(
+            builder
+                .add_actions(vec![transfer.clone(), Action::Delegate(Box::new(inner))])
+                .unwrap_err(),
+            TransactionBuilderError::NestedDelegateAction
+        );
+        assert!(builder.actions.is_empty());
+
+        builder.add_actions(vec![transfer, deploy]).unwrap();
+        assert_eq!(builder.actions.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Delegate actions cannot be nested")]
+    fn test_nested_delegate_action_panics() {
+        let signer = test_signer();
+        let inner = MetaTxBuilder::new(
+            "alice.near".parse().unwrap(),
+            1,
+            1_000,
+            "bob.near".parse().unwrap(),
+        )
+        .sign(&signer);
+
+        MetaTxBuilder::new(
+            "alice.near".parse().unwrap(),
+            2,
+            1_000,
+            "bob.near".parse().unwrap(),
+        )
+        .add_action(Action::Delegate(Box::new(inner)));
+    }
+}
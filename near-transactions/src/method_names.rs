@@ -0,0 +1,71 @@
+//! Normalized method name lists for function call access keys.
+
+use crate::error::TransactionBuilderError;
+
+/// The method names a function call access key may call, free of duplicates and empty names.
+///
+/// An empty list allows calling any method of the receiver.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MethodNames(Vec<String>);
+
+impl MethodNames {
+    /// Normalizes `names` by removing duplicates, keeping the first occurrence of each name.
+    ///
+    /// Empty names and names with non-ASCII or control characters can never match a contract method, so
+    /// they are rejected with `TransactionBuilderError::InvalidMethodName` rather than dropped.
+    pub fn new<I, S>(names: I) -> Result<Self, TransactionBuilderError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut method_names: Vec<String> = Vec::new();
+        for name in names {
+            let name = name.into();
+            if !is_valid_method_name(&name) {
+                return Err(TransactionBuilderError::InvalidMethodName(name));
+            }
+            if !method_names.contains(&name) {
+                method_names.push(name);
+            }
+        }
+        Ok(Self(method_names))
+    }
+
+    /// Returns the method names.
+    pub fn as_slice(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Returns the method names, e.g. for `FunctionCallPermission::method_names`.
+    pub fn into_vec(self) -> Vec<String> {
+        self.0
+    }
+}
+
+/// Returns true if `method_name` is non-empty and only contains printable ASCII characters.
+pub(crate) fn is_valid_method_name(method_name: &str) -> bool {
+    !method_name.is_empty()
+        && method_name
+            .chars()
+            .all(|c| c.is_ascii() && !c.is_ascii_control())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_and_reject_empty() {
+        let method_names = MethodNames::new(["a", "b", "a"]).unwrap();
+        assert_eq!(method_names.as_slice(), ["a", "b"]);
+
+        assert_eq!(
+            MethodNames::new(["a", "a", ""]),
+            Err(TransactionBuilderError::InvalidMethodName(String::new()))
+        );
+        assert_eq!(
+            MethodNames::new(Vec::<String>::new()).unwrap(),
+            MethodNames::default()
+        );
+    }
+}
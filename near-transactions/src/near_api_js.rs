@@ -0,0 +1,270 @@
+//! Converts transactions exported by `near-api-js` as JSON into `TransactionBuilder`s.
+//!
+//! The expected format mirrors `near-api-js`'s `Transaction` class: camelCase field names, public keys
+//! as `ed25519:...` strings, the block hash in base58, byte arrays (function call args, contract code)
+//! in base64, and amounts as decimal strings or numbers. Actions use `near-api-js`'s enum layout, where
+//! the `enum` field names the variant and the payload is stored under the same name:
+//!
+//! ```json
+//! { "enum": "transfer", "transfer": { "deposit": "1000000000000000000000000" } }
+//! ```
+
+use crate::{ParseError, TransactionBuilder};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use near_primitives::account::{AccessKey, AccessKeyPermission, FunctionCallPermission};
+use near_primitives::transaction::{
+    Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
+    DeployContractAction, FunctionCallAction, StakeAction, TransferAction,
+};
+use serde_json::Value;
+use std::str::FromStr;
+
+impl TransactionBuilder {
+    /// Constructs a `TransactionBuilder` from a transaction exported by `near-api-js` as JSON.
+    ///
+    /// # Returns
+    ///
+    /// The builder with all actions of the exported transaction, or a `ParseError` naming the first
+    /// missing or invalid field.
+    pub fn from_near_api_js_json(json: &str) -> Result<TransactionBuilder, ParseError> {
+        let transaction: Value =
+            serde_json::from_str(json).map_err(|err| ParseError::InvalidJson(err.to_string()))?;
+
+        let mut builder = TransactionBuilder::new(
+            parse_field(&transaction, "signerId")?,
+            parse_field(&transaction, "publicKey")?,
+            parse_field(&transaction, "receiverId")?,
+            u64_field(&transaction, "nonce")?,
+            parse_field(&transaction, "blockHash")?,
+        );
+        let actions = field(&transaction, "actions")?
+            .as_array()
+            .ok_or_else(|| invalid("actions", "expected an array"))?;
+        for action in actions {
+            builder.add_action(parse_action(action)?);
+        }
+        Ok(builder)
+    }
+}
+
+fn parse_action(action: &Value) -> Result<Action, ParseError> {
+    let kind = string_field(action, "enum")?;
+    let payload = field(action, kind)?;
+    let action = match kind {
+        "createAccount" => Action::CreateAccount(CreateAccountAction {}),
+        "deployContract" => Action::DeployContract(DeployContractAction {
+            code: base64_field(payload, "code")?,
+        }),
+        "functionCall" => Action::FunctionCall(Box::new(FunctionCallAction {
+            method_name: string_field(payload, "methodName")?.to_string(),
+            args: base64_field(payload, "args")?,
+            gas: u64_field(payload, "gas")?,
+            deposit: amount_field(payload, "deposit")?,
+        })),
+        "transfer" => Action::Transfer(TransferAction {
+            deposit: amount_field(payload, "deposit")?,
+        }),
+        "stake" => Action::Stake(Box::new(StakeAction {
+            stake: amount_field(payload, "stake")?,
+            public_key: parse_field(payload, "publicKey")?,
+        })),
+        "addKey" => Action::AddKey(Box::new(AddKeyAction {
+            public_key: parse_field(payload, "publicKey")?,
+            access_key: parse_access_key(field(payload, "accessKey")?)?,
+        })),
+        "deleteKey" => Action::DeleteKey(Box::new(DeleteKeyAction {
+            public_key: parse_field(payload, "publicKey")?,
+        })),
+        "deleteAccount" => Action::DeleteAccount(DeleteAccountAction {
+            beneficiary_id: parse_field(payload, "beneficiaryId")?,
+        }),
+        other => return Err(invalid("enum", &format!("unsupported action {}", other))),
+    };
+    Ok(action)
+}
+
+fn parse_access_key(access_key: &Value) -> Result<AccessKey, ParseError> {
+    let permission = field(access_key, "permission")?;
+    let permission = match string_field(permission, "enum")? {
+        "fullAccess" => AccessKeyPermission::FullAccess,
+        "functionCall" => {
+            let function_call = field(permission, "functionCall")?;
+            let allowance = match function_call.get("allowance") {
+                None | Some(Value::Null) => None,
+                Some(_) => Some(amount_field(function_call, "allowance")?),
+            };
+            let method_names = field(function_call, "methodNames")?
+                .as_array()
+                .ok_or_else(|| invalid("methodNames", "expected an array"))?
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| invalid("methodNames", "expected strings"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            AccessKeyPermission::FunctionCall(FunctionCallPermission {
+                allowance,
+                receiver_id: string_field(function_call, "receiverId")?.to_string(),
+                method_names,
+            })
+        }
+        other => {
+            return Err(invalid(
+                "permission",
+                &format!("unsupported permission {}", other),
+            ));
+        }
+    };
+    Ok(AccessKey {
+        nonce: match access_key.get("nonce") {
+            None | Some(Value::Null) => 0,
+            Some(_) => u64_field(access_key, "nonce")?,
+        },
+        permission,
+    })
+}
+
+fn invalid(field: &str, reason: &str) -> ParseError {
+    ParseError::InvalidField {
+        field: field.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn field<'a>(object: &'a Value, name: &str) -> Result<&'a Value, ParseError> {
+    object
+        .get(name)
+        .ok_or_else(|| ParseError::MissingField(name.to_string()))
+}
+
+fn string_field<'a>(object: &'a Value, name: &str) -> Result<&'a str, ParseError> {
+    field(object, name)?
+        .as_str()
+        .ok_or_else(|| invalid(name, "expected a string"))
+}
+
+fn parse_field<T: FromStr>(object: &Value, name: &str) -> Result<T, ParseError>
+where
+    T::Err: std::fmt::Display,
+{
+    string_field(object, name)?
+        .parse()
+        .map_err(|err: T::Err| invalid(name, &err.to_string()))
+}
+
+/// Reads an amount given either as a decimal string (as produced by `BN.toString()`) or a JSON number.
+fn amount_field(object: &Value, name: &str) -> Result<u128, ParseError> {
+    match field(object, name)? {
+        Value::String(amount) => amount
+            .parse()
+            .map_err(|_| invalid(name, "expected a decimal amount")),
+        Value::Number(amount) => amount
+            .as_u64()
+            .map(u128::from)
+            .ok_or_else(|| invalid(name, "expected a non-negative integer")),
+        _ => Err(invalid(name, "expected a string or a number")),
+    }
+}
+
+/// Reads an amount like `amount_field`, rejecting values that don't fit in a `u64`, such as nonces and gas.
+fn u64_field(object: &Value, name: &str) -> Result<u64, ParseError> {
+    u64::try_from(amount_field(object, name)?)
+        .map_err(|_| invalid(name, "exceeds the maximum of a u64"))
+}
+
+fn base64_field(object: &Value, name: &str) -> Result<Vec<u8>, ParseError> {
+    STANDARD
+        .decode(string_field(object, name)?)
+        .map_err(|err| invalid(name, &err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_near_api_js_function_call() {
+        let json = r#"{
+            "signerId": "alice.testnet",
+            "publicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+            "nonce": "108227000001",
+            "receiverId": "guest-book.testnet",
+            "blockHash": "6rHQKCoVnJzk5AJA4rL1hYxWjGZAvNjVxk4aUAtB4q5H",
+            "actions": [
+                {
+                    "enum": "functionCall",
+                    "functionCall": {
+                        "methodName": "add_message",
+                        "args": "eyJ0ZXh0IjoiaGVsbG8ifQ==",
+                        "gas": "30000000000000",
+                        "deposit": "0"
+                    }
+                }
+            ]
+        }"#;
+
+        let transaction = TransactionBuilder::from_near_api_js_json(json)
+            .unwrap()
+            .build();
+        assert_eq!(transaction.signer_id().as_str(), "alice.testnet");
+        assert_eq!(transaction.receiver_id().as_str(), "guest-book.testnet");
+        assert_eq!(transaction.nonce(), 108227000001);
+        match &transaction.actions()[0] {
+            Action::FunctionCall(call) => {
+                assert_eq!(call.method_name, "add_message");
+                assert_eq!(call.args, br#"{"text":"hello"}"#.to_vec());
+                assert_eq!(call.gas, 30_000_000_000_000);
+                assert_eq!(call.deposit, 0);
+            }
+            action => panic!("Unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_from_near_api_js_missing_field() {
+        let result = TransactionBuilder::from_near_api_js_json(r#"{"signerId": "alice.testnet"}"#);
+        assert_eq!(
+            result.unwrap_err(),
+            ParseError::MissingField("publicKey".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_near_api_js_rejects_u64_overflow() {
+        let json = r#"{
+            "signerId": "alice.testnet",
+            "publicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+            "nonce": "1",
+            "receiverId": "guest-book.testnet",
+            "blockHash": "6rHQKCoVnJzk5AJA4rL1hYxWjGZAvNjVxk4aUAtB4q5H",
+            "actions": [
+                {
+                    "enum": "functionCall",
+                    "functionCall": {
+                        "methodName": "add_message",
+                        "args": "e30=",
+                        "gas": "18446744073709551616",
+                        "deposit": "0"
+                    }
+                }
+            ]
+        }"#;
+        assert!(matches!(
+            TransactionBuilder::from_near_api_js_json(json),
+            Err(ParseError::InvalidField { field, .. }) if field == "gas"
+        ));
+
+        let json = json
+            .replace(
+                r#""gas": "18446744073709551616""#,
+                r#""gas": "30000000000000""#,
+            )
+            .replace(r#""nonce": "1""#, r#""nonce": "18446744073709551616""#);
+        assert!(matches!(
+            TransactionBuilder::from_near_api_js_json(&json),
+            Err(ParseError::InvalidField { field, .. }) if field == "nonce"
+        ));
+    }
+}
@@ -0,0 +1,318 @@
+//! Renders transactions as equivalent `near` CLI commands, for debugging.
+//!
+//! The commands use the syntax of the JavaScript `near-cli`. Most of its commands send a single action,
+//! so a transaction with several actions is rendered as one command per action, which are sent as
+//! separate transactions. Creating an account is the exception: `near create-account` also funds the
+//! account and adds a key.
+//!
+//! Transactions are exchanged with the Rust `near` CLI (`send-signed-transaction`) as a borsh-serialized
+//! `SignedTransaction`, encoded with the standard base64 alphabet (`+` and `/`) and `=` padding.
+
+use crate::near_token::NearToken;
+use crate::{ParseError, TransactionBuilder};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use near_crypto::{InMemorySigner, Signature};
+use near_primitives::account::AccessKeyPermission;
+use near_primitives::transaction::{Action, SignedTransaction, Transaction};
+use near_primitives::types::AccountId;
+
+/// Comment prepended when the actions cannot be sent in a single `near` command.
+const SPLIT_NOTE: &str = "# near-cli sends each of these actions as a separate transaction";
+
+impl TransactionBuilder {
+    /// Renders the `near` CLI command(s) performing the same actions as this transaction.
+    ///
+    /// Actions without an exact equivalent, such as deploying a contract, are rendered with a
+    /// placeholder or as a comment.
+    pub fn to_near_cli_command(&self) -> String {
+        let signer_id = self.transaction.signer_id();
+        let receiver_id = self.transaction.receiver_id();
+        let actions = self.transaction.actions();
+
+        if let Some(command) = create_account_command(signer_id, receiver_id, actions) {
+            return command;
+        }
+
+        let commands: Vec<String> = actions
+            .iter()
+            .map(|action| action_command(signer_id, receiver_id, action))
+            .collect();
+        if commands.len() > 1 {
+            format!("{}\n{}", SPLIT_NOTE, commands.join("\n"))
+        } else {
+            commands.join("\n")
+        }
+    }
+
+    /// Signs the transaction with `signer` and encodes it the way `near send-signed-transaction`
+    /// expects: borsh-serialized, then standard base64 with padding.
+    pub fn into_near_cli_base64(self, signer: &InMemorySigner) -> String {
+        let signed_transaction = self.sign_transaction(signer);
+        STANDARD.encode(borsh::to_vec(&signed_transaction).expect("Borsh serialization failed"))
+    }
+
+    /// Decodes a transaction encoded as standard, padded base64 of its borsh serialization, as generated
+    /// by the `near` CLI.
+    ///
+    /// # Returns
+    ///
+    /// The builder with all actions of the transaction, and its signature if the input is a
+    /// `SignedTransaction` rather than an unsigned `Transaction`.
+    pub fn from_near_cli_base64(
+        s: &str,
+    ) -> Result<(TransactionBuilder, Option<Signature>), ParseError> {
+        let bytes = STANDARD
+            .decode(s.trim())
+            .map_err(|err| invalid_transaction(err.to_string()))?;
+        if let Ok(signed_transaction) = borsh::from_slice::<SignedTransaction>(&bytes) {
+            let builder = TransactionBuilder {
+                transaction: signed_transaction.transaction,
+            };
+            return Ok((builder, Some(signed_transaction.signature)));
+        }
+        let transaction = borsh::from_slice::<Transaction>(&bytes)
+            .map_err(|err| invalid_transaction(err.to_string()))?;
+        Ok((TransactionBuilder { transaction }, None))
+    }
+
+    /// Encodes the unsigned transaction as standard, padded base64 of its borsh serialization, e.g. for a
+    /// coordinator distributing it to signers.
+    pub fn to_base64_unsigned(&self) -> String {
+        STANDARD.encode(borsh::to_vec(&self.transaction).expect("Borsh serialization failed"))
+    }
+
+    /// Decodes an unsigned transaction encoded by `to_base64_unsigned`, so it can be signed locally.
+    ///
+    /// Unlike `from_near_cli_base64`, signed transactions are rejected.
+    pub fn from_base64_unsigned(s: &str) -> Result<TransactionBuilder, ParseError> {
+        let bytes = STANDARD
+            .decode(s.trim())
+            .map_err(|err| invalid_transaction(err.to_string()))?;
+        let transaction = borsh::from_slice::<Transaction>(&bytes)
+            .map_err(|err| invalid_transaction(err.to_string()))?;
+        Ok(TransactionBuilder { transaction })
+    }
+}
+
+fn invalid_transaction(reason: String) -> ParseError {
+    ParseError::InvalidField {
+        field: "transaction".to_string(),
+        reason,
+    }
+}
+
+/// Renders `CreateAccount`, optionally followed by a `Transfer` and a full access `AddKey`, as a
+/// single `near create-account` command.
+fn create_account_command(
+    signer_id: &AccountId,
+    receiver_id: &AccountId,
+    actions: &[Action],
+) -> Option<String> {
+    let (Action::CreateAccount(_), rest) = actions.split_first()? else {
+        return None;
+    };
+    let mut command = format!(
+        "near create-account {} --masterAccount {}",
+        receiver_id, signer_id
+    );
+    for action in rest {
+        match action {
+            Action::Transfer(transfer) => command.push_str(&format!(
+                " --initialBalance {}",
+                NearToken::from_yoctonear(transfer.deposit).to_near_string()
+            )),
+            Action::AddKey(add_key)
+                if add_key.access_key.permission == AccessKeyPermission::FullAccess =>
+            {
+                command.push_str(&format!(" --publicKey {}", add_key.public_key))
+            }
+            _ => return None,
+        }
+    }
+    Some(command)
+}
+
+fn action_command(signer_id: &AccountId, receiver_id: &AccountId, action: &Action) -> String {
+    match action {
+        Action::CreateAccount(_) => format!(
+            "near create-account {} --masterAccount {}",
+            receiver_id, signer_id
+        ),
+        Action::DeployContract(_) => format!("near deploy {} <path/to/contract.wasm>", receiver_id),
+        Action::FunctionCall(call) => {
+            let mut command = match std::str::from_utf8(&call.args) {
+                Ok(args) => format!(
+                    "near call {} {} {}",
+                    receiver_id,
+                    call.method_name,
+                    shell_quote(args)
+                ),
+                Err(_) => format!(
+                    "near call {} {} {} --base64",
+                    receiver_id,
+                    call.method_name,
+                    STANDARD.encode(&call.args)
+                ),
+            };
+            command.push_str(&format!(" --accountId {} --gas {}", signer_id, call.gas));
+            if call.deposit > 0 {
+                command.push_str(&format!(" --depositYocto {}", call.deposit));
+            }
+            command
+        }
+        Action::Transfer(transfer) => format!(
+            "near send {} {} {}",
+            signer_id,
+            receiver_id,
+            NearToken::from_yoctonear(transfer.deposit).to_near_string()
+        ),
+        Action::Stake(stake) => format!(
+            "near stake {} {} {}",
+            receiver_id,
+            stake.public_key,
+            NearToken::from_yoctonear(stake.stake).to_near_string()
+        ),
+        Action::AddKey(add_key) => match &add_key.access_key.permission {
+            AccessKeyPermission::FullAccess => {
+                format!("near add-key {} {}", receiver_id, add_key.public_key)
+            }
+            AccessKeyPermission::FunctionCall(permission) => {
+                let mut command = format!(
+                    "near add-key {} {} --contract-id {}",
+                    receiver_id, add_key.public_key, permission.receiver_id
+                );
+                if !permission.method_names.is_empty() {
+                    command.push_str(&format!(
+                        " --method-names {}",
+                        permission.method_names.join(",")
+                    ));
+                }
+                if let Some(allowance) = permission.allowance {
+                    command.push_str(&format!(
+                        " --allowance {}",
+                        NearToken::from_yoctonear(allowance).to_near_string()
+                    ));
+                }
+                command
+            }
+        },
+        Action::DeleteKey(delete_key) => {
+            format!("near delete-key {} {}", receiver_id, delete_key.public_key)
+        }
+        Action::DeleteAccount(delete_account) => format!(
+            "near delete {} {}",
+            receiver_id, delete_account.beneficiary_id
+        ),
+        action => format!("# no near-cli equivalent for {:?}", action),
+    }
+}
+
+/// Quotes `value` as a single shell argument.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, PublicKey, SecretKey};
+    use near_primitives::hash::CryptoHash;
+
+    fn builder() -> TransactionBuilder {
+        TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "guest-book.near".parse().unwrap(),
+            1,
+            CryptoHash::default(),
+        )
+    }
+
+    #[test]
+    fn test_function_call_command() {
+        let mut builder = builder();
+        builder
+            .function_call(
+                "add_message".to_string(),
+                br#"{"text":"it's me"}"#.to_vec(),
+                30_000_000_000_000,
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(
+            builder.to_near_cli_command(),
+            r#"near call guest-book.near add_message '{"text":"it'\''s me"}' --accountId alice.near --gas 30000000000000"#
+        );
+    }
+
+    #[test]
+    fn test_multi_action_command() {
+        let mut builder = builder();
+        builder
+            .transfer(10u128.pow(24))
+            .delete_key(PublicKey::empty(KeyType::ED25519));
+
+        let command = builder.to_near_cli_command();
+        let lines: Vec<&str> = command.lines().collect();
+        assert_eq!(lines[0], SPLIT_NOTE);
+        assert_eq!(lines[1], "near send alice.near guest-book.near 1");
+        assert!(lines[2].starts_with("near delete-key guest-book.near ed25519:"));
+    }
+
+    #[test]
+    fn test_near_cli_base64_round_trip() {
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "alice.near");
+        let signer = InMemorySigner {
+            account_id: "alice.near".parse().unwrap(),
+            public_key: secret_key.public_key(),
+            secret_key,
+        };
+        let mut builder = builder();
+        builder.transfer(1);
+        let expected = builder.sign_transaction(&signer);
+
+        let encoded = builder.into_near_cli_base64(&signer);
+        assert_eq!(encoded, STANDARD.encode(borsh::to_vec(&expected).unwrap()));
+
+        let (decoded, signature) = TransactionBuilder::from_near_cli_base64(&encoded).unwrap();
+        assert_eq!(decoded.build(), expected.transaction);
+        assert_eq!(signature, Some(expected.signature));
+    }
+
+    #[test]
+    fn test_from_near_cli_base64_unsigned() {
+        let mut builder = builder();
+        builder.transfer(1);
+        let encoded = STANDARD.encode(borsh::to_vec(&builder.transaction).unwrap());
+
+        let (decoded, signature) = TransactionBuilder::from_near_cli_base64(&encoded).unwrap();
+        assert_eq!(decoded, builder);
+        assert_eq!(signature, None);
+        assert!(TransactionBuilder::from_near_cli_base64("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_base64_unsigned_round_trip() {
+        let mut builder = builder();
+        builder
+            .transfer(1)
+            .delete_key(PublicKey::empty(KeyType::ED25519));
+
+        let encoded = builder.to_base64_unsigned();
+        assert_eq!(
+            TransactionBuilder::from_base64_unsigned(&encoded).unwrap(),
+            builder
+        );
+
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "alice.near");
+        let signer = InMemorySigner {
+            account_id: "alice.near".parse().unwrap(),
+            public_key: secret_key.public_key(),
+            secret_key,
+        };
+        let signed = builder.into_near_cli_base64(&signer);
+        assert!(TransactionBuilder::from_base64_unsigned(&signed).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! Provides the `NearToken` type for working with NEAR amounts in human readable form.
+//!
+//! On chain, amounts are plain `Balance` values in yoctoNEAR (10^-24 NEAR). `NearToken` wraps such a
+//! value and converts it to and from decimal strings like `"1.5"`.
+
+use near_primitives::types::Balance;
+use std::fmt;
+use std::str::FromStr;
+
+/// Number of decimal places of the NEAR token.
+pub const NEAR_DECIMALS: u32 = 24;
+
+/// One NEAR in yoctoNEAR.
+pub const ONE_NEAR: Balance = 10u128.pow(NEAR_DECIMALS);
+
+/// An amount of NEAR, stored in yoctoNEAR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct NearToken(Balance);
+
+/// Error returned when a string cannot be parsed into a `NearToken`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNearTokenError {
+    /// The string that failed to parse.
+    pub input: String,
+    /// Why the string was rejected.
+    pub reason: String,
+}
+
+impl fmt::Display for ParseNearTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid NEAR amount {:?}: {}", self.input, self.reason)
+    }
+}
+
+impl std::error::Error for ParseNearTokenError {}
+
+impl NearToken {
+    /// Creates a `NearToken` from an amount in yoctoNEAR.
+    pub const fn from_yoctonear(amount: Balance) -> Self {
+        Self(amount)
+    }
+
+    /// Returns the amount in yoctoNEAR.
+    pub const fn as_yoctonear(&self) -> Balance {
+        self.0
+    }
+
+    /// Formats the amount in NEAR with at most `decimals` decimal places, trimming trailing zeros.
+    ///
+    /// Digits beyond `decimals` are truncated, not rounded. `decimals` is capped at 24, the precision of
+    /// yoctoNEAR, so `to_display_string(2)` of 1.567 NEAR is `"1.56"` and of 2 NEAR is `"2"`.
+    pub fn to_display_string(&self, decimals: u32) -> String {
+        let decimals = decimals.min(NEAR_DECIMALS) as usize;
+        let whole = self.0 / ONE_NEAR;
+        let fraction = format!("{:024}", self.0 % ONE_NEAR);
+        let fraction = fraction[..decimals].trim_end_matches('0');
+        if fraction.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{}.{}", whole, fraction)
+        }
+    }
+
+    /// Formats the exact amount in NEAR, using all 24 decimal places when needed.
+    pub fn to_near_string(&self) -> String {
+        self.to_display_string(NEAR_DECIMALS)
+    }
+
+    /// Parses a decimal amount of NEAR, such as `"1.5"`, with at most `decimals` decimal places.
+    ///
+    /// # Returns
+    ///
+    /// The parsed amount, or an error if the string is not a plain decimal number, has more than
+    /// `decimals` (or 24) decimal places, or overflows.
+    pub fn from_display_string(s: &str, decimals: u32) -> Result<Self, ParseNearTokenError> {
+        parse_scaled(s, s.trim(), NEAR_DECIMALS, decimals).map(Self)
+    }
+
+    /// Parses an amount with a unit, such as `"1.5 NEAR"`, `"100 mNEAR"`, `"1000 μNEAR"` or
+    /// `"500 yoctoNEAR"`. The space before the unit is optional and units are case-sensitive.
+    ///
+    /// # Returns
+    ///
+    /// The parsed amount, or an error if the unit is missing or unknown, the number is not a plain
+    /// decimal number, is more precise than a yoctoNEAR, or overflows.
+    pub fn from_str_human(s: &str) -> Result<Self, ParseNearTokenError> {
+        let trimmed = s.trim();
+        let unit_start = trimmed
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(trimmed.len());
+        let (number, unit) = trimmed.split_at(unit_start);
+        let exponent = match unit.trim_start() {
+            "NEAR" => NEAR_DECIMALS,
+            "mNEAR" => 21,
+            // Both the Greek small letter mu and the micro sign
+            "μNEAR" | "µNEAR" => 18,
+            "yoctoNEAR" => 0,
+            "" => return Err(parse_error(s, "missing unit, e.g. NEAR")),
+            _ => return Err(parse_error(s, "unknown unit")),
+        };
+        parse_scaled(s, number, exponent, exponent).map(Self)
+    }
+}
+
+impl fmt::Display for NearToken {
+    /// Formats the exact amount in NEAR with its unit, e.g. `1.5 NEAR`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} NEAR", self.to_near_string())
+    }
+}
+
+impl FromStr for NearToken {
+    type Err = ParseNearTokenError;
+
+    /// Parses an amount with a unit, see `NearToken::from_str_human`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_human(s)
+    }
+}
+
+fn parse_error(input: &str, reason: &str) -> ParseNearTokenError {
+    ParseNearTokenError {
+        input: input.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Parses the decimal `number`, with at most `decimals` (or `exponent`) decimal places, into a `Balance`
+/// of `number * 10^exponent`. Errors report `input` as the rejected string.
+fn parse_scaled(
+    input: &str,
+    number: &str,
+    exponent: u32,
+    decimals: u32,
+) -> Result<Balance, ParseNearTokenError> {
+    let error = |reason: &str| parse_error(input, reason);
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(error("empty amount"));
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(error("expected a decimal number"));
+    }
+    if fraction.len() > decimals.min(exponent) as usize {
+        return Err(error("too many decimal places"));
+    }
+
+    let whole: Balance = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| error("amount overflows u128"))?
+    };
+    let fraction: Balance = if exponent == 0 {
+        0
+    } else {
+        format!("{:0<width$}", fraction, width = exponent as usize)
+            .parse()
+            .map_err(|_| error("expected a decimal number"))?
+    };
+    whole
+        .checked_mul(10u128.pow(exponent))
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(|| error("amount overflows u128"))
+}
+
+impl From<Balance> for NearToken {
+    fn from(amount: Balance) -> Self {
+        Self(amount)
+    }
+}
+
+impl From<NearToken> for Balance {
+    fn from(token: NearToken) -> Self {
+        token.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_display_string() {
+        assert_eq!(NearToken::from_yoctonear(0).to_near_string(), "0");
+        assert_eq!(
+            NearToken::from_yoctonear(1_500_000_000_000_000_000_000_000).to_near_string(),
+            "1.5"
+        );
+        assert_eq!(
+            NearToken::from_yoctonear(1_567_000_000_000_000_000_000_000).to_display_string(2),
+            "1.56"
+        );
+        assert_eq!(NearToken::from_yoctonear(1).to_display_string(2), "0");
+        assert_eq!(
+            NearToken::from_yoctonear(1).to_near_string(),
+            "0.000000000000000000000001"
+        );
+        assert_eq!(
+            NearToken::from_yoctonear(u128::MAX).to_near_string(),
+            "340282366920938.463463374607431768211455"
+        );
+    }
+
+    #[test]
+    fn test_from_display_string() {
+        assert_eq!(
+            NearToken::from_display_string("0", NEAR_DECIMALS),
+            Ok(NearToken::from_yoctonear(0))
+        );
+        assert_eq!(
+            NearToken::from_display_string("1.5", NEAR_DECIMALS),
+            Ok(NearToken::from_yoctonear(1_500_000_000_000_000_000_000_000))
+        );
+        assert_eq!(
+            NearToken::from_display_string(".000000000000000000000001", NEAR_DECIMALS),
+            Ok(NearToken::from_yoctonear(1))
+        );
+        assert_eq!(
+            NearToken::from_display_string("340282366920938.463463374607431768211455", 24),
+            Ok(NearToken::from_yoctonear(u128::MAX))
+        );
+        assert!(
+            NearToken::from_display_string("340282366920938.463463374607431768211456", 24).is_err()
+        );
+        assert!(NearToken::from_display_string("1.567", 2).is_err());
+        assert!(NearToken::from_display_string("", NEAR_DECIMALS).is_err());
+        assert!(NearToken::from_display_string("1,5", NEAR_DECIMALS).is_err());
+    }
+
+    #[test]
+    fn test_from_str_human() {
+        let parse = |s: &str| s.parse::<NearToken>().map(|token| token.as_yoctonear());
+        assert_eq!(parse("1 NEAR"), Ok(ONE_NEAR));
+        assert_eq!(parse("1.5 NEAR"), Ok(ONE_NEAR * 3 / 2));
+        assert_eq!(parse("100 mNEAR"), Ok(ONE_NEAR / 10));
+        assert_eq!(parse("1000 μNEAR"), Ok(ONE_NEAR / 1000));
+        assert_eq!(parse("1000µNEAR"), Ok(ONE_NEAR / 1000));
+        assert_eq!(parse("500 yoctoNEAR"), Ok(500));
+        assert_eq!(parse(" 0.001 mNEAR "), Ok(10u128.pow(18)));
+
+        assert_eq!(
+            parse("1.5"),
+            Err(ParseNearTokenError {
+                input: "1.5".to_string(),
+                reason: "missing unit, e.g. NEAR".to_string(),
+            })
+        );
+        assert!(parse("1 near").is_err());
+        assert!(parse("1.5 yoctoNEAR").is_err());
+        assert!(parse("NEAR").is_err());
+        assert!(parse("1 000 NEAR").is_err());
+        assert!(parse("340282366920939 NEAR").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let edge_cases = [0, 1, ONE_NEAR - 1, ONE_NEAR, u128::MAX];
+        let random = (0..10_000).map(|i| rng.gen_range(0..=u128::MAX >> (i % 128)));
+        for amount in edge_cases.into_iter().chain(random) {
+            let token = NearToken::from_yoctonear(amount);
+            assert_eq!(token.to_string().parse(), Ok(token), "{}", token);
+        }
+    }
+}
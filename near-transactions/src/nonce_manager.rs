@@ -0,0 +1,77 @@
+//! Local nonce allocation for accounts sending many transactions.
+//!
+//! Fetching the access key before every transaction costs an RPC round trip. `NonceManager` hands out
+//! nonces from a local counter instead, and only goes back to the RPC when a transaction was rejected
+//! with `InvalidNonce`, see `near_accounts::nonces`.
+use near_primitives::types::Nonce;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A `NonceManager` shared between the builders of one service signing with a single access key, see
+/// `TransactionBuilder::with_shared_nonce`.
+pub type SharedNonce = Arc<NonceManager>;
+
+/// Hands out strictly increasing nonces for a single access key. Safe to share between threads.
+#[derive(Debug)]
+pub struct NonceManager {
+    last_nonce: AtomicU64,
+}
+
+impl NonceManager {
+    /// Creates a manager whose first nonce is `initial_nonce + 1`, where `initial_nonce` is the current
+    /// nonce of the access key.
+    pub fn new(initial_nonce: Nonce) -> Self {
+        Self {
+            last_nonce: AtomicU64::new(initial_nonce),
+        }
+    }
+
+    /// Returns a nonce that is greater than every nonce returned before.
+    pub fn next_nonce(&self) -> Nonce {
+        self.last_nonce.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Makes sure the next nonce is greater than `nonce`. Never moves the counter backwards.
+    pub fn advance_to(&self, nonce: Nonce) {
+        self.last_nonce.fetch_max(nonce, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_concurrent_allocation() {
+        let manager = Arc::new(NonceManager::new(10));
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = manager.clone();
+                std::thread::spawn(move || {
+                    let nonces: Vec<Nonce> = (0..100).map(|_| manager.next_nonce()).collect();
+                    // Nonces handed out to a single thread are increasing
+                    assert!(nonces.windows(2).all(|pair| pair[0] < pair[1]));
+                    nonces
+                })
+            })
+            .collect();
+
+        let nonces: HashSet<Nonce> = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect();
+        assert_eq!(nonces.len(), 800);
+        assert_eq!(nonces.iter().min(), Some(&11));
+        assert_eq!(nonces.iter().max(), Some(&810));
+    }
+
+    #[test]
+    fn test_advance_to_never_moves_backwards() {
+        let manager = NonceManager::new(10);
+        manager.advance_to(20);
+        assert_eq!(manager.next_nonce(), 21);
+        manager.advance_to(5);
+        assert_eq!(manager.next_nonce(), 22);
+    }
+}
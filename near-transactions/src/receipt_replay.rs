@@ -0,0 +1,132 @@
+//! Replaying the actions of a receipt as a new transaction, e.g. to debug a failing cross-contract call
+//! with different arguments or a different signer.
+
+use crate::{MAX_ACTIONS_PER_RECEIPT, TransactionBuilder, TransactionBuilderError};
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::ActionReceipt;
+use near_primitives::types::{AccountId, Nonce};
+
+impl TransactionBuilder {
+    /// Appends the actions of `receipt` to the transaction, keeping its signer and receiver.
+    ///
+    /// Nothing is added if the transaction would exceed `MAX_ACTIONS_PER_RECEIPT`.
+    pub fn with_actions_from_receipt(
+        mut self,
+        receipt: &ActionReceipt,
+    ) -> Result<Self, TransactionBuilderError> {
+        let count = self.transaction.actions().len() + receipt.actions.len();
+        if count > MAX_ACTIONS_PER_RECEIPT {
+            return Err(TransactionBuilderError::TooManyActions {
+                count,
+                max: MAX_ACTIONS_PER_RECEIPT,
+            });
+        }
+        for action in &receipt.actions {
+            self.add_action(action.clone());
+        }
+        Ok(self)
+    }
+}
+
+/// Extension methods for `ActionReceipt`.
+pub trait ActionReceiptExt {
+    /// Creates a transaction from `signer_id` to `receiver_id` with the actions of the receipt, using the
+    /// public key of the receipt's signer.
+    ///
+    /// An `ActionReceipt` doesn't record its receiver, which is part of the enclosing `Receipt`, so it
+    /// has to be passed in.
+    fn to_transaction(
+        &self,
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> TransactionBuilder;
+}
+
+impl ActionReceiptExt for ActionReceipt {
+    fn to_transaction(
+        &self,
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> TransactionBuilder {
+        let mut builder = TransactionBuilder::new(
+            signer_id,
+            self.signer_public_key.clone(),
+            receiver_id,
+            nonce,
+            block_hash,
+        );
+        for action in &self.actions {
+            builder.add_action(action.clone());
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        TEST_RECEIVER_ID, TEST_SIGNER_ID, TransactionAssertions, dummy_builder, test_account,
+        test_public_key,
+    };
+    use near_primitives::transaction::{Action, TransferAction};
+
+    fn receipt(actions: Vec<Action>) -> ActionReceipt {
+        ActionReceipt {
+            signer_id: test_account("original.test.near"),
+            signer_public_key: test_public_key(),
+            gas_price: 100_000_000,
+            output_data_receivers: Vec::new(),
+            input_data_ids: Vec::new(),
+            actions,
+        }
+    }
+
+    #[test]
+    fn test_replay_receipt() {
+        let mut original = dummy_builder();
+        original
+            .function_call("nft_mint".to_string(), b"{}".to_vec(), 10, 1)
+            .unwrap()
+            .transfer(5);
+        let failing = receipt(original.build().actions().to_vec());
+
+        let replayed = dummy_builder()
+            .with_actions_from_receipt(&failing)
+            .unwrap()
+            .build();
+        replayed
+            .assert_signer(TEST_SIGNER_ID)
+            .assert_receiver(TEST_RECEIVER_ID)
+            .assert_action_count(2)
+            .assert_has_function_call("nft_mint")
+            .assert_has_transfer(5);
+        assert_eq!(
+            failing
+                .to_transaction(
+                    test_account(TEST_SIGNER_ID),
+                    test_account(TEST_RECEIVER_ID),
+                    1,
+                    CryptoHash::default()
+                )
+                .build(),
+            replayed
+        );
+
+        let too_many = receipt(vec![
+            Action::Transfer(TransferAction { deposit: 1 });
+            MAX_ACTIONS_PER_RECEIPT + 1
+        ]);
+        assert_eq!(
+            dummy_builder().with_actions_from_receipt(&too_many),
+            Err(TransactionBuilderError::TooManyActions {
+                count: MAX_ACTIONS_PER_RECEIPT + 1,
+                max: MAX_ACTIONS_PER_RECEIPT,
+            })
+        );
+    }
+}
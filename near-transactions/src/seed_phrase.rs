@@ -0,0 +1,126 @@
+//! Derives NEAR ed25519 keys from BIP-39 seed phrases.
+//!
+//! Keys are derived the same way as in NEAR wallets and `near-api-js`: the mnemonic is turned into a
+//! BIP-39 seed (without passphrase), from which an ed25519 key is derived along a SLIP-0010 path.
+//! The default path `m/44'/397'/0'` uses NEAR's SLIP-0044 coin type `397`. Further accounts are usually
+//! derived at paths such as `m/44'/397'/0'/0'/1'`; SLIP-0010 only supports hardened components for
+//! ed25519, so every component must end with `'`.
+
+use near_crypto::{ED25519SecretKey, SecretKey};
+use std::fmt;
+use std::str::FromStr;
+use zeroize::Zeroize;
+
+/// The default SLIP-0044 derivation path for NEAR accounts.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/397'/0'";
+
+/// Errors that can occur while deriving a key from a seed phrase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedPhraseError {
+    /// The phrase is not a valid BIP-39 mnemonic.
+    InvalidMnemonic(String),
+    /// The derivation path could not be parsed, or contains a non-hardened component.
+    InvalidDerivationPath(String),
+    /// The key could not be derived along the given path.
+    Derivation(String),
+}
+
+impl fmt::Display for SeedPhraseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMnemonic(reason) => write!(f, "invalid mnemonic: {}", reason),
+            Self::InvalidDerivationPath(path) => write!(f, "invalid derivation path: {}", path),
+            Self::Derivation(reason) => write!(f, "key derivation failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SeedPhraseError {}
+
+/// Derives the ed25519 secret key for the seed `phrase` along the SLIP-0010 derivation `path`.
+///
+/// The intermediate seed and key bytes are wiped from memory once the secret key has been built.
+///
+/// # Arguments
+///
+/// * `phrase` - A BIP-39 mnemonic, e.g. the 12 words shown by a NEAR wallet.
+/// * `path` - A hardened derivation path such as `DEFAULT_DERIVATION_PATH` or `m/44'/397'/0'/0'/1'`.
+pub fn from_mnemonic(phrase: &str, path: &str) -> Result<SecretKey, SeedPhraseError> {
+    let mnemonic = bip39::Mnemonic::parse(phrase)
+        .map_err(|err| SeedPhraseError::InvalidMnemonic(err.to_string()))?;
+    let derivation_path = parse_derivation_path(path)?;
+
+    let mut seed = mnemonic.to_seed("");
+    let derived = slip10::derive_key_from_path(&seed, slip10::Curve::Ed25519, &derivation_path);
+    seed.zeroize();
+    let mut derived = derived.map_err(|err| SeedPhraseError::Derivation(format!("{:?}", err)))?;
+
+    let signing_key = ed25519_dalek::SigningKey::from_bytes(&derived.key);
+    derived.key.zeroize();
+    derived.chain_code.zeroize();
+
+    Ok(SecretKey::ED25519(ED25519SecretKey(
+        signing_key.to_keypair_bytes(),
+    )))
+}
+
+/// Parses `path`, checking that it starts with `m/` and that all of its components are hardened.
+fn parse_derivation_path(path: &str) -> Result<slip10::BIP32Path, SeedPhraseError> {
+    let invalid = || SeedPhraseError::InvalidDerivationPath(path.to_string());
+    let components = path.strip_prefix("m/").ok_or_else(invalid)?;
+    for component in components.split('/') {
+        let index = component.strip_suffix('\'').ok_or_else(invalid)?;
+        if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+    }
+    slip10::BIP32Path::from_str(path).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_from_mnemonic() {
+        let secret_key = from_mnemonic(PHRASE, DEFAULT_DERIVATION_PATH).unwrap();
+        assert_eq!(
+            secret_key.public_key().to_string(),
+            "ed25519:6j4b6zUaty6fD1awqcGCCU9JYGCWYUgdJhQrzfZhqE25"
+        );
+        assert!(matches!(
+            from_mnemonic("abandon about", DEFAULT_DERIVATION_PATH),
+            Err(SeedPhraseError::InvalidMnemonic(_))
+        ));
+    }
+
+    #[test]
+    fn test_derive_at_custom_paths() {
+        let first = from_mnemonic(PHRASE, "m/44'/397'/0'/0'/1'").unwrap();
+        let second = from_mnemonic(PHRASE, "m/44'/397'/0'/0'/2'").unwrap();
+        let default = from_mnemonic(PHRASE, DEFAULT_DERIVATION_PATH).unwrap();
+
+        assert_ne!(first.public_key(), second.public_key());
+        assert_ne!(first.public_key(), default.public_key());
+        assert_eq!(from_mnemonic(PHRASE, "m/44'/397'/0'/0'/1'").unwrap(), first);
+    }
+
+    #[test]
+    fn test_reject_malformed_paths() {
+        for path in [
+            "",
+            "44'/397'/0'",
+            "m/44'/397'/0",
+            "m/44'//0'",
+            "m/44'/x'/0'",
+            "m/",
+        ] {
+            assert_eq!(
+                from_mnemonic(PHRASE, path),
+                Err(SeedPhraseError::InvalidDerivationPath(path.to_string()))
+            );
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Decoding borsh-serialized signed transactions of any `Transaction` version.
+//!
+//! A `V0` transaction is serialized without a version tag, so it starts with the length of its signer ID,
+//! which is between 2 and 64. Later versions are prefixed with their version number, so the first byte
+//! tells the versions apart without deserializing the rest.
+
+use crate::{ParseError, TransactionVersion};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use near_crypto::Signature;
+use near_primitives::transaction::{SignedTransaction, Transaction, TransactionV0, TransactionV1};
+
+/// The version tag of a `V1` transaction.
+const V1_TAG: u8 = 1;
+
+/// Extension methods for `SignedTransaction`.
+pub trait SignedTransactionExt {
+    /// Returns the version of the signed transaction.
+    fn version(&self) -> TransactionVersion;
+}
+
+impl SignedTransactionExt for SignedTransaction {
+    fn version(&self) -> TransactionVersion {
+        match self.transaction {
+            Transaction::V0(_) => TransactionVersion::V0,
+            Transaction::V1(_) => TransactionVersion::V1,
+        }
+    }
+}
+
+/// Returns the version of the borsh-serialized (signed) transaction `bytes` from its first byte, without
+/// deserializing it.
+pub fn signed_tx_version(bytes: &[u8]) -> Result<TransactionVersion, ParseError> {
+    match bytes.first() {
+        Some(&V1_TAG) => Ok(TransactionVersion::V1),
+        Some(2..=64) => Ok(TransactionVersion::V0),
+        Some(tag) => Err(invalid_transaction(format!("unknown version tag {}", tag))),
+        None => Err(invalid_transaction("empty input".to_string())),
+    }
+}
+
+/// Decodes a signed transaction encoded as standard, padded base64 of its borsh serialization.
+pub fn signed_tx_from_base64(s: &str) -> Result<SignedTransaction, ParseError> {
+    let bytes = STANDARD
+        .decode(s.trim())
+        .map_err(|err| invalid_transaction(err.to_string()))?;
+    let (transaction, signature) = match signed_tx_version(&bytes)? {
+        TransactionVersion::V0 => {
+            let (transaction, signature) = borsh::from_slice::<(TransactionV0, Signature)>(&bytes)
+                .map_err(|err| invalid_transaction(err.to_string()))?;
+            (Transaction::V0(transaction), signature)
+        }
+        TransactionVersion::V1 => {
+            let (transaction, signature) =
+                borsh::from_slice::<(TransactionV1, Signature)>(&bytes[1..])
+                    .map_err(|err| invalid_transaction(err.to_string()))?;
+            (Transaction::V1(transaction), signature)
+        }
+    };
+    Ok(SignedTransaction::new(signature, transaction))
+}
+
+fn invalid_transaction(reason: String) -> ParseError {
+    ParseError::InvalidField {
+        field: "transaction".to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransactionBuilder;
+    use crate::test_utils::{TEST_RECEIVER_ID, TEST_SIGNER_ID, test_account, test_signer};
+    use near_primitives::hash::CryptoHash;
+
+    fn signed_base64(version: TransactionVersion) -> String {
+        let signer = test_signer();
+        let mut builder = TransactionBuilder::new_with_version(
+            test_account(TEST_SIGNER_ID),
+            signer.public_key.clone(),
+            test_account(TEST_RECEIVER_ID),
+            1,
+            CryptoHash::hash_bytes(b"block"),
+            version,
+        );
+        builder.transfer(1);
+        builder.into_near_cli_base64(&signer)
+    }
+
+    #[test]
+    fn test_decodes_v0() {
+        let encoded = signed_base64(TransactionVersion::V0);
+        let expected: SignedTransaction =
+            borsh::from_slice(&STANDARD.decode(&encoded).unwrap()).unwrap();
+
+        let signed = signed_tx_from_base64(&encoded).unwrap();
+        assert_eq!(signed.version(), TransactionVersion::V0);
+        assert_eq!(signed, expected);
+        assert_eq!(signed.get_hash(), expected.get_hash());
+    }
+
+    #[test]
+    fn test_decodes_v1() {
+        let encoded = signed_base64(TransactionVersion::V1);
+        let bytes = STANDARD.decode(&encoded).unwrap();
+        assert_eq!(signed_tx_version(&bytes), Ok(TransactionVersion::V1));
+
+        let signed = signed_tx_from_base64(&encoded).unwrap();
+        assert_eq!(signed.version(), TransactionVersion::V1);
+        assert_eq!(
+            signed,
+            borsh::from_slice::<SignedTransaction>(&bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_versions() {
+        assert!(signed_tx_version(&[]).is_err());
+        assert!(signed_tx_version(&[0]).is_err());
+        assert!(signed_tx_version(&[2]).is_ok());
+        assert!(signed_tx_from_base64(&STANDARD.encode([7, 0, 0, 0])).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+//! Defines the `AsyncSigner` trait for signers that sign transactions asynchronously, such as hardware
+//! wallets that wait for the user to confirm on the device.
+
+use async_trait::async_trait;
+use near_crypto::{InMemorySigner, PublicKey, Signature};
+use near_primitives::transaction::Transaction;
+use std::convert::Infallible;
+
+/// A signer that signs whole transactions asynchronously.
+///
+/// Unlike `near_crypto::Signer`, which signs the transaction hash, implementations receive the full
+/// `Transaction`, as some devices need to display its contents before signing.
+#[async_trait]
+pub trait AsyncSigner: Send + Sync {
+    /// The error returned when signing fails.
+    type Error;
+
+    /// Returns the public key matching the signatures produced by this signer.
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs `transaction`, returning the signature over its hash.
+    async fn sign_transaction(&self, transaction: &Transaction) -> Result<Signature, Self::Error>;
+}
+
+#[async_trait]
+impl AsyncSigner for InMemorySigner {
+    type Error = Infallible;
+
+    fn public_key(&self) -> PublicKey {
+        InMemorySigner::public_key(self)
+    }
+
+    async fn sign_transaction(&self, transaction: &Transaction) -> Result<Signature, Self::Error> {
+        Ok(self.sign(transaction.get_hash_and_size().0.as_ref()))
+    }
+}
@@ -0,0 +1,117 @@
+//! Compact, serializable signing requests for handing a transaction to a mobile wallet, e.g. as the
+//! JSON payload of a QR code.
+//!
+//! The actions are borsh-serialized and base64 encoded, which is much shorter than their JSON
+//! representation, while the fields a wallet shows before signing stay readable.
+
+use crate::{ParseError, TransactionBuilder};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use near_crypto::PublicKey;
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::Action;
+use near_primitives::types::{AccountId, Nonce};
+use serde::{Deserialize, Serialize};
+
+/// A transaction to be signed by a wallet, see `TransactionBuilder::to_signing_request`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningRequest {
+    /// The account signing the transaction.
+    pub signer_id: AccountId,
+    /// The access key the transaction is signed with.
+    pub public_key: PublicKey,
+    /// The account the actions are executed on.
+    pub receiver_id: AccountId,
+    /// The nonce of the transaction.
+    pub nonce: Nonce,
+    /// The hash of the block the transaction references.
+    pub block_hash: CryptoHash,
+    /// The actions, as standard base64 of their borsh-serialized `Vec<Action>`.
+    pub actions: String,
+    /// Where the wallet sends the user after signing, if anywhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+}
+
+impl TransactionBuilder {
+    /// Creates a signing request for the transaction, without a callback URL.
+    pub fn to_signing_request(&self) -> SigningRequest {
+        let actions =
+            borsh::to_vec(self.transaction.actions()).expect("Borsh serialization failed");
+        SigningRequest {
+            signer_id: self.transaction.signer_id().clone(),
+            public_key: self.transaction.public_key().clone(),
+            receiver_id: self.transaction.receiver_id().clone(),
+            nonce: self.transaction.nonce(),
+            block_hash: *self.transaction.block_hash(),
+            actions: STANDARD.encode(actions),
+            callback_url: None,
+        }
+    }
+
+    /// Reconstructs the transaction of a signing request created by `to_signing_request`.
+    ///
+    /// # Returns
+    ///
+    /// A `TransactionBuilder` holding the transaction, or a `ParseError` if the actions can't be decoded.
+    pub fn from_signing_request(request: &SigningRequest) -> Result<Self, ParseError> {
+        let invalid_actions = |reason: String| ParseError::InvalidField {
+            field: "actions".to_string(),
+            reason,
+        };
+        let bytes = STANDARD
+            .decode(&request.actions)
+            .map_err(|err| invalid_actions(err.to_string()))?;
+        let actions: Vec<Action> =
+            borsh::from_slice(&bytes).map_err(|err| invalid_actions(err.to_string()))?;
+
+        let mut builder = TransactionBuilder::new(
+            request.signer_id.clone(),
+            request.public_key.clone(),
+            request.receiver_id.clone(),
+            request.nonce,
+            request.block_hash,
+        );
+        for action in actions {
+            builder.add_action(action);
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dummy_builder, test_public_key};
+    use near_primitives::account::AccessKey;
+
+    #[test]
+    fn test_signing_request_round_trip() {
+        let mut builder = dummy_builder();
+        builder
+            .create_account()
+            .transfer(1)
+            .add_key(test_public_key(), AccessKey::full_access())
+            .function_call("init".to_string(), br#"{"owner":"a.near"}"#.to_vec(), 10, 0)
+            .unwrap();
+
+        let mut request = builder.to_signing_request();
+        request.callback_url = Some("https://app.example/signed".to_string());
+        let payload = serde_json::to_string(&request).unwrap();
+        let parsed: SigningRequest = serde_json::from_str(&payload).unwrap();
+        assert_eq!(parsed, request);
+        assert_eq!(
+            TransactionBuilder::from_signing_request(&parsed),
+            Ok(builder)
+        );
+
+        let invalid = SigningRequest {
+            actions: "not base64!".to_string(),
+            ..request
+        };
+        assert!(matches!(
+            TransactionBuilder::from_signing_request(&invalid),
+            Err(ParseError::InvalidField { .. })
+        ));
+    }
+}
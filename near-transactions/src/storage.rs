@@ -0,0 +1,267 @@
+//! NEP-145 storage management.
+//!
+//! Contracts implementing NEP-145, such as most fungible token contracts, require accounts to pay for
+//! their storage before interacting with them. `StorageDepositBuilder` creates the `storage_deposit`,
+//! `storage_unregister` and `storage_withdraw` calls defined by the standard.
+use crate::near_token::NearToken;
+use near_primitives::{
+    account::{AccessKey, AccessKeyPermission},
+    transaction::{Action, FunctionCallAction},
+    types::{AccountId, Balance, Gas},
+};
+use serde_json::{Value, json};
+
+/// Gas attached to every storage management call (30 TGas).
+pub const STORAGE_MANAGEMENT_GAS: Gas = 30_000_000_000_000;
+
+/// Deposit attached to `storage_unregister` and `storage_withdraw`, which require exactly one yoctoNEAR.
+const ONE_YOCTO: Balance = 1;
+
+/// Bytes the protocol charges for every record in the state on top of its key and value.
+const NUM_EXTRA_BYTES_RECORD: u64 = 40;
+
+/// Length of a borsh-serialized ED25519 public key: the key type followed by 32 bytes.
+const ED25519_PUBLIC_KEY_LEN: u64 = 33;
+
+/// Computes the balance locked for storage when adding an ED25519 access key with `permission`.
+///
+/// The protocol charges `storage_byte_cost` (see the runtime config) for the public key, the
+/// borsh-serialized access key and a fixed overhead per record. Function call keys with more or longer
+/// method names therefore cost more.
+pub fn access_key_storage_cost(
+    storage_byte_cost: Balance,
+    permission: &AccessKeyPermission,
+) -> Balance {
+    let access_key = AccessKey {
+        nonce: 0,
+        permission: permission.clone(),
+    };
+    let access_key_len = borsh::to_vec(&access_key)
+        .expect("Access keys are always serializable")
+        .len() as u64;
+    let bytes = ED25519_PUBLIC_KEY_LEN + access_key_len + NUM_EXTRA_BYTES_RECORD;
+    storage_byte_cost * bytes as Balance
+}
+
+/// The storage deposit a contract requires, as returned by `storage_balance_bounds`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageBalanceBounds {
+    /// The deposit required to register an account.
+    pub min: NearToken,
+    /// The maximum deposit the contract accepts, or `None` if unbounded.
+    pub max: Option<NearToken>,
+}
+
+/// The storage balance of an account registered on a contract, as returned by `storage_balance_of`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageBalance {
+    /// The total deposit of the account.
+    pub total: NearToken,
+    /// The part of the deposit not used for storage, which can be withdrawn.
+    pub available: NearToken,
+}
+
+/// Builds the NEP-145 storage management calls for a single contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageDepositBuilder {
+    contract_id: AccountId,
+    actions: Vec<Action>,
+}
+
+impl StorageDepositBuilder {
+    /// Starts building storage management calls to `contract_id`.
+    pub fn new(contract_id: AccountId) -> Self {
+        Self {
+            contract_id,
+            actions: Vec::new(),
+        }
+    }
+
+    /// The contract the calls are meant for, which must be the receiver of the transaction.
+    pub fn contract_id(&self) -> &AccountId {
+        &self.contract_id
+    }
+
+    /// Deposits `amount` for the storage of `account_id`, registering it if needed.
+    ///
+    /// Registering a new account requires at least the `min` of the contract's storage balance bounds.
+    pub fn deposit_for(&mut self, account_id: AccountId, amount: NearToken) -> &mut Self {
+        let args = json!({ "account_id": account_id });
+        self.call("storage_deposit", args, amount.as_yoctonear())
+    }
+
+    /// Unregisters the predecessor account and refunds its storage deposit. Fails if the account still
+    /// holds a balance on the contract.
+    pub fn unregister(&mut self) -> &mut Self {
+        self.call("storage_unregister", json!({ "force": false }), ONE_YOCTO)
+    }
+
+    /// Withdraws `amount` of the predecessor's available storage balance, or all of it when `None`.
+    pub fn withdraw(&mut self, amount: Option<NearToken>) -> &mut Self {
+        let args = match amount {
+            Some(amount) => json!({ "amount": amount.as_yoctonear().to_string() }),
+            None => json!({}),
+        };
+        self.call("storage_withdraw", args, ONE_YOCTO)
+    }
+
+    /// Returns the function call actions in the order they were added.
+    pub fn build(self) -> Vec<Action> {
+        self.actions
+    }
+
+    fn call(&mut self, method_name: &str, args: Value, deposit: Balance) -> &mut Self {
+        self.actions
+            .push(Action::FunctionCall(Box::new(FunctionCallAction {
+                method_name: method_name.to_string(),
+                args: args.to_string().into_bytes(),
+                gas: STORAGE_MANAGEMENT_GAS,
+                deposit,
+            })));
+        self
+    }
+}
+
+/// Parses the JSON result of `storage_balance_bounds`, e.g. `{"min": "1250000000000000000000", "max": null}`.
+pub fn parse_storage_balance_bounds(
+    result: &[u8],
+) -> Result<StorageBalanceBounds, Box<dyn std::error::Error>> {
+    let value: Value = serde_json::from_slice(result)?;
+    Ok(StorageBalanceBounds {
+        min: parse_amount(&value, "min")?.ok_or("Missing `min` in storage balance bounds")?,
+        max: parse_amount(&value, "max")?,
+    })
+}
+
+/// Parses the JSON result of `storage_balance_of`, e.g. `{"total": "100", "available": "0"}` or `null`.
+pub fn parse_storage_balance(
+    result: &[u8],
+) -> Result<Option<StorageBalance>, Box<dyn std::error::Error>> {
+    let value: Value = serde_json::from_slice(result)?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(StorageBalance {
+        total: parse_amount(&value, "total")?.ok_or("Missing `total` in storage balance")?,
+        available: parse_amount(&value, "available")?
+            .ok_or("Missing `available` in storage balance")?,
+    }))
+}
+
+/// Parses the optional `U128` amount `field` of `value`, which NEP-145 encodes as a decimal string.
+fn parse_amount(
+    value: &Value,
+    field: &str,
+) -> Result<Option<NearToken>, Box<dyn std::error::Error>> {
+    match &value[field] {
+        Value::Null => Ok(None),
+        Value::String(amount) => Ok(Some(NearToken::from_yoctonear(amount.parse()?))),
+        other => Err(format!("Invalid `{}` in storage balance: {}", field, other).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function_call(action: &Action) -> (&str, Value, Balance) {
+        match action {
+            Action::FunctionCall(call) => (
+                call.method_name.as_str(),
+                serde_json::from_slice(&call.args).unwrap(),
+                call.deposit,
+            ),
+            action => panic!("Unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_storage_management_calls() {
+        let mut builder = StorageDepositBuilder::new("token.near".parse().unwrap());
+        builder
+            .deposit_for(
+                "alice.near".parse().unwrap(),
+                NearToken::from_yoctonear(1_250_000_000_000_000_000_000),
+            )
+            .withdraw(Some(NearToken::from_yoctonear(10)))
+            .withdraw(None)
+            .unregister();
+        let actions = builder.build();
+
+        assert_eq!(
+            function_call(&actions[0]),
+            (
+                "storage_deposit",
+                json!({ "account_id": "alice.near" }),
+                1_250_000_000_000_000_000_000
+            )
+        );
+        assert_eq!(
+            function_call(&actions[1]),
+            ("storage_withdraw", json!({ "amount": "10" }), ONE_YOCTO)
+        );
+        assert_eq!(
+            function_call(&actions[2]),
+            ("storage_withdraw", json!({}), ONE_YOCTO)
+        );
+        assert_eq!(
+            function_call(&actions[3]),
+            ("storage_unregister", json!({ "force": false }), ONE_YOCTO)
+        );
+    }
+
+    #[test]
+    fn test_parse_storage_balance() {
+        assert_eq!(
+            parse_storage_balance(br#"{"total": "100", "available": "40"}"#).unwrap(),
+            Some(StorageBalance {
+                total: NearToken::from_yoctonear(100),
+                available: NearToken::from_yoctonear(40),
+            })
+        );
+        assert_eq!(parse_storage_balance(b"null").unwrap(), None);
+    }
+
+    #[test]
+    fn test_access_key_storage_cost() {
+        use near_primitives::account::FunctionCallPermission;
+
+        let storage_byte_cost = 10_000_000_000_000_000_000;
+        let function_call_key = |method_names: Vec<String>| {
+            AccessKeyPermission::FunctionCall(FunctionCallPermission {
+                allowance: None,
+                receiver_id: "app.near".to_string(),
+                method_names,
+            })
+        };
+
+        let full_access =
+            access_key_storage_cost(storage_byte_cost, &AccessKeyPermission::FullAccess);
+        let one_method =
+            access_key_storage_cost(storage_byte_cost, &function_call_key(vec!["a".to_string()]));
+        let two_methods = access_key_storage_cost(
+            storage_byte_cost,
+            &function_call_key(vec!["a".to_string(), "set_status".to_string()]),
+        );
+
+        // nonce (8) + permission tag (1) + public key (33) + record overhead (40)
+        assert_eq!(full_access, 82 * storage_byte_cost);
+        assert!(full_access < one_method);
+        assert!(one_method < two_methods);
+    }
+
+    #[test]
+    fn test_parse_storage_balance_bounds() {
+        let bounds =
+            parse_storage_balance_bounds(br#"{"min": "1250000000000000000000", "max": null}"#)
+                .unwrap();
+        assert_eq!(
+            bounds,
+            StorageBalanceBounds {
+                min: NearToken::from_yoctonear(1_250_000_000_000_000_000_000),
+                max: None,
+            }
+        );
+        assert!(parse_storage_balance_bounds(br#"{"max": "1"}"#).is_err());
+    }
+}
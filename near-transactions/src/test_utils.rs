@@ -0,0 +1,182 @@
+//! Throwaway values for tests and fixtures, so they don't need to construct account IDs, keys and
+//! builders by hand.
+//!
+//! All keys are derived from fixed seeds, so they are the same in every run and must never hold funds.
+
+use crate::TransactionBuilder;
+use near_crypto::{InMemorySigner, KeyType, PublicKey, SecretKey};
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::{Action, Transaction};
+use near_primitives::types::{AccountId, Balance};
+
+/// The signer of the transactions built by `dummy_builder`.
+pub const TEST_SIGNER_ID: &str = "signer.test.near";
+
+/// The receiver of the transactions built by `dummy_builder`.
+pub const TEST_RECEIVER_ID: &str = "receiver.test.near";
+
+/// Parses `name` into an `AccountId`.
+///
+/// # Panics
+///
+/// Panics if `name` is not a valid account ID.
+pub fn test_account(name: &str) -> AccountId {
+    name.parse()
+        .unwrap_or_else(|err| panic!("{:?} is not a valid account ID: {}", name, err))
+}
+
+/// Returns the ed25519 secret key of `test_signer`.
+pub fn test_secret_key() -> SecretKey {
+    SecretKey::from_seed(KeyType::ED25519, TEST_SIGNER_ID)
+}
+
+/// Returns the public key of `test_signer`.
+pub fn test_public_key() -> PublicKey {
+    test_secret_key().public_key()
+}
+
+/// Returns a signer for `TEST_SIGNER_ID` using `test_secret_key`.
+pub fn test_signer() -> InMemorySigner {
+    let secret_key = test_secret_key();
+    InMemorySigner {
+        account_id: test_account(TEST_SIGNER_ID),
+        public_key: secret_key.public_key(),
+        secret_key,
+    }
+}
+
+/// Returns a builder without actions for a transaction from `TEST_SIGNER_ID` to `TEST_RECEIVER_ID`,
+/// signed with `test_public_key`, with nonce 1 and a zero block hash.
+pub fn dummy_builder() -> TransactionBuilder {
+    TransactionBuilder::new(
+        test_account(TEST_SIGNER_ID),
+        test_public_key(),
+        test_account(TEST_RECEIVER_ID),
+        1,
+        CryptoHash::default(),
+    )
+}
+
+/// Assertions on the fields and actions of a transaction, panicking with a description of the
+/// transaction if they fail.
+pub trait TransactionAssertions {
+    /// Asserts that the transaction is signed by `expected`.
+    fn assert_signer(&self, expected: &str) -> &Self;
+    /// Asserts that the transaction is sent to `expected`.
+    fn assert_receiver(&self, expected: &str) -> &Self;
+    /// Asserts that the transaction has exactly `n` actions.
+    fn assert_action_count(&self, n: usize) -> &Self;
+    /// Asserts that the transaction transfers exactly `amount` yoctoNEAR in one of its actions.
+    fn assert_has_transfer(&self, amount: Balance) -> &Self;
+    /// Asserts that the transaction calls `method` in one of its actions.
+    fn assert_has_function_call(&self, method: &str) -> &Self;
+}
+
+impl TransactionAssertions for Transaction {
+    fn assert_signer(&self, expected: &str) -> &Self {
+        assert_eq!(
+            self.signer_id().as_str(),
+            expected,
+            "unexpected signer of {:?}",
+            self
+        );
+        self
+    }
+
+    fn assert_receiver(&self, expected: &str) -> &Self {
+        assert_eq!(
+            self.receiver_id().as_str(),
+            expected,
+            "unexpected receiver of {:?}",
+            self
+        );
+        self
+    }
+
+    fn assert_action_count(&self, n: usize) -> &Self {
+        assert_eq!(
+            self.actions().len(),
+            n,
+            "unexpected number of actions in {:?}",
+            self.actions()
+        );
+        self
+    }
+
+    fn assert_has_transfer(&self, amount: Balance) -> &Self {
+        assert!(
+            self.actions().iter().any(
+                |action| matches!(action, Action::Transfer(transfer) if transfer.deposit == amount)
+            ),
+            "no transfer of {} yoctoNEAR in {:?}",
+            amount,
+            self.actions()
+        );
+        self
+    }
+
+    fn assert_has_function_call(&self, method: &str) -> &Self {
+        assert!(
+            self.actions().iter().any(
+                |action| matches!(action, Action::FunctionCall(call) if call.method_name == method)
+            ),
+            "no call of {:?} in {:?}",
+            method,
+            self.actions()
+        );
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dummy_builder_is_signable() {
+        let mut builder = dummy_builder();
+        builder.transfer(1);
+        let signed = builder.sign_transaction(&test_signer());
+
+        assert_eq!(
+            signed.transaction.signer_id(),
+            &test_account(TEST_SIGNER_ID)
+        );
+        assert_eq!(signed.transaction.public_key(), &test_public_key());
+        assert!(
+            signed
+                .signature
+                .verify(signed.get_hash().as_ref(), &test_public_key())
+        );
+    }
+
+    #[test]
+    fn test_transaction_assertions() {
+        let mut builder = dummy_builder();
+        builder
+            .transfer(5)
+            .function_call("ping".to_string(), vec![], 10, 0)
+            .unwrap();
+        builder
+            .build()
+            .assert_signer(TEST_SIGNER_ID)
+            .assert_receiver(TEST_RECEIVER_ID)
+            .assert_action_count(2)
+            .assert_has_transfer(5)
+            .assert_has_function_call("ping");
+    }
+
+    #[test]
+    #[should_panic(expected = "no transfer of 6 yoctoNEAR")]
+    fn test_transaction_assertions_panic() {
+        let mut builder = dummy_builder();
+        builder.transfer(5);
+        builder.build().assert_has_transfer(6);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a valid account ID")]
+    fn test_account_rejects_invalid_names() {
+        test_account("Not Valid");
+    }
+}
@@ -0,0 +1,75 @@
+//! Converts NEAR amounts to USD using prices of the Pyth price oracle deployed on NEAR.
+//!
+//! The price is the result of `get_price` on the Pyth contract `pyth-oracle.near` for the NEAR/USD price
+//! feed, which `near_accounts::token_price::TokenPriceClient` fetches and `parse_pyth_price` decodes.
+//!
+//! Oracle prices are only as trustworthy as the oracle. They lag the market, and a manipulated or stale
+//! price feed yields wrong values, so only use them for display, never to decide how much to transfer.
+use crate::near_token::{NearToken, ONE_NEAR};
+use serde_json::Value;
+
+/// Account ID of the Pyth price oracle on mainnet.
+pub const PYTH_ORACLE_MAINNET: &str = "pyth-oracle.near";
+
+/// Pyth price feed ID of NEAR/USD on mainnet.
+pub const NEAR_USD_PRICE_FEED: &str =
+    "c415de8d2eba7db216527dff4b60e8f3a5311c740dadb233e13e12547e226750";
+
+/// Parses a Pyth price like `{"price": "512345678", "conf": "1000", "expo": -8, "publish_time": 1}`
+/// into a decimal price, failing for `null`, which the oracle returns when it has no recent price.
+pub fn parse_pyth_price(value: &Value) -> Result<f64, Box<dyn std::error::Error>> {
+    if value.is_null() {
+        return Err("The oracle has no recent price".into());
+    }
+    let price: i64 = value["price"]
+        .as_str()
+        .ok_or("Missing `price` in oracle response")?
+        .parse()?;
+    let expo = value["expo"]
+        .as_i64()
+        .ok_or("Missing `expo` in oracle response")?;
+    Ok(price as f64 * 10f64.powi(expo as i32))
+}
+
+impl NearToken {
+    /// Converts the amount to USD given the price of one NEAR, e.g. from `parse_pyth_price`.
+    pub fn to_usd(&self, price_per_near: f64) -> f64 {
+        self.as_yoctonear() as f64 / ONE_NEAR as f64 * price_per_near
+    }
+}
+
+/// Formats a USD amount with two decimal places, e.g. `"$1234.57"`.
+pub fn format_usd(amount: f64) -> String {
+    if amount < 0.0 {
+        format!("-${:.2}", -amount)
+    } else {
+        format!("${:.2}", amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_pyth_price() {
+        let price = parse_pyth_price(&json!({
+            "price": "512345678",
+            "conf": "1000",
+            "expo": -8,
+            "publish_time": 1_700_000_000,
+        }))
+        .unwrap();
+        assert!((price - 5.12345678).abs() < 1e-9);
+        assert!(parse_pyth_price(&Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_to_usd_and_format() {
+        let amount = NearToken::from_yoctonear(ONE_NEAR * 3 / 2);
+        assert_eq!(format_usd(amount.to_usd(4.0)), "$6.00");
+        assert_eq!(format_usd(1234.567), "$1234.57");
+        assert_eq!(format_usd(-0.5), "-$0.50");
+    }
+}
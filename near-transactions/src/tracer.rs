@@ -0,0 +1,252 @@
+//! Records how a transaction was built, for debugging and replaying it with new chain parameters.
+use crate::{TransactionBuilder, TransactionBuilderError};
+use near_crypto::{InMemorySigner, PublicKey};
+use near_primitives::{
+    account::AccessKey,
+    hash::CryptoHash,
+    transaction::Action,
+    types::{AccountId, Balance, Gas, Nonce},
+};
+
+/// A single call made on a `TransactionTracer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildStep {
+    CreateAccount,
+    DeployContract {
+        /// The sha256 hash of the deployed code.
+        code_hash: CryptoHash,
+    },
+    FunctionCall {
+        method: String,
+        gas: Gas,
+        deposit: Balance,
+    },
+    Transfer {
+        deposit: Balance,
+    },
+    Stake {
+        stake: Balance,
+        public_key: PublicKey,
+    },
+    AddKey {
+        public_key: PublicKey,
+        access_key: AccessKey,
+    },
+    DeleteKey {
+        public_key: PublicKey,
+    },
+    DeleteAccount {
+        beneficiary_id: AccountId,
+    },
+    Delegate {
+        sender_id: AccountId,
+    },
+    /// Any other action added with `add_action`.
+    Other,
+}
+
+impl From<&Action> for BuildStep {
+    fn from(action: &Action) -> Self {
+        match action {
+            Action::CreateAccount(_) => BuildStep::CreateAccount,
+            Action::DeployContract(deploy) => BuildStep::DeployContract {
+                code_hash: CryptoHash::hash_bytes(&deploy.code),
+            },
+            Action::FunctionCall(call) => BuildStep::FunctionCall {
+                method: call.method_name.clone(),
+                gas: call.gas,
+                deposit: call.deposit,
+            },
+            Action::Transfer(transfer) => BuildStep::Transfer {
+                deposit: transfer.deposit,
+            },
+            Action::Stake(stake) => BuildStep::Stake {
+                stake: stake.stake,
+                public_key: stake.public_key.clone(),
+            },
+            Action::AddKey(add_key) => BuildStep::AddKey {
+                public_key: add_key.public_key.clone(),
+                access_key: add_key.access_key.clone(),
+            },
+            Action::DeleteKey(delete_key) => BuildStep::DeleteKey {
+                public_key: delete_key.public_key.clone(),
+            },
+            Action::DeleteAccount(delete_account) => BuildStep::DeleteAccount {
+                beneficiary_id: delete_account.beneficiary_id.clone(),
+            },
+            Action::Delegate(signed_delegate) => BuildStep::Delegate {
+                sender_id: signed_delegate.delegate_action.sender_id.clone(),
+            },
+            _ => BuildStep::Other,
+        }
+    }
+}
+
+/// Wraps a `TransactionBuilder` and records every action added through it.
+#[derive(Debug, Clone)]
+pub struct TransactionTracer {
+    builder: TransactionBuilder,
+    steps: Vec<BuildStep>,
+}
+
+impl TransactionTracer {
+    /// Starts tracing `builder`. Actions already in the builder are not recorded.
+    pub fn new(builder: TransactionBuilder) -> Self {
+        Self {
+            builder,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Adds an arbitrary action and records it.
+    pub fn add_action(&mut self, action: Action) -> &mut Self {
+        self.steps.push(BuildStep::from(&action));
+        self.builder.add_action(action);
+        self
+    }
+
+    pub fn create_account(&mut self) -> &mut Self {
+        self.builder.create_account();
+        self.record_last_action()
+    }
+
+    pub fn deploy_contract(&mut self, code: &[u8]) -> &mut Self {
+        self.builder.deploy_contract(code);
+        self.record_last_action()
+    }
+
+    pub fn function_call(
+        &mut self,
+        method_name: String,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit: Balance,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        self.builder
+            .function_call(method_name, args, gas, deposit)?;
+        Ok(self.record_last_action())
+    }
+
+    pub fn transfer(&mut self, deposit: Balance) -> &mut Self {
+        self.builder.transfer(deposit);
+        self.record_last_action()
+    }
+
+    pub fn stake(&mut self, stake: Balance, public_key: PublicKey) -> &mut Self {
+        self.builder.stake(stake, public_key);
+        self.record_last_action()
+    }
+
+    pub fn add_key(&mut self, public_key: PublicKey, access_key: AccessKey) -> &mut Self {
+        self.builder.add_key(public_key, access_key);
+        self.record_last_action()
+    }
+
+    pub fn delete_key(&mut self, public_key: PublicKey) -> &mut Self {
+        self.builder.delete_key(public_key);
+        self.record_last_action()
+    }
+
+    pub fn delete_account(&mut self, beneficiary_id: AccountId) -> &mut Self {
+        self.builder.delete_account(beneficiary_id);
+        self.record_last_action()
+    }
+
+    /// Returns the recorded steps in the order they were made.
+    pub fn steps(&self) -> &[BuildStep] {
+        &self.steps
+    }
+
+    /// Returns the traced builder.
+    pub fn builder(&self) -> &TransactionBuilder {
+        &self.builder
+    }
+
+    /// Rebuilds the transaction from the recorded steps for `new_signer`, with a new nonce and block hash.
+    /// The receiver and transaction version stay the same.
+    pub fn replay(
+        &self,
+        new_signer: &InMemorySigner,
+        new_nonce: Nonce,
+        new_block_hash: CryptoHash,
+    ) -> TransactionBuilder {
+        let mut builder = TransactionBuilder::new_with_version(
+            new_signer.account_id.clone(),
+            new_signer.public_key(),
+            self.builder.transaction.receiver_id().clone(),
+            new_nonce,
+            new_block_hash,
+            self.builder.version(),
+        );
+        let actions = self.builder.transaction.actions();
+        for action in &actions[actions.len() - self.steps.len()..] {
+            builder.add_action(action.clone());
+        }
+        builder
+    }
+
+    fn record_last_action(&mut self) -> &mut Self {
+        let action = self
+            .builder
+            .transaction
+            .actions()
+            .last()
+            .expect("An action was just added");
+        self.steps.push(BuildStep::from(action));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, SecretKey};
+
+    #[test]
+    fn test_trace_and_replay() {
+        let mut tracer = TransactionTracer::new(TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "contract.near".parse().unwrap(),
+            1,
+            CryptoHash::default(),
+        ));
+        tracer
+            .deploy_contract(b"wasm")
+            .function_call("init".to_string(), vec![], 10, 0)
+            .unwrap()
+            .transfer(5);
+
+        assert_eq!(
+            tracer.steps(),
+            &[
+                BuildStep::DeployContract {
+                    code_hash: CryptoHash::hash_bytes(b"wasm"),
+                },
+                BuildStep::FunctionCall {
+                    method: "init".to_string(),
+                    gas: 10,
+                    deposit: 0,
+                },
+                BuildStep::Transfer { deposit: 5 },
+            ]
+        );
+
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "bob.near");
+        let signer = InMemorySigner {
+            account_id: "bob.near".parse().unwrap(),
+            public_key: secret_key.public_key(),
+            secret_key,
+        };
+        let block_hash = CryptoHash::hash_bytes(b"block");
+        let replayed = tracer.replay(&signer, 7, block_hash).build();
+        let original = tracer.builder().clone().build();
+
+        assert_eq!(replayed.signer_id().as_str(), "bob.near");
+        assert_eq!(replayed.public_key(), &signer.public_key());
+        assert_eq!(replayed.receiver_id().as_str(), "contract.near");
+        assert_eq!(replayed.nonce(), 7);
+        assert_eq!(replayed.block_hash(), &block_hash);
+        assert_eq!(replayed.actions(), original.actions());
+    }
+}
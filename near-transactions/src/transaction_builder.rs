@@ -10,22 +10,69 @@
 //!
 //! This module aims to simplify transaction creation and enhance developer experience by providing a clear and concise
 //! way to interact with the NEAR blockchain programmatically.
-use near_crypto::{InMemorySigner, PublicKey};
+use crate::error::{ArgsFileError, TransactionBuilderError, WrongActionCount};
+use crate::key_import::{KeyImportError, KeyPair};
+use crate::method_names::{MethodNames, is_valid_method_name};
+use crate::near_token::{NEAR_DECIMALS, NearToken};
+use crate::nonce_manager::SharedNonce;
+#[cfg(feature = "seed-phrase")]
+use crate::seed_phrase::{DEFAULT_DERIVATION_PATH, SeedPhraseError, from_mnemonic};
+use crate::signer::AsyncSigner;
+use near_crypto::{InMemorySigner, KeyType, PublicKey, Signature};
 use near_primitives::{
-    account::AccessKey,
+    account::{AccessKey, AccessKeyPermission, FunctionCallPermission},
     hash::CryptoHash,
     transaction::{
         Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
         DeployContractAction, FunctionCallAction, SignedTransaction, StakeAction, Transaction,
-        TransactionV0, TransferAction,
+        TransactionV0, TransactionV1, TransferAction,
     },
     types::{AccountId, Balance, Gas, Nonce},
 };
+#[cfg(any(test, feature = "test-utils"))]
+use rand::Rng;
+use serde_json::json;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Maximum size in bytes of a borsh-serialized transaction accepted by the protocol (1.5 MiB).
+pub const MAX_TRANSACTION_SIZE: u64 = 1_572_864;
+
+/// Account IDs of the promise batch helper contract used by test harnesses, see `attach_promise_batch`.
+#[cfg(any(test, feature = "test-utils"))]
+pub const PROMISE_BATCH_TEST_CONTRACTS: &[&str] =
+    &["promise-batch.test.near", "promise-batch.testnet"];
+
+/// Maximum number of actions in a single transaction, the protocol's `max_actions_per_receipt`.
+pub const MAX_ACTIONS_PER_RECEIPT: usize = 100;
+
+/// Maximum gas a single function call can attach (300 TGas).
+pub const MAX_GAS: Gas = 300_000_000_000_000;
+
+/// Maximum size in bytes of a contract's code, the protocol's `max_contract_size` (4 MiB).
+pub const MAX_CONTRACT_SIZE: usize = 4_194_304;
+
+/// One teragas (10^12 gas), the unit gas amounts are usually given in.
+pub const TGAS: Gas = 1_000_000_000_000;
+
+/// Deposit attached to NEP-141 `ft_transfer` calls, which require exactly one yoctoNEAR.
+const ONE_YOCTO: Balance = 1;
+
+/// The version of the `Transaction` a `TransactionBuilder` produces.
+///
+/// `V1` transactions carry a priority fee and are only accepted by nodes whose protocol version
+/// supports them, so pin `V0` when targeting older networks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionVersion {
+    #[default]
+    V0,
+    V1,
+}
 
 // TransactionBuilder struct
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransactionBuilder {
-    transaction: Transaction,
+    pub(crate) transaction: Transaction,
 }
 
 impl TransactionBuilder {
@@ -37,8 +84,28 @@ impl TransactionBuilder {
         nonce: Nonce,
         block_hash: CryptoHash,
     ) -> Self {
-        Self {
-            transaction: Transaction::V0(TransactionV0 {
+        Self::new_with_version(
+            signer_id,
+            public_key,
+            receiver_id,
+            nonce,
+            block_hash,
+            TransactionVersion::V0,
+        )
+    }
+
+    /// Initialize a new TransactionBuilder producing a transaction of the given `version`, e.g. to test
+    /// against a localnet running an older protocol version. `V1` transactions have no priority fee.
+    pub fn new_with_version(
+        signer_id: AccountId,
+        public_key: PublicKey,
+        receiver_id: AccountId,
+        nonce: Nonce,
+        block_hash: CryptoHash,
+        version: TransactionVersion,
+    ) -> Self {
+        let transaction = match version {
+            TransactionVersion::V0 => Transaction::V0(TransactionV0 {
                 signer_id,
                 public_key,
                 receiver_id,
@@ -46,114 +113,1602 @@ impl TransactionBuilder {
                 block_hash,
                 actions: Vec::new(), // Initialize the actions vector here
             }),
+            TransactionVersion::V1 => Transaction::V1(TransactionV1 {
+                signer_id,
+                public_key,
+                receiver_id,
+                nonce,
+                block_hash,
+                actions: Vec::new(),
+                priority_fee: 0,
+            }),
+        };
+        Self { transaction }
+    }
+
+    /// Returns the version of the transaction being built.
+    pub fn version(&self) -> TransactionVersion {
+        match self.transaction {
+            Transaction::V0(_) => TransactionVersion::V0,
+            Transaction::V1(_) => TransactionVersion::V1,
         }
     }
 
+    /// Replaces the transaction's block hash with `block_hash`, e.g. of a block fetched right before
+    /// signing, see `near_accounts::accounts::set_recent_block_hash`.
+    pub fn set_block_hash(&mut self, block_hash: CryptoHash) -> &mut Self {
+        match &mut self.transaction {
+            Transaction::V0(tx) => tx.block_hash = block_hash,
+            Transaction::V1(tx) => tx.block_hash = block_hash,
+        }
+        self
+    }
+
+    /// Initialize a new TransactionBuilder without a public key, for when the signer is only chosen at
+    /// sign time. `sign_transaction` fills in the signer's public key.
+    pub fn new_unsigned(
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> Self {
+        Self::new(
+            signer_id,
+            PublicKey::empty(KeyType::ED25519),
+            receiver_id,
+            nonce,
+            block_hash,
+        )
+    }
+
+    /// Replaces the nonce with a random one, so tests that never reach the network don't depend on
+    /// hardcoded nonces.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn with_random_nonce(mut self, rng: &mut impl Rng) -> Self {
+        let nonce = rng.r#gen::<Nonce>();
+        match &mut self.transaction {
+            Transaction::V0(tx) => tx.nonce = nonce,
+            Transaction::V1(tx) => tx.nonce = nonce,
+        }
+        self
+    }
+
+    /// Replaces the block hash with `CryptoHash::default()`, for tests that don't care about it.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn with_zero_block_hash(mut self) -> Self {
+        match &mut self.transaction {
+            Transaction::V0(tx) => tx.block_hash = CryptoHash::default(),
+            Transaction::V1(tx) => tx.block_hash = CryptoHash::default(),
+        }
+        self
+    }
+
+    /// Returns a copy of this builder with the receiver replaced by `new_receiver`, e.g. to send the
+    /// same actions to many accounts.
+    #[inline]
+    pub fn clone_with_new_receiver(&self, new_receiver: AccountId) -> TransactionBuilder {
+        let mut transaction = self.transaction.clone();
+        match &mut transaction {
+            Transaction::V0(tx) => tx.receiver_id = new_receiver,
+            Transaction::V1(tx) => tx.receiver_id = new_receiver,
+        }
+        TransactionBuilder { transaction }
+    }
+
+    /// Returns a copy of this builder with the nonce replaced by `nonce`.
+    #[inline]
+    pub fn clone_with_new_nonce(&self, nonce: Nonce) -> TransactionBuilder {
+        let mut transaction = self.transaction.clone();
+        match &mut transaction {
+            Transaction::V0(tx) => tx.nonce = nonce,
+            Transaction::V1(tx) => tx.nonce = nonce,
+        }
+        TransactionBuilder { transaction }
+    }
+
+    /// Replaces the nonce with the next nonce of `counter`, so builders sharing a `SharedNonce` get
+    /// strictly increasing nonces without querying the access key for each transaction.
+    ///
+    /// Pass the errors of sent transactions to `near_accounts::nonces::handle_nonce_error`, so the
+    /// counter resyncs with the access key after a nonce was rejected.
+    pub fn with_shared_nonce(mut self, counter: &SharedNonce) -> Self {
+        let nonce = counter.next_nonce();
+        match &mut self.transaction {
+            Transaction::V0(tx) => tx.nonce = nonce,
+            Transaction::V1(tx) => tx.nonce = nonce,
+        }
+        self
+    }
+
     /// Sign a transaction with your custom Signer.
+    ///
+    /// The transaction's public key is replaced by the signer's public key before signing.
     pub fn sign_transaction(&self, signer: &InMemorySigner) -> SignedTransaction {
-        let signature = signer.sign(self.transaction.get_hash_and_size().0.as_ref());
-        SignedTransaction::new(signature, self.transaction.clone())
+        let transaction = self.transaction_with_public_key(signer.public_key());
+        let signature = signer.sign(transaction.get_hash_and_size().0.as_ref());
+        SignedTransaction::new(signature, transaction)
+    }
+
+    /// Returns the bytes an air-gapped signer has to sign for this transaction, the `sha256` hash of its
+    /// borsh serialization (`sha256(borsh(transaction))`), which is also the transaction hash.
+    ///
+    /// The hash covers the transaction's public key, so the builder must already hold the key of the
+    /// offline signer. Pass the returned signature to `assemble_offline_signature`.
+    pub fn prepare_for_offline_signing(&self) -> [u8; 32] {
+        self.transaction.get_hash_and_size().0.0
+    }
+
+    /// Builds the signed transaction from the ed25519 `signature_bytes` produced offline over the hash
+    /// returned by `prepare_for_offline_signing`.
+    ///
+    /// The transaction's public key is replaced by `public_key`, so the signature only verifies if it is
+    /// the key the hash was prepared with. The signature is not verified here.
+    pub fn assemble_offline_signature(
+        self,
+        public_key: PublicKey,
+        signature_bytes: [u8; 64],
+    ) -> SignedTransaction {
+        let transaction = self.transaction_with_public_key(public_key);
+        let signature = Signature::ED25519(ed25519_dalek::Signature::from_bytes(&signature_bytes));
+        SignedTransaction::new(signature, transaction)
+    }
+
+    /// Sign a transaction with a callback, e.g. of an HSM that signs raw bytes.
+    ///
+    /// `sign_fn` receives the transaction hash, see `prepare_for_offline_signing`. Unlike the other
+    /// signing methods, the transaction's public key is kept, so it must be the key `sign_fn` signs with.
+    pub fn sign_with<F>(&self, sign_fn: F) -> SignedTransaction
+    where
+        F: FnOnce(&[u8]) -> Signature,
+    {
+        let transaction = self.transaction.clone();
+        let signature = sign_fn(transaction.get_hash_and_size().0.as_ref());
+        SignedTransaction::new(signature, transaction)
+    }
+
+    /// Signs every transaction of `builders` with `signer`, e.g. the result of `split_into_transactions`,
+    /// in the order of `builders`. Wrap the result in a `near_providers::SignedTransactionBatch` to
+    /// broadcast the transactions concurrently.
+    pub fn sign_all(
+        builders: &[TransactionBuilder],
+        signer: &InMemorySigner,
+    ) -> Vec<SignedTransaction> {
+        builders
+            .iter()
+            .map(|builder| builder.sign_transaction(signer))
+            .collect()
+    }
+
+    /// Sign a transaction with an `AsyncSigner`, such as a hardware wallet.
+    ///
+    /// The transaction's public key is replaced by the signer's public key before signing.
+    pub async fn sign_transaction_async<S: AsyncSigner>(
+        &self,
+        signer: &S,
+    ) -> Result<SignedTransaction, S::Error> {
+        let transaction = self.transaction_with_public_key(signer.public_key());
+        let signature = signer.sign_transaction(&transaction).await?;
+        Ok(SignedTransaction::new(signature, transaction))
+    }
+
+    /// Sign a transaction with the key derived from a BIP-39 seed phrase.
+    ///
+    /// The key is derived along `derivation_path`, or NEAR's default `m/44'/397'/0'` when `None`.
+    /// The transaction's public key is replaced by the public key of the derived key before signing.
+    #[cfg(feature = "seed-phrase")]
+    pub fn sign_with_seed_phrase(
+        &self,
+        phrase: &str,
+        derivation_path: Option<&str>,
+    ) -> Result<SignedTransaction, SeedPhraseError> {
+        let secret_key = from_mnemonic(phrase, derivation_path.unwrap_or(DEFAULT_DERIVATION_PATH))?;
+        let transaction = self.transaction_with_public_key(secret_key.public_key());
+        let signature = secret_key.sign(transaction.get_hash_and_size().0.as_ref());
+        Ok(SignedTransaction::new(signature, transaction))
+    }
+
+    /// Sign a transaction with a private key in NEAR's `ed25519:<base58>` string format.
+    ///
+    /// The transaction's public key is replaced by the key's public key before signing.
+    pub fn sign_with_private_key_str(
+        &self,
+        private_key: &str,
+    ) -> Result<SignedTransaction, KeyImportError> {
+        let key_pair = KeyPair::from_near_key_string(private_key)?;
+        let transaction = self.transaction_with_public_key(key_pair.public_key);
+        let signature = key_pair
+            .secret_key
+            .sign(transaction.get_hash_and_size().0.as_ref());
+        Ok(SignedTransaction::new(signature, transaction))
+    }
+
+    /// Returns a copy of the transaction with its public key replaced by `public_key`.
+    fn transaction_with_public_key(&self, public_key: PublicKey) -> Transaction {
+        let mut transaction = self.transaction.clone();
+        match &mut transaction {
+            Transaction::V0(tx) => tx.public_key = public_key,
+            Transaction::V1(tx) => tx.public_key = public_key,
+        }
+        transaction
+    }
+
+    /// Returns the actions of the transaction, whatever its version.
+    fn actions_mut(&mut self) -> &mut Vec<Action> {
+        match &mut self.transaction {
+            Transaction::V0(tx) => &mut tx.actions,
+            Transaction::V1(tx) => &mut tx.actions,
+        }
+    }
+
+    /// Adds an arbitrary action to the transaction.
+    pub fn add_action(&mut self, action: Action) -> &mut Self {
+        self.actions_mut().push(action);
+        self
+    }
+
+    /// Keeps only the actions for which `predicate` returns true, preserving their order.
+    pub fn retain_actions(&mut self, predicate: impl Fn(&Action) -> bool) -> &mut Self {
+        self.actions_mut().retain(|action| predicate(action));
+        self
     }
 
     /// Methods to add CreateAccount action directly to the Transaction's actions vector
     pub fn create_account(&mut self) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions
-                .push(Action::CreateAccount(CreateAccountAction {}));
-        } else {
-            panic!("Transaction is not a V0");
-        }
+        self.actions_mut()
+            .push(Action::CreateAccount(CreateAccountAction {}));
         self
     }
 
+    /// Creates the sub account `{label}.{signer_id}`, funds it with `initial_balance` and adds
+    /// `public_key` as its full access key, replacing the receiver with the new account.
+    ///
+    /// Nothing is changed if `label` contains a dot, since only direct sub accounts can be created, or
+    /// if the resulting account ID is invalid.
+    pub fn create_sub_account(
+        &mut self,
+        label: &str,
+        public_key: PublicKey,
+        initial_balance: Balance,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let invalid = || TransactionBuilderError::InvalidSubAccountLabel(label.to_string());
+        if label.contains('.') {
+            return Err(invalid());
+        }
+        let sub_account_id: AccountId = format!("{}.{}", label, self.transaction.signer_id())
+            .parse()
+            .map_err(|_| invalid())?;
+        match &mut self.transaction {
+            Transaction::V0(tx) => tx.receiver_id = sub_account_id,
+            Transaction::V1(tx) => tx.receiver_id = sub_account_id,
+        }
+        Ok(self
+            .create_account()
+            .transfer(initial_balance)
+            .add_key(public_key, AccessKey::full_access()))
+    }
+
     /// Method to add a DeployContract action
     pub fn deploy_contract(&mut self, code: &[u8]) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions
-                .push(Action::DeployContract(DeployContractAction {
-                    code: code.to_vec(),
-                }));
-        } else {
-            panic!("Transaction is not a V0");
-        }
+        self.actions_mut()
+            .push(Action::DeployContract(DeployContractAction {
+                code: code.to_vec(),
+            }));
         self
     }
 
+    /// Adds a DeployContract action after checking that `code` is neither empty nor larger than
+    /// `MAX_CONTRACT_SIZE`, e.g. to catch a build that produced no WASM before it fails on chain.
+    pub fn try_deploy_contract(
+        &mut self,
+        code: &[u8],
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        check_contract_code(code)?;
+        Ok(self.deploy_contract(code))
+    }
+
+    /// Creates the receiver account and deploys `code` to it, as factories and launchpads do. The actions
+    /// are added in the order `CreateAccount`, `AddKey` (`public_key` with full access), `Transfer`
+    /// (`initial_balance`) and `DeployContract`.
+    ///
+    /// Only sub accounts of the signer can be created this way. Nothing is changed if the transaction
+    /// already has actions, since `CreateAccount` must come first, or if `code` is rejected by
+    /// `try_deploy_contract`.
+    pub fn create_account_and_deploy(
+        &mut self,
+        public_key: PublicKey,
+        initial_balance: Balance,
+        code: &[u8],
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        check_contract_code(code)?;
+        let index = self.transaction.actions().len();
+        if index != 0 {
+            return Err(TransactionBuilderError::CreateAccountNotFirst { index });
+        }
+        Ok(self
+            .create_account()
+            .add_key(public_key, AccessKey::full_access())
+            .transfer(initial_balance)
+            .deploy_contract(code))
+    }
+
+    /// Adds a function call action after checking that `method_name` is non-empty and only contains
+    /// printable ASCII characters, and that `gas` does not exceed `MAX_GAS`. Other method names can
+    /// never match a contract method, and the protocol rejects calls with more gas.
     pub fn function_call(
         &mut self,
         method_name: String,
         args: Vec<u8>,
         gas: Gas,
         deposit: Balance,
-    ) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions
-                .push(Action::FunctionCall(Box::new(FunctionCallAction {
-                    method_name,
-                    args,
-                    gas,
-                    deposit,
-                })));
-        } else {
-            panic!("Transaction is not a V0");
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        if !is_valid_method_name(&method_name) {
+            return Err(TransactionBuilderError::InvalidMethodName(method_name));
         }
-        self
+        if gas > MAX_GAS {
+            return Err(TransactionBuilderError::GasExceedsMaximum { gas, max: MAX_GAS });
+        }
+        self.actions_mut()
+            .push(Action::FunctionCall(Box::new(FunctionCallAction {
+                method_name,
+                args,
+                gas,
+                deposit,
+            })));
+        Ok(self)
     }
 
+    /// Adds a function call action attaching `total_gas` minus `reserve_tgas` teragas.
+    ///
+    /// Transactions only carry the absolute gas of each call. Contracts spawning cross-contract calls
+    /// split the gas left after their own execution between those calls and the callback, so attaching
+    /// less than the budget leaves the reserve for whatever runs after this call, e.g. a callback of a
+    /// yield/resume flow. Nothing is added if the reserve exceeds `total_gas` or the method name is
+    /// invalid, see `function_call`.
+    pub fn function_call_with_gas_reserve(
+        &mut self,
+        method_name: String,
+        args: Vec<u8>,
+        deposit: Balance,
+        total_gas: Gas,
+        reserve_tgas: u64,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let reserve = reserve_tgas.saturating_mul(TGAS);
+        let gas = total_gas.checked_sub(reserve).ok_or(
+            TransactionBuilderError::GasReserveExceedsTotal {
+                total: total_gas,
+                reserve,
+            },
+        )?;
+        self.function_call(method_name, args, gas, deposit)
+    }
+
+    /// Adds a function call action whose args are the content of the JSON file at `path`.
+    ///
+    /// The file is checked to be valid JSON, and its bytes are used as args unchanged. The method name
+    /// is validated like in `function_call`.
+    pub fn function_call_from_file(
+        &mut self,
+        method_name: String,
+        path: impl AsRef<Path>,
+        gas: Gas,
+        deposit: Balance,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let path = path.as_ref();
+        let args = std::fs::read(path).map_err(|err| {
+            TransactionBuilderError::ArgsFile(match err.kind() {
+                ErrorKind::NotFound => ArgsFileError::NotFound(path.to_path_buf()),
+                _ => ArgsFileError::Io {
+                    path: path.to_path_buf(),
+                    reason: err.to_string(),
+                },
+            })
+        })?;
+        serde_json::from_slice::<serde_json::Value>(&args).map_err(|err| {
+            TransactionBuilderError::ArgsFile(ArgsFileError::InvalidJson {
+                path: path.to_path_buf(),
+                reason: err.to_string(),
+            })
+        })?;
+        self.function_call(method_name, args, gas, deposit)
+    }
+
+    /// Adds a function call action with a deposit given in NEAR, such as `"0.1"`.
+    ///
+    /// The deposit is parsed with `NearToken::from_display_string` and attached in yoctoNEAR. Nothing
+    /// is added if it can't be parsed or the method name is invalid, see `function_call`.
+    pub fn function_call_near_deposit(
+        &mut self,
+        method_name: String,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit_near: &str,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let deposit = NearToken::from_display_string(deposit_near, NEAR_DECIMALS)
+            .map_err(TransactionBuilderError::InvalidDeposit)?;
+        self.function_call(method_name, args, gas, deposit.as_yoctonear())
+    }
+
+    /// Adds a regular, refundable `Transfer` action.
+    ///
+    /// `near_primitives` 0.31 has no `NonrefundableStorageTransfer` action: the NEP-491 prototype was
+    /// removed from nearcore before stabilization, so sponsored storage can't be made non-withdrawable
+    /// here. Any deposit sent with this method can be spent by the receiver.
     pub fn transfer(&mut self, deposit: Balance) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions
-                .push(Action::Transfer(TransferAction { deposit }));
-        } else {
-            panic!("Transaction is not a V0");
-        }
+        self.actions_mut()
+            .push(Action::Transfer(TransferAction { deposit }));
         self
     }
 
     pub fn stake(&mut self, stake: Balance, public_key: PublicKey) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions
-                .push(Action::Stake(Box::new(StakeAction { stake, public_key })));
-        } else {
-            panic!("Transaction is not a V0");
-        }
+        self.actions_mut()
+            .push(Action::Stake(Box::new(StakeAction { stake, public_key })));
         self
     }
     pub fn add_key(&mut self, public_key: PublicKey, access_key: AccessKey) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions.push(Action::AddKey(Box::new(AddKeyAction {
+        self.actions_mut()
+            .push(Action::AddKey(Box::new(AddKeyAction {
                 public_key,
                 access_key,
             })));
-        } else {
-            panic!("Transaction is not a V0");
-        }
         self
     }
 
-    pub fn delete_key(&mut self, public_key: PublicKey) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions
-                .push(Action::DeleteKey(Box::new(DeleteKeyAction { public_key })));
-        } else {
-            panic!("Transaction is not a V0");
+    /// Adds a function call access key that may only call `method_names` on `receiver_id`.
+    ///
+    /// An empty `method_names` allows calling any method of the receiver. `allowance` caps the amount
+    /// of NEAR the key can spend on gas; `None` means unlimited.
+    pub fn add_function_call_key(
+        &mut self,
+        public_key: PublicKey,
+        receiver_id: AccountId,
+        method_names: Vec<String>,
+        allowance: Option<Balance>,
+    ) -> &mut Self {
+        let access_key = AccessKey {
+            nonce: 0,
+            permission: AccessKeyPermission::FunctionCall(FunctionCallPermission {
+                allowance,
+                receiver_id: receiver_id.to_string(),
+                method_names,
+            }),
+        };
+        self.add_key(public_key, access_key)
+    }
+
+    /// Adds a function call access key like `add_function_call_key`, after normalizing `method_names`
+    /// with `MethodNames::new`: duplicates are removed and empty or invalid names are rejected.
+    pub fn try_add_function_call_key(
+        &mut self,
+        public_key: PublicKey,
+        receiver_id: AccountId,
+        method_names: Vec<String>,
+        allowance: Option<Balance>,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let method_names = MethodNames::new(method_names)?;
+        Ok(self.add_function_call_key(public_key, receiver_id, method_names.into_vec(), allowance))
+    }
+
+    /// Adds one function call access key per entry of `keys`, each given as public key, receiver,
+    /// method names and allowance as in `add_function_call_key`.
+    ///
+    /// A function call key can only call a single contract, so this is how access to several contracts
+    /// is granted at once. No key is added if the transaction would exceed `MAX_ACTIONS_PER_RECEIPT`.
+    pub fn add_function_call_keys(
+        &mut self,
+        keys: Vec<(PublicKey, AccountId, Vec<String>, Option<Balance>)>,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let count = self.transaction.actions().len() + keys.len();
+        if count > MAX_ACTIONS_PER_RECEIPT {
+            return Err(TransactionBuilderError::TooManyActions {
+                count,
+                max: MAX_ACTIONS_PER_RECEIPT,
+            });
+        }
+        for (public_key, receiver_id, method_names, allowance) in keys {
+            self.add_function_call_key(public_key, receiver_id, method_names, allowance);
         }
+        Ok(self)
+    }
+
+    /// Adds a function call access key that may only call the single `method` on `receiver_id`,
+    /// e.g. a dApp login key.
+    pub fn add_single_method_key(
+        &mut self,
+        public_key: PublicKey,
+        receiver_id: AccountId,
+        method: impl Into<String>,
+        allowance: Option<Balance>,
+    ) -> &mut Self {
+        self.add_function_call_key(public_key, receiver_id, vec![method.into()], allowance)
+    }
+
+    pub fn delete_key(&mut self, public_key: PublicKey) -> &mut Self {
+        self.actions_mut()
+            .push(Action::DeleteKey(Box::new(DeleteKeyAction { public_key })));
         self
     }
 
+    /// Adds one `DeleteKey` action per entry of `public_keys`, in order, e.g. to clean up keys after a
+    /// compromise.
+    ///
+    /// No key is deleted if the transaction would exceed `MAX_ACTIONS_PER_RECEIPT` or if `public_keys`
+    /// contains the key the transaction is signed with, since losing it mid-cleanup can lock the
+    /// account. Use `delete_key` to delete the signing key deliberately.
+    pub fn delete_keys(
+        &mut self,
+        public_keys: Vec<PublicKey>,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let count = self.transaction.actions().len() + public_keys.len();
+        if count > MAX_ACTIONS_PER_RECEIPT {
+            return Err(TransactionBuilderError::TooManyActions {
+                count,
+                max: MAX_ACTIONS_PER_RECEIPT,
+            });
+        }
+        if let Some(public_key) = public_keys
+            .iter()
+            .find(|public_key| *public_key == self.transaction.public_key())
+        {
+            return Err(TransactionBuilderError::DeletesSigningKey(
+                public_key.clone(),
+            ));
+        }
+        for public_key in public_keys {
+            self.delete_key(public_key);
+        }
+        Ok(self)
+    }
+
+    /// Replaces the full access key `old_key` with `new_key` in a single transaction.
+    ///
+    /// The new key is added before the old one is deleted, so a failure of either action leaves the
+    /// account with a working full access key.
+    pub fn rotate_full_access_key(&mut self, new_key: PublicKey, old_key: PublicKey) -> &mut Self {
+        self.add_key(new_key, AccessKey::full_access())
+            .delete_key(old_key)
+    }
+
     pub fn delete_account(&mut self, beneficiary_id: AccountId) -> &mut Self {
-        if let Transaction::V0(tx) = &mut self.transaction {
-            tx.actions.push(Action::DeleteAccount(DeleteAccountAction {
+        self.actions_mut()
+            .push(Action::DeleteAccount(DeleteAccountAction {
                 beneficiary_id,
             }));
-        } else {
-            panic!("Transaction is not a V0");
-        }
         self
     }
 
+    /// Testing utility, not meant for production use: adds a call to the promise batch helper contract,
+    /// which in turn creates a receipt for `receiver_id` executing `actions`.
+    ///
+    /// This allows test harnesses to mimic the receipt chains produced by cross-contract calls. The helper
+    /// contract receives the actions as JSON `{ "receiver_id", "actions" }` in its `promise_batch` method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction's receiver is not one of the `PROMISE_BATCH_TEST_CONTRACTS`.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn attach_promise_batch(
+        &mut self,
+        receiver_id: AccountId,
+        actions: Vec<Action>,
+    ) -> &mut Self {
+        let helper_id = self.transaction.receiver_id();
+        assert!(
+            PROMISE_BATCH_TEST_CONTRACTS.contains(&helper_id.as_str()),
+            "{} is not a known promise batch test contract",
+            helper_id
+        );
+
+        let args = json!({
+            "receiver_id": receiver_id,
+            "actions": actions,
+        });
+        self.function_call(
+            "promise_batch".to_string(),
+            args.to_string().into_bytes(),
+            MAX_GAS,
+            0,
+        )
+        .expect("promise_batch is a valid method name and MAX_GAS is allowed")
+    }
+
+    /// Registers `receiver_id` on the token contract and transfers `amount` of the token to it in one transaction.
+    ///
+    /// Adds a NEP-145 `storage_deposit` call with `registration_only` set, attaching `storage_deposit`,
+    /// followed by a NEP-141 `ft_transfer` call attaching one yoctoNEAR. Both calls use `gas` and target
+    /// the transaction's receiver, which must be the token contract.
+    pub fn storage_deposit_and_ft_transfer(
+        &mut self,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<String>,
+        storage_deposit: Balance,
+        gas: Gas,
+    ) -> Result<&mut Self, TransactionBuilderError> {
+        let storage_args = json!({
+            "account_id": receiver_id,
+            "registration_only": true,
+        });
+        let transfer_args = json!({
+            "receiver_id": receiver_id,
+            "amount": amount.to_string(),
+            "memo": memo,
+        });
+
+        self.function_call(
+            "storage_deposit".to_string(),
+            storage_args.to_string().into_bytes(),
+            gas,
+            storage_deposit,
+        )?
+        .function_call(
+            "ft_transfer".to_string(),
+            transfer_args.to_string().into_bytes(),
+            gas,
+            ONE_YOCTO,
+        )
+    }
+
+    /// Checks that the transaction contains exactly `expected` actions.
+    ///
+    /// The check only runs in debug builds; in release builds this always succeeds.
+    #[inline]
+    pub fn assert_action_count_eq(
+        &mut self,
+        expected: usize,
+    ) -> Result<&mut Self, WrongActionCount> {
+        self.assert_action_count_range(expected, expected)
+    }
+
+    /// Checks that the transaction contains between `min` and `max` actions, inclusive.
+    ///
+    /// The check only runs in debug builds; in release builds this always succeeds.
+    #[inline]
+    pub fn assert_action_count_range(
+        &mut self,
+        min: usize,
+        max: usize,
+    ) -> Result<&mut Self, WrongActionCount> {
+        if cfg!(debug_assertions) {
+            let actual = self.transaction.actions().len();
+            if actual < min || actual > max {
+                return Err(WrongActionCount { actual, min, max });
+            }
+        }
+        Ok(self)
+    }
+
+    /// Computes a `CryptoHash` of each action's borsh encoding, in the order the actions were added.
+    ///
+    /// Useful as a stable, per-action identifier for audit logging without submitting to the chain.
+    pub fn action_hashes(&self) -> Vec<CryptoHash> {
+        self.transaction
+            .actions()
+            .iter()
+            .map(CryptoHash::hash_borsh)
+            .collect()
+    }
+
+    /// Returns true if no action attaches a deposit, including the actions inside delegate actions.
+    pub fn is_deposit_free(&self) -> bool {
+        self.transaction
+            .actions()
+            .iter()
+            .all(action_is_deposit_free)
+    }
+
+    /// Returns true if every action is a function call.
+    pub fn has_only_function_calls(&self) -> bool {
+        self.transaction
+            .actions()
+            .iter()
+            .all(|action| matches!(action, Action::FunctionCall(_)))
+    }
+
+    /// Computes a hash of the borsh-serialized actions only, ignoring signer, receiver, nonce and block
+    /// hash, e.g. to deduplicate identical action sets in a relayer queue.
+    pub fn actions_fingerprint(&self) -> CryptoHash {
+        CryptoHash::hash_borsh(self.transaction.actions())
+    }
+
+    /// Computes the hash of the full transaction, the same way the NEAR protocol derives the transaction id.
+    pub fn transaction_id(&self) -> CryptoHash {
+        self.transaction.get_hash_and_size().0
+    }
+
+    /// Returns the size in bytes of the borsh-serialized transaction.
+    pub fn size(&self) -> u64 {
+        self.transaction.get_hash_and_size().1
+    }
+
+    /// Predicts whether adding `additional_bytes` more to the transaction, e.g. another action of that
+    /// serialized size, would push it past `MAX_TRANSACTION_SIZE`.
+    pub fn would_exceed_size_limit(&self, additional_bytes: usize) -> bool {
+        self.size().saturating_add(additional_bytes as u64) > MAX_TRANSACTION_SIZE
+    }
+
+    /// Checks the ordering rules the protocol imposes on the actions of a transaction:
+    ///
+    /// - `CreateAccount` must be the first action, since every other action requires the receiver to
+    ///   exist and creating an existing account fails.
+    /// - `DeleteAccount` must be the last action, the runtime rejects transactions with actions after it.
+    ///
+    /// # Returns
+    ///
+    /// An error with the index of the first action violating a rule.
+    pub fn validate_ordering(&self) -> Result<(), TransactionBuilderError> {
+        let actions = self.transaction.actions();
+        for (index, action) in actions.iter().enumerate() {
+            match action {
+                Action::CreateAccount(_) if index != 0 => {
+                    return Err(TransactionBuilderError::CreateAccountNotFirst { index });
+                }
+                Action::DeleteAccount(_) if index != actions.len() - 1 => {
+                    return Err(TransactionBuilderError::DeleteAccountNotLast { index });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits the actions into as few transactions as possible, each within `MAX_ACTIONS_PER_RECEIPT`
+    /// and `MAX_TRANSACTION_SIZE`, preserving their order.
+    ///
+    /// The transactions keep the signer, public key, receiver and version, reference `block_hash` and
+    /// use sequential nonces starting at `base_nonce`. An action too large for any transaction is still
+    /// placed in one of its own, which the network will reject, and a warning is logged.
+    pub fn split_into_transactions(
+        self,
+        base_nonce: Nonce,
+        block_hash: CryptoHash,
+    ) -> Vec<TransactionBuilder> {
+        let version = self.version();
+        let mut nonce = base_nonce;
+        let empty_transaction = |nonce| {
+            TransactionBuilder::new_with_version(
+                self.transaction.signer_id().clone(),
+                self.transaction.public_key().clone(),
+                self.transaction.receiver_id().clone(),
+                nonce,
+                block_hash,
+                version,
+            )
+        };
+
+        let mut transactions = Vec::new();
+        let mut current = empty_transaction(nonce);
+        for action in self.transaction.actions() {
+            let action_size = borsh::object_length(action).expect("Borsh serialization failed");
+            if current.would_exceed_size_limit(action_size)
+                && current.transaction.actions().is_empty()
+            {
+                log::warn!(
+                    "action of {} bytes exceeds the transaction size limit of {} bytes on its own",
+                    action_size,
+                    MAX_TRANSACTION_SIZE
+                );
+            }
+            if !current.transaction.actions().is_empty()
+                && (current.transaction.actions().len() >= MAX_ACTIONS_PER_RECEIPT
+                    || current.would_exceed_size_limit(action_size))
+            {
+                nonce += 1;
+                transactions.push(std::mem::replace(&mut current, empty_transaction(nonce)));
+            }
+            current.add_action(action.clone());
+        }
+        if !current.transaction.actions().is_empty() {
+            transactions.push(current);
+        }
+        transactions
+    }
+
     // Finalize and return the built Transaction
     pub fn build(self) -> Transaction {
         self.transaction
     }
 }
+
+/// Returns true if `action` does not attach a deposit.
+fn action_is_deposit_free(action: &Action) -> bool {
+    match action {
+        Action::Transfer(transfer) => transfer.deposit == 0,
+        Action::FunctionCall(function_call) => function_call.deposit == 0,
+        Action::Delegate(signed_delegate) => signed_delegate
+            .delegate_action
+            .get_actions()
+            .iter()
+            .all(action_is_deposit_free),
+        _ => true,
+    }
+}
+
+/// Checks that `code` is neither empty nor larger than `MAX_CONTRACT_SIZE`.
+fn check_contract_code(code: &[u8]) -> Result<(), TransactionBuilderError> {
+    if code.is_empty() {
+        return Err(TransactionBuilderError::EmptyContractCode);
+    }
+    if code.len() > MAX_CONTRACT_SIZE {
+        return Err(TransactionBuilderError::ContractCodeTooLarge {
+            size: code.len(),
+            max: MAX_CONTRACT_SIZE,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NonceManager;
+    use near_crypto::SecretKey;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use serde_json::Value;
+
+    fn builder() -> TransactionBuilder {
+        TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "token.near".parse().unwrap(),
+            1,
+            CryptoHash::default(),
+        )
+    }
+
+    #[test]
+    fn test_with_random_nonce_and_zero_block_hash() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let transaction = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            PublicKey::empty(KeyType::ED25519),
+            "bob.near".parse().unwrap(),
+            0,
+            CryptoHash::hash_bytes(b"block"),
+        )
+        .with_random_nonce(&mut rng)
+        .with_zero_block_hash()
+        .build();
+
+        let expected_nonce = StdRng::seed_from_u64(42).r#gen::<Nonce>();
+        assert_eq!(transaction.nonce(), expected_nonce);
+        assert_eq!(transaction.block_hash(), &CryptoHash::default());
+    }
+
+    #[test]
+    fn test_new_with_version() {
+        for version in [TransactionVersion::V0, TransactionVersion::V1] {
+            let mut builder = TransactionBuilder::new_with_version(
+                "alice.near".parse().unwrap(),
+                PublicKey::empty(KeyType::ED25519),
+                "bob.near".parse().unwrap(),
+                1,
+                CryptoHash::default(),
+                version,
+            );
+            builder.transfer(1);
+            assert_eq!(builder.version(), version);
+
+            let transaction = builder.build();
+            match version {
+                TransactionVersion::V0 => assert!(matches!(transaction, Transaction::V0(_))),
+                TransactionVersion::V1 => assert!(matches!(transaction, Transaction::V1(_))),
+            }
+            assert_eq!(transaction.actions().len(), 1);
+        }
+        assert_eq!(builder().version(), TransactionVersion::V0);
+    }
+
+    #[test]
+    fn test_sign_fills_in_public_key() {
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "alice.near");
+        let signer = InMemorySigner {
+            account_id: "alice.near".parse().unwrap(),
+            public_key: secret_key.public_key(),
+            secret_key,
+        };
+        let builder = TransactionBuilder::new_unsigned(
+            "alice.near".parse().unwrap(),
+            "bob.near".parse().unwrap(),
+            1,
+            CryptoHash::default(),
+        );
+
+        let signed_transaction = builder.sign_transaction(&signer);
+        assert_eq!(
+            signed_transaction.transaction.public_key(),
+            &signer.public_key()
+        );
+        assert!(
+            signed_transaction
+                .signature
+                .verify(signed_transaction.get_hash().as_ref(), &signer.public_key())
+        );
+    }
+
+    #[test]
+    fn test_sign_with_private_key_str() {
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "alice.near");
+        let signed_transaction = builder()
+            .sign_with_private_key_str(&secret_key.to_string())
+            .unwrap();
+        assert_eq!(
+            signed_transaction.transaction.public_key(),
+            &secret_key.public_key()
+        );
+        assert!(signed_transaction.signature.verify(
+            signed_transaction.get_hash().as_ref(),
+            &secret_key.public_key()
+        ));
+        assert!(matches!(
+            builder().sign_with_private_key_str("ed25519:not-base58"),
+            Err(KeyImportError::InvalidPrivateKey { .. })
+        ));
+    }
+
+    #[test]
+    fn test_function_call_from_file() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let path = fixtures.join("ft_transfer_args.json");
+        let mut builder = builder();
+        builder
+            .function_call_from_file("ft_transfer".to_string(), &path, 10, 1)
+            .unwrap();
+
+        let Action::FunctionCall(call) = &builder.transaction.actions()[0] else {
+            panic!("Expected a function call");
+        };
+        assert_eq!(call.args, std::fs::read(&path).unwrap());
+        let args: Value = serde_json::from_slice(&call.args).unwrap();
+        assert_eq!(args["receiver_id"], "bob.near");
+
+        let missing = fixtures.join("missing.json");
+        assert_eq!(
+            builder()
+                .function_call_from_file("ft_transfer".to_string(), &missing, 10, 1)
+                .unwrap_err(),
+            TransactionBuilderError::ArgsFile(ArgsFileError::NotFound(missing))
+        );
+
+        let not_json = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert!(matches!(
+            builder().function_call_from_file("ft_transfer".to_string(), not_json, 10, 1),
+            Err(TransactionBuilderError::ArgsFile(
+                ArgsFileError::InvalidJson { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_function_call_near_deposit() {
+        let mut valid = builder();
+        valid
+            .function_call_near_deposit("deposit".to_string(), vec![], 10, "0.1")
+            .unwrap();
+        let Action::FunctionCall(call) = &valid.transaction.actions()[0] else {
+            panic!("Expected a function call");
+        };
+        assert_eq!(call.deposit, 100_000_000_000_000_000_000_000);
+
+        let mut invalid = builder();
+        assert!(
+            invalid
+                .function_call_near_deposit("deposit".to_string(), vec![], 10, "0.1 NEAR")
+                .is_err()
+        );
+        assert!(invalid.transaction.actions().is_empty());
+    }
+
+    #[test]
+    fn test_offline_signing() {
+        use crate::test_utils::{dummy_builder, test_public_key, test_secret_key, test_signer};
+
+        let mut builder = dummy_builder();
+        builder.transfer(1);
+        let expected = builder.sign_transaction(&test_signer());
+
+        let hash = builder.prepare_for_offline_signing();
+        assert_eq!(hash, expected.get_hash().0);
+        let Signature::ED25519(signature) = test_secret_key().sign(&hash) else {
+            panic!("Expected an ed25519 signature");
+        };
+
+        let signed = builder.assemble_offline_signature(test_public_key(), signature.to_bytes());
+        assert_eq!(signed, expected);
+        assert!(
+            signed
+                .signature
+                .verify(&hash, signed.transaction.public_key())
+        );
+    }
+
+    #[test]
+    fn test_function_call_with_gas_reserve() {
+        let mut with_reserve = builder();
+        with_reserve
+            .function_call_with_gas_reserve("spawn".to_string(), vec![], 0, 300 * TGAS, 20)
+            .unwrap();
+        let Action::FunctionCall(call) = &with_reserve.transaction.actions()[0] else {
+            panic!("Expected a function call");
+        };
+        assert_eq!(call.gas, 280 * TGAS);
+
+        assert_eq!(
+            builder()
+                .function_call_with_gas_reserve("spawn".to_string(), vec![], 0, 10 * TGAS, 20)
+                .unwrap_err(),
+            TransactionBuilderError::GasReserveExceedsTotal {
+                total: 10 * TGAS,
+                reserve: 20 * TGAS,
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_deploy_contract() {
+        let mut deploying = builder();
+        deploying.try_deploy_contract(b"\0asm").unwrap();
+        assert_eq!(deploying.deploy_contract_actions()[0].code, b"\0asm");
+
+        assert_eq!(
+            builder().try_deploy_contract(&[]).unwrap_err(),
+            TransactionBuilderError::EmptyContractCode
+        );
+        assert_eq!(
+            builder()
+                .try_deploy_contract(&vec![0; MAX_CONTRACT_SIZE + 1])
+                .unwrap_err(),
+            TransactionBuilderError::ContractCodeTooLarge {
+                size: MAX_CONTRACT_SIZE + 1,
+                max: MAX_CONTRACT_SIZE,
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_sub_account() {
+        let mut creating = builder();
+        creating
+            .create_sub_account("app", PublicKey::empty(KeyType::ED25519), 1)
+            .unwrap();
+        let transaction = creating.build();
+        assert_eq!(transaction.receiver_id().as_str(), "app.alice.near");
+        assert!(matches!(
+            transaction.actions(),
+            [
+                Action::CreateAccount(_),
+                Action::Transfer(_),
+                Action::AddKey(_)
+            ]
+        ));
+
+        for label in ["app.v2", "App", ""] {
+            let mut rejecting = builder();
+            assert_eq!(
+                rejecting
+                    .create_sub_account(label, PublicKey::empty(KeyType::ED25519), 1)
+                    .unwrap_err(),
+                TransactionBuilderError::InvalidSubAccountLabel(label.to_string())
+            );
+            assert_eq!(rejecting, builder());
+        }
+    }
+
+    #[test]
+    fn test_with_shared_nonce() {
+        let counter = SharedNonce::new(NonceManager::new(10));
+        let first = builder().with_shared_nonce(&counter).build();
+        let second = builder().with_shared_nonce(&counter.clone()).build();
+        assert_eq!(first.nonce(), 11);
+        assert_eq!(second.nonce(), 12);
+    }
+
+    #[test]
+    fn test_create_account_and_deploy() {
+        let mut creating = builder();
+        creating
+            .create_account_and_deploy(PublicKey::empty(KeyType::ED25519), 1, b"\0asm")
+            .unwrap();
+        assert_eq!(creating.validate_ordering(), Ok(()));
+        assert!(matches!(
+            creating.build().actions(),
+            [
+                Action::CreateAccount(_),
+                Action::AddKey(_),
+                Action::Transfer(_),
+                Action::DeployContract(_)
+            ]
+        ));
+
+        let mut rejecting = builder();
+        rejecting.transfer(1);
+        assert_eq!(
+            rejecting
+                .create_account_and_deploy(PublicKey::empty(KeyType::ED25519), 1, b"\0asm")
+                .unwrap_err(),
+            TransactionBuilderError::CreateAccountNotFirst { index: 1 }
+        );
+        assert_eq!(
+            builder()
+                .create_account_and_deploy(PublicKey::empty(KeyType::ED25519), 1, b"")
+                .unwrap_err(),
+            TransactionBuilderError::EmptyContractCode
+        );
+    }
+
+    #[test]
+    fn test_sign_with() {
+        use crate::test_utils::{dummy_builder, test_public_key, test_secret_key, test_signer};
+
+        let mut builder = dummy_builder();
+        builder.transfer(1);
+        let signed = builder.sign_with(|hash| test_secret_key().sign(hash));
+
+        assert_eq!(signed, builder.sign_transaction(&test_signer()));
+        assert!(
+            signed
+                .signature
+                .verify(signed.get_hash().as_ref(), &test_public_key())
+        );
+    }
+
+    #[test]
+    fn test_sign_all() {
+        use crate::test_utils::{dummy_builder, test_signer};
+
+        let mut builder = dummy_builder();
+        for _ in 0..150 {
+            builder.transfer(1);
+        }
+        let builders = builder.split_into_transactions(10, CryptoHash::default());
+        let signed_transactions = TransactionBuilder::sign_all(&builders, &test_signer());
+
+        assert_eq!(signed_transactions.len(), 2);
+        for (signed, builder) in signed_transactions.iter().zip(&builders) {
+            assert_eq!(signed, &builder.sign_transaction(&test_signer()));
+        }
+    }
+
+    #[test]
+    fn test_validate_ordering() {
+        let mut valid = builder();
+        valid
+            .create_account()
+            .transfer(1)
+            .delete_account("bob.near".parse().unwrap());
+        assert_eq!(valid.validate_ordering(), Ok(()));
+
+        let mut create_after_transfer = builder();
+        create_after_transfer.transfer(1).create_account();
+        assert_eq!(
+            create_after_transfer.validate_ordering(),
+            Err(TransactionBuilderError::CreateAccountNotFirst { index: 1 })
+        );
+
+        let mut delete_before_transfer = builder();
+        delete_before_transfer
+            .delete_account("bob.near".parse().unwrap())
+            .transfer(1);
+        assert_eq!(
+            delete_before_transfer.validate_ordering(),
+            Err(TransactionBuilderError::DeleteAccountNotLast { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_split_into_transactions() {
+        let mut builder = builder();
+        for _ in 0..150 {
+            builder.transfer(1);
+        }
+        let block_hash = CryptoHash::hash_bytes(b"block");
+        let transactions = builder.split_into_transactions(10, block_hash);
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(
+            transactions[0].transaction.actions().len(),
+            MAX_ACTIONS_PER_RECEIPT
+        );
+        assert_eq!(transactions[1].transaction.actions().len(), 50);
+        for (i, transaction) in transactions.iter().enumerate() {
+            assert_eq!(transaction.transaction.nonce(), 10 + i as Nonce);
+            assert_eq!(transaction.transaction.block_hash(), &block_hash);
+            assert_eq!(transaction.transaction.receiver_id().as_str(), "token.near");
+        }
+    }
+
+    #[test]
+    fn test_split_into_transactions_by_size() {
+        let mut builder = builder();
+        let code = vec![0; 1_000_000];
+        builder
+            .deploy_contract(&code)
+            .transfer(1)
+            .deploy_contract(&code);
+        let transactions = builder.split_into_transactions(1, CryptoHash::default());
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].transaction.actions().len(), 2);
+        assert_eq!(transactions[1].transaction.actions().len(), 1);
+        assert!(
+            transactions
+                .iter()
+                .all(|tx| tx.size() <= MAX_TRANSACTION_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_storage_deposit_and_ft_transfer() {
+        let receiver_id: AccountId = "bob.near".parse().unwrap();
+        let storage_deposit = 1_250_000_000_000_000_000_000;
+        let mut builder = builder();
+        builder
+            .storage_deposit_and_ft_transfer(
+                &receiver_id,
+                100,
+                None,
+                storage_deposit,
+                30_000_000_000_000,
+            )
+            .unwrap();
+        let transaction = builder.build();
+
+        let calls: Vec<&FunctionCallAction> = transaction
+            .actions()
+            .iter()
+            .map(|action| match action {
+                Action::FunctionCall(call) => call.as_ref(),
+                other => panic!("Unexpected action {:?}", other),
+            })
+            .collect();
+        assert_eq!(calls.len(), 2);
+
+        assert_eq!(calls[0].method_name, "storage_deposit");
+        assert_eq!(calls[0].deposit, storage_deposit);
+        let args: Value = serde_json::from_slice(&calls[0].args).unwrap();
+        assert_eq!(args["account_id"], "bob.near");
+        assert_eq!(args["registration_only"], true);
+
+        assert_eq!(calls[1].method_name, "ft_transfer");
+        assert_eq!(calls[1].deposit, ONE_YOCTO);
+        let args: Value = serde_json::from_slice(&calls[1].args).unwrap();
+        assert_eq!(args["receiver_id"], "bob.near");
+        assert_eq!(args["amount"], "100");
+    }
+
+    #[test]
+    fn test_add_single_method_key() {
+        let mut builder = builder();
+        builder.add_single_method_key(
+            PublicKey::empty(KeyType::ED25519),
+            "app.near".parse().unwrap(),
+            "set_status",
+            None,
+        );
+        let transaction = builder.build();
+
+        match &transaction.actions()[0] {
+            Action::AddKey(add_key) => match &add_key.access_key.permission {
+                AccessKeyPermission::FunctionCall(permission) => {
+                    assert_eq!(permission.receiver_id, "app.near");
+                    assert_eq!(permission.method_names, vec!["set_status".to_string()]);
+                }
+                permission => panic!("Unexpected permission {:?}", permission),
+            },
+            action => panic!("Unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_rotate_full_access_key() {
+        let new_key = SecretKey::from_seed(KeyType::ED25519, "new").public_key();
+        let old_key = SecretKey::from_seed(KeyType::ED25519, "old").public_key();
+        let mut builder = builder();
+        builder.rotate_full_access_key(new_key.clone(), old_key.clone());
+        let transaction = builder.build();
+
+        assert_eq!(transaction.actions().len(), 2);
+        match &transaction.actions()[0] {
+            Action::AddKey(add_key) => {
+                assert_eq!(add_key.public_key, new_key);
+                assert_eq!(add_key.access_key, AccessKey::full_access());
+            }
+            action => panic!("Unexpected action {:?}", action),
+        }
+        match &transaction.actions()[1] {
+            Action::DeleteKey(delete_key) => assert_eq!(delete_key.public_key, old_key),
+            action => panic!("Unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_deposit_and_function_call_predicates() {
+        let mut calls = builder();
+        calls
+            .function_call("get".to_string(), vec![], 1, 0)
+            .unwrap();
+        assert!(calls.is_deposit_free());
+        assert!(calls.has_only_function_calls());
+
+        calls
+            .function_call("ft_transfer".to_string(), vec![], 1, ONE_YOCTO)
+            .unwrap();
+        assert!(!calls.is_deposit_free());
+        assert!(calls.has_only_function_calls());
+
+        let mut mixed = builder();
+        mixed
+            .function_call("get".to_string(), vec![], 1, 0)
+            .unwrap()
+            .delete_key(PublicKey::empty(KeyType::ED25519));
+        assert!(mixed.is_deposit_free());
+        assert!(!mixed.has_only_function_calls());
+
+        mixed.transfer(1);
+        assert!(!mixed.is_deposit_free());
+        assert!(!mixed.has_only_function_calls());
+    }
+
+    #[test]
+    fn test_clone_with_new_receiver_and_nonce() {
+        let mut original = builder();
+        original.transfer(1);
+
+        let cloned = original
+            .clone_with_new_receiver("bob.near".parse().unwrap())
+            .clone_with_new_nonce(7)
+            .build();
+        let original = original.build();
+
+        assert_eq!(cloned.receiver_id().as_str(), "bob.near");
+        assert_eq!(cloned.nonce(), 7);
+        assert_eq!(cloned.signer_id(), original.signer_id());
+        assert_eq!(cloned.public_key(), original.public_key());
+        assert_eq!(cloned.block_hash(), original.block_hash());
+        assert_eq!(cloned.actions(), original.actions());
+    }
+
+    #[test]
+    fn test_retain_actions() {
+        let mut builder = builder();
+        builder
+            .function_call("get".to_string(), vec![], 1, 0)
+            .unwrap()
+            .transfer(5)
+            .function_call("set".to_string(), vec![], 1, 0)
+            .unwrap()
+            .retain_actions(|action| !matches!(action, Action::FunctionCall(_)));
+        let transaction = builder.build();
+
+        assert_eq!(
+            transaction.actions(),
+            &[Action::Transfer(TransferAction { deposit: 5 })]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_assert_action_count() {
+        let mut builder = builder();
+        builder.transfer(1).transfer(2);
+
+        assert!(builder.assert_action_count_eq(2).is_ok());
+        assert!(builder.assert_action_count_range(1, 3).is_ok());
+        assert_eq!(
+            builder.assert_action_count_eq(3).unwrap_err(),
+            WrongActionCount {
+                actual: 2,
+                min: 3,
+                max: 3
+            }
+        );
+        assert!(builder.assert_action_count_range(3, 5).is_err());
+    }
+
+    #[test]
+    fn test_function_call_validates_method_name() {
+        let mut builder = builder();
+        assert_eq!(
+            builder
+                .function_call(String::new(), vec![], 1, 0)
+                .unwrap_err(),
+            TransactionBuilderError::InvalidMethodName(String::new())
+        );
+        assert!(
+            builder
+                .function_call("set\n".to_string(), vec![], 1, 0)
+                .is_err()
+        );
+        assert!(
+            builder
+                .function_call("set_status".to_string(), vec![], 1, 0)
+                .is_ok()
+        );
+        assert_eq!(builder.build().actions().len(), 1);
+    }
+
+    #[test]
+    fn test_function_call_helpers_validate_method_name() {
+        let invalid = TransactionBuilderError::InvalidMethodName("set\n".to_string());
+        let path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ft_transfer_args.json");
+        let mut builder = builder();
+        assert_eq!(
+            builder
+                .function_call_with_gas_reserve("set\n".to_string(), vec![], 0, 300 * TGAS, 20)
+                .unwrap_err(),
+            invalid
+        );
+        assert_eq!(
+            builder
+                .function_call_from_file("set\n".to_string(), &path, 10, 0)
+                .unwrap_err(),
+            invalid
+        );
+        assert_eq!(
+            builder
+                .function_call_near_deposit("set\n".to_string(), vec![], 10, "0.1")
+                .unwrap_err(),
+            invalid
+        );
+        assert!(builder.build().actions().is_empty());
+
+        let mut valid = self::builder();
+        valid
+            .function_call_with_gas_reserve("set".to_string(), vec![], 0, 300 * TGAS, 20)
+            .unwrap()
+            .function_call_from_file("set".to_string(), &path, 10, 0)
+            .unwrap()
+            .function_call_near_deposit("set".to_string(), vec![], 10, "0.1")
+            .unwrap();
+        assert_eq!(valid.build().actions().len(), 3);
+    }
+
+    #[test]
+    fn test_function_call_rejects_excess_gas() {
+        let excess = TransactionBuilderError::GasExceedsMaximum {
+            gas: 301 * TGAS,
+            max: MAX_GAS,
+        };
+        let mut builder = builder();
+        assert_eq!(
+            builder
+                .function_call("set_status".to_string(), vec![], 301 * TGAS, 0)
+                .unwrap_err(),
+            excess
+        );
+        assert_eq!(
+            builder
+                .function_call_with_gas_reserve("set_status".to_string(), vec![], 0, 321 * TGAS, 20)
+                .unwrap_err(),
+            excess
+        );
+        assert_eq!(
+            builder
+                .function_call_near_deposit("set_status".to_string(), vec![], 301 * TGAS, "0.1")
+                .unwrap_err(),
+            excess
+        );
+        assert!(builder.build().actions().is_empty());
+        assert!(
+            builder
+                .function_call("set_status".to_string(), vec![], MAX_GAS, 0)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_add_function_call_keys() {
+        let key = |seed: &str| SecretKey::from_seed(KeyType::ED25519, seed).public_key();
+        let mut builder = builder();
+        builder
+            .add_function_call_keys(vec![
+                (key("a"), "a.near".parse().unwrap(), vec![], None),
+                (
+                    key("b"),
+                    "b.near".parse().unwrap(),
+                    vec!["set".to_string()],
+                    None,
+                ),
+                (key("c"), "c.near".parse().unwrap(), vec![], Some(1)),
+            ])
+            .unwrap();
+        let transaction = builder.build();
+
+        assert_eq!(transaction.actions().len(), 3);
+        for (action, (seed, receiver_id)) in
+            transaction
+                .actions()
+                .iter()
+                .zip([("a", "a.near"), ("b", "b.near"), ("c", "c.near")])
+        {
+            match action {
+                Action::AddKey(add_key) => {
+                    assert_eq!(add_key.public_key, key(seed));
+                    match &add_key.access_key.permission {
+                        AccessKeyPermission::FunctionCall(permission) => {
+                            assert_eq!(permission.receiver_id, receiver_id)
+                        }
+                        permission => panic!("Unexpected permission {:?}", permission),
+                    }
+                }
+                action => panic!("Unexpected action {:?}", action),
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_function_call_keys_checks_action_limit() {
+        let mut builder = builder();
+        let keys = (0..=MAX_ACTIONS_PER_RECEIPT)
+            .map(|_| {
+                (
+                    PublicKey::empty(KeyType::ED25519),
+                    "a.near".parse().unwrap(),
+                    vec![],
+                    None,
+                )
+            })
+            .collect();
+        assert!(builder.add_function_call_keys(keys).is_err());
+        assert!(builder.build().actions().is_empty());
+    }
+
+    #[test]
+    fn test_delete_keys() {
+        let keys: Vec<PublicKey> = ["a", "b", "c"]
+            .iter()
+            .map(|seed| SecretKey::from_seed(KeyType::ED25519, seed).public_key())
+            .collect();
+        let mut deleting = builder();
+        deleting.delete_keys(keys.clone()).unwrap();
+
+        let actions = deleting.transaction.actions();
+        assert_eq!(actions.len(), 3);
+        for (action, key) in actions.iter().zip(&keys) {
+            let Action::DeleteKey(delete) = action else {
+                panic!("Expected a delete key action");
+            };
+            assert_eq!(&delete.public_key, key);
+        }
+
+        let signing_key = PublicKey::empty(KeyType::ED25519);
+        let mut refusing = builder();
+        assert_eq!(
+            refusing
+                .delete_keys(vec![keys[0].clone(), signing_key.clone()])
+                .unwrap_err(),
+            TransactionBuilderError::DeletesSigningKey(signing_key)
+        );
+        assert!(refusing.transaction.actions().is_empty());
+    }
+
+    #[test]
+    fn test_actions_fingerprint() {
+        let mut original = builder();
+        original.transfer(1);
+
+        let renonced = original.clone_with_new_nonce(2);
+        assert_eq!(
+            renonced.actions_fingerprint(),
+            original.actions_fingerprint()
+        );
+
+        let mut changed = original.clone();
+        changed.transfer(2);
+        assert_ne!(
+            changed.actions_fingerprint(),
+            original.actions_fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_would_exceed_size_limit() {
+        let mut builder = builder();
+        builder.transfer(1);
+        let remaining = (MAX_TRANSACTION_SIZE - builder.size()) as usize;
+
+        assert!(!builder.would_exceed_size_limit(remaining));
+        assert!(builder.would_exceed_size_limit(remaining + 1));
+    }
+}
@@ -0,0 +1,209 @@
+//! Deep links exchanging transactions with mobile wallets.
+//!
+//! An app asks a wallet to sign a transaction by opening a `near://sign` link whose `transactions`
+//! parameter holds the borsh-serialized `Transaction`. Once signed, the wallet opens the app's callback
+//! URL with the borsh-serialized `SignedTransaction` in the `signedTransaction` parameter, or with
+//! `errorCode` if the user rejected the request. Both are encoded as URL-safe base64 without padding, so
+//! they need no further escaping.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use near_primitives::transaction::{SignedTransaction, Transaction};
+use std::fmt;
+use url::Url;
+
+/// The URL a sign request is sent to.
+const SIGN_URL: &str = "near://sign";
+
+/// Errors that can occur while parsing a wallet deep link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletConnectError {
+    /// The link is not a valid URL.
+    InvalidUrl(String),
+    /// A required query parameter is missing.
+    MissingParameter(&'static str),
+    /// A query parameter does not hold a valid encoded transaction.
+    InvalidTransaction(String),
+    /// The wallet did not sign the transaction, with the error code it reported.
+    Rejected(String),
+}
+
+impl fmt::Display for WalletConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl(reason) => write!(f, "invalid URL: {}", reason),
+            Self::MissingParameter(name) => write!(f, "missing query parameter {:?}", name),
+            Self::InvalidTransaction(reason) => write!(f, "invalid transaction: {}", reason),
+            Self::Rejected(code) => write!(f, "the wallet rejected the request: {}", code),
+        }
+    }
+}
+
+impl std::error::Error for WalletConnectError {}
+
+/// Builds and parses the deep links of the wallet signing flow.
+pub struct WalletConnect;
+
+impl WalletConnect {
+    /// Builds the link asking a wallet to sign `tx` on behalf of `app_name`, after which the wallet
+    /// opens `return_url` with the result.
+    pub fn build_sign_request_url(tx: &Transaction, return_url: &str, app_name: &str) -> String {
+        let mut url = Url::parse(SIGN_URL).expect("SIGN_URL is a valid URL");
+        url.query_pairs_mut()
+            .append_pair("transactions", &encode(tx))
+            .append_pair("callbackUrl", return_url)
+            .append_pair("appName", app_name);
+        url.into()
+    }
+
+    /// Decodes the transaction of a link built by `build_sign_request_url`, as a wallet does before
+    /// showing it to the user.
+    pub fn parse_sign_request_url(url: &str) -> Result<Transaction, WalletConnectError> {
+        let url = parse_url(url)?;
+        decode(&query_parameter(&url, "transactions")?)
+    }
+
+    /// Builds the link a wallet opens after signing, `return_url` with `signed` appended.
+    pub fn build_signed_response_url(
+        signed: &SignedTransaction,
+        return_url: &str,
+    ) -> Result<String, WalletConnectError> {
+        let mut url = parse_url(return_url)?;
+        url.query_pairs_mut()
+            .append_pair("signedTransaction", &encode(signed));
+        Ok(url.into())
+    }
+
+    /// Decodes the signed transaction of the link the wallet opened after a sign request.
+    ///
+    /// # Returns
+    ///
+    /// The signed transaction, or an error if the wallet rejected the request or the link is malformed.
+    /// The signature is not verified.
+    pub fn parse_signed_response(url: &str) -> Result<SignedTransaction, WalletConnectError> {
+        let url = parse_url(url)?;
+        if let Ok(code) = query_parameter(&url, "errorCode") {
+            return Err(WalletConnectError::Rejected(code));
+        }
+        decode(&query_parameter(&url, "signedTransaction")?)
+    }
+}
+
+fn encode<T: borsh::BorshSerialize>(value: &T) -> String {
+    URL_SAFE_NO_PAD.encode(borsh::to_vec(value).expect("Borsh serialization failed"))
+}
+
+fn decode<T: borsh::BorshDeserialize>(s: &str) -> Result<T, WalletConnectError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(s)
+        .map_err(|err| WalletConnectError::InvalidTransaction(err.to_string()))?;
+    borsh::from_slice(&bytes).map_err(|err| WalletConnectError::InvalidTransaction(err.to_string()))
+}
+
+fn parse_url(url: &str) -> Result<Url, WalletConnectError> {
+    Url::parse(url).map_err(|err| WalletConnectError::InvalidUrl(err.to_string()))
+}
+
+fn query_parameter(url: &Url, name: &'static str) -> Result<String, WalletConnectError> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+        .ok_or(WalletConnectError::MissingParameter(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransactionBuilder;
+    use near_crypto::{InMemorySigner, KeyType, PublicKey, SecretKey};
+    use near_primitives::account::AccessKey;
+    use near_primitives::hash::CryptoHash;
+
+    fn signer() -> InMemorySigner {
+        let secret_key = SecretKey::from_seed(KeyType::ED25519, "alice.near");
+        InMemorySigner {
+            account_id: "alice.near".parse().unwrap(),
+            public_key: secret_key.public_key(),
+            secret_key,
+        }
+    }
+
+    /// One builder per action type.
+    fn builders() -> Vec<TransactionBuilder> {
+        let key = PublicKey::empty(KeyType::ED25519);
+        let builder = TransactionBuilder::new(
+            "alice.near".parse().unwrap(),
+            key.clone(),
+            "app.alice.near".parse().unwrap(),
+            1,
+            CryptoHash::hash_bytes(b"block"),
+        );
+        let mut builders = vec![builder; 8];
+        builders[0].create_account();
+        builders[1].deploy_contract(b"\0asm");
+        builders[2]
+            .function_call("set".to_string(), br#"{"a":1}"#.to_vec(), 10, 1)
+            .unwrap();
+        builders[3].transfer(1);
+        builders[4].stake(1, key.clone());
+        builders[5].add_key(key.clone(), AccessKey::full_access());
+        builders[6].delete_key(key);
+        builders[7].delete_account("bob.near".parse().unwrap());
+        builders
+    }
+
+    #[test]
+    fn test_sign_request_round_trip() {
+        for builder in builders() {
+            let transaction = builder.build();
+            let url = WalletConnect::build_sign_request_url(
+                &transaction,
+                "https://app.example/callback?session=1",
+                "My App",
+            );
+            assert!(url.starts_with("near://sign?transactions="));
+            assert!(url.contains("appName=My+App"));
+            assert_eq!(
+                WalletConnect::parse_sign_request_url(&url).unwrap(),
+                transaction
+            );
+        }
+    }
+
+    #[test]
+    fn test_signed_response_round_trip() {
+        for builder in builders() {
+            let signed = builder.sign_transaction(&signer());
+            let url = WalletConnect::build_signed_response_url(
+                &signed,
+                "https://app.example/callback?session=1",
+            )
+            .unwrap();
+            assert_eq!(WalletConnect::parse_signed_response(&url).unwrap(), signed);
+        }
+    }
+
+    #[test]
+    fn test_parse_signed_response_errors() {
+        assert_eq!(
+            WalletConnect::parse_signed_response(
+                "https://app.example/callback?errorCode=userRejected"
+            ),
+            Err(WalletConnectError::Rejected("userRejected".to_string()))
+        );
+        assert_eq!(
+            WalletConnect::parse_signed_response("https://app.example/callback"),
+            Err(WalletConnectError::MissingParameter("signedTransaction"))
+        );
+        assert!(matches!(
+            WalletConnect::parse_signed_response(
+                "https://app.example/callback?signedTransaction=AAAA"
+            ),
+            Err(WalletConnectError::InvalidTransaction(_))
+        ));
+        assert!(matches!(
+            WalletConnect::parse_signed_response("not a url"),
+            Err(WalletConnectError::InvalidUrl(_))
+        ));
+    }
+}